@@ -0,0 +1,247 @@
+/* std use */
+use std::sync::atomic::Ordering;
+
+/* crate use */
+use biotest::Format as _;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/* project use */
+use pcon::counter;
+
+/// Global allocator that count number of allocation
+struct CountingAllocator;
+
+static ALLOCATIONS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        std::alloc::System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn generate_fasta(nb_record: u64) -> Vec<u8> {
+    let mut rng = biotest::rand();
+    let generator = biotest::Fasta::builder()
+        .sequence_len(150)
+        .build()
+        .expect("Can't build fasta generator");
+
+    let mut buffer = Vec::new();
+    generator
+        .records(&mut buffer, &mut rng, nb_record)
+        .expect("Can't generate fasta");
+
+    buffer
+}
+
+fn local_count(c: &mut Criterion) {
+    let fasta = generate_fasta(10_000);
+
+    let mut group = c.benchmark_group("local_count");
+    for local_count in [false, true] {
+        let options = counter::CountOptions {
+            local_count,
+            ..Default::default()
+        };
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(local_count),
+            &options,
+            |b, options| {
+                b.iter(|| {
+                    let mut counter = counter::Counter::<pcon::CountType>::new(11);
+                    counter
+                        .count_fasta_with(
+                            Box::new(std::io::Cursor::new(fasta.clone())),
+                            8192,
+                            options,
+                        )
+                        .unwrap();
+                    counter
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn pipeline(c: &mut Criterion) {
+    let fasta = generate_fasta(100_000);
+
+    let mut group = c.benchmark_group("pipeline");
+    for sync_read in [true, false] {
+        let options = counter::CountOptions {
+            sync_read,
+            ..Default::default()
+        };
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(if sync_read { "sync_read" } else { "pipeline" }),
+            &options,
+            |b, options| {
+                b.iter(|| {
+                    let mut counter = counter::Counter::<pcon::CountType>::new(11);
+                    counter
+                        .count_fasta_with(
+                            Box::new(std::io::Cursor::new(fasta.clone())),
+                            8192,
+                            options,
+                        )
+                        .unwrap();
+                    counter
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn atomic_ordering(c: &mut Criterion) {
+    let fasta = generate_fasta(100_000);
+
+    // small kmer size, all threads increment the same few counts
+    let mut group = c.benchmark_group("atomic_ordering");
+    for relaxed in [false, true] {
+        let options = counter::CountOptions {
+            relaxed,
+            ..Default::default()
+        };
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(if relaxed { "relaxed" } else { "seqcst" }),
+            &options,
+            |b, options| {
+                b.iter(|| {
+                    let mut counter = counter::Counter::<pcon::CountType>::new(5);
+                    counter
+                        .count_fasta_with(
+                            Box::new(std::io::Cursor::new(fasta.clone())),
+                            8192,
+                            options,
+                        )
+                        .unwrap();
+                    counter
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn prefetch(c: &mut Criterion) {
+    let fasta = generate_fasta(100_000);
+
+    // large kmer size, count array doesn't fit in cache
+    let mut group = c.benchmark_group("prefetch");
+    for prefetch in [false, true] {
+        let options = counter::CountOptions {
+            prefetch,
+            ..Default::default()
+        };
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(if prefetch { "prefetch" } else { "naive" }),
+            &options,
+            |b, options| {
+                b.iter(|| {
+                    let mut counter = counter::Counter::<u8>::new(15);
+                    counter
+                        .count_fasta_with(
+                            Box::new(std::io::Cursor::new(fasta.clone())),
+                            8192,
+                            options,
+                        )
+                        .unwrap();
+                    counter
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn minicount(c: &mut Criterion) {
+    let fasta = generate_fasta(10_000);
+
+    let mut group = c.benchmark_group("minicount");
+    for record_buffer in [1, 8192] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(record_buffer),
+            &record_buffer,
+            |b, record_buffer| {
+                b.iter(|| {
+                    let mut counter = pcon::minicounter::MiniCounter::<
+                        pcon::CountType,
+                        pcon::CountTypeNoAtomic,
+                    >::new(15, 9, 0, 0);
+                    counter
+                        .count_fasta(
+                            Box::new(std::io::Cursor::new(fasta.clone())),
+                            *record_buffer,
+                        )
+                        .unwrap();
+                    counter
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn allocation(c: &mut Criterion) {
+    let nb_record = 100_000;
+    let fasta = generate_fasta(nb_record);
+
+    let mut group = c.benchmark_group("allocation");
+    for record_buffer in [1024, 8192] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(record_buffer),
+            &record_buffer,
+            |b, record_buffer| {
+                b.iter_custom(|iters| {
+                    let before = ALLOCATIONS.load(Ordering::Relaxed);
+                    let start = std::time::Instant::now();
+                    for _ in 0..iters {
+                        let mut counter = counter::Counter::<pcon::CountType>::new(11);
+                        counter
+                            .count_fasta(
+                                Box::new(std::io::Cursor::new(fasta.clone())),
+                                *record_buffer,
+                            )
+                            .unwrap();
+                    }
+                    let elapsed = start.elapsed();
+
+                    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+                    eprintln!(
+                        "record_buffer {}: {:.3} allocation by record",
+                        record_buffer,
+                        allocations as f64 / (iters * nb_record) as f64
+                    );
+
+                    elapsed
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    local_count,
+    pipeline,
+    atomic_ordering,
+    prefetch,
+    minicount,
+    allocation
+);
+criterion_main!(benches);
@@ -0,0 +1,86 @@
+/* std use */
+
+/* crate use */
+use biotest::Format as _;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/* project use */
+use pcon::counter;
+
+fn generate_fasta(nb_record: u64) -> Vec<u8> {
+    let mut rng = biotest::rand();
+    let generator = biotest::Fasta::builder()
+        .sequence_len(150)
+        .build()
+        .expect("Can't build fasta generator");
+
+    let mut buffer = Vec::new();
+    generator
+        .records(&mut buffer, &mut rng, nb_record)
+        .expect("Can't generate fasta");
+
+    buffer
+}
+
+fn csv(c: &mut Criterion) {
+    let fasta = generate_fasta(10_000);
+
+    let mut sequential = counter::Counter::<u8>::new(11);
+    sequential
+        .count_fasta(Box::new(std::io::Cursor::new(fasta.clone())), 8192)
+        .unwrap();
+    let sequential = sequential.serialize();
+
+    let mut counter = counter::Counter::<pcon::CountType>::new(11);
+    counter
+        .count_fasta(Box::new(std::io::Cursor::new(fasta)), 8192)
+        .unwrap();
+    let counter = counter.serialize();
+
+    let mut group = c.benchmark_group("csv");
+    group.bench_function("sequential", |b| {
+        b.iter(|| sequential.csv(0, std::io::sink()).unwrap())
+    });
+    group.bench_function("count_type", |b| {
+        b.iter(|| counter.csv(0, std::io::sink()).unwrap())
+    });
+    group.finish();
+}
+
+fn load(c: &mut Criterion) {
+    let fasta = generate_fasta(10_000);
+
+    let mut counter = counter::Counter::<pcon::CountType>::new(13);
+    counter
+        .count_fasta(Box::new(std::io::Cursor::new(fasta)), 8192)
+        .unwrap();
+
+    let mut file = Vec::new();
+    counter
+        .serialize()
+        .sparse_density(0.0)
+        .pcon(&mut file)
+        .unwrap();
+
+    let mut group = c.benchmark_group("load");
+    group.bench_function("from_stream", |b| {
+        b.iter(|| counter::Counter::<pcon::CountType>::from_stream(&file[..]).unwrap())
+    });
+
+    // buffer is reuse between iteration, no full size array is allocate
+    let mut buffer = Some(counter::Counter::<pcon::CountType>::new(13).into_raw());
+    group.bench_function("from_stream_into", |b| {
+        b.iter(|| {
+            let loaded = counter::Counter::<pcon::CountType>::from_stream_into(
+                &file[..],
+                buffer.take().unwrap(),
+            )
+            .unwrap();
+            buffer = Some(loaded.into_raw());
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, csv, load);
+criterion_main!(benches);
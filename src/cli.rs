@@ -6,7 +6,10 @@ use std::io::Read as _;
 /* crate use */
 
 /* project use */
+use crate::counter;
 use crate::error;
+use crate::serialize;
+use crate::spectrum;
 
 /// Prompt COuNter, a short kmer counter.
 #[derive(clap::Parser, std::fmt::Debug)]
@@ -37,6 +40,14 @@ pub struct Command {
     /// Timestamp (sec, ms, ns, none)
     #[clap(short = 'T', long = "timestamp")]
     ts: Option<stderrlog::Timestamp>,
+
+    /// Write log as one JSON object (timestamp, level, message) by line
+    #[clap(long = "log-json")]
+    log_json: bool,
+
+    /// Seed of all randomized component (minimizer hash), default value 0 use unsalted minimizer
+    #[clap(long = "seed")]
+    seed: Option<u64>,
 }
 
 impl Command {
@@ -60,6 +71,31 @@ impl Command {
     pub fn timestamp(&self) -> stderrlog::Timestamp {
         self.ts.unwrap_or(stderrlog::Timestamp::Off)
     }
+
+    /// Get log_json
+    pub fn log_json(&self) -> bool {
+        self.log_json
+    }
+
+    /// Get seed
+    pub fn seed(&self) -> u64 {
+        self.seed.unwrap_or(0)
+    }
+
+    /// Get log level filter, match stderrlog verbosity
+    pub fn log_level(&self) -> log::LevelFilter {
+        if self.quiet {
+            return log::LevelFilter::Off;
+        }
+
+        match self.verbosity {
+            0 => log::LevelFilter::Error,
+            1 => log::LevelFilter::Warn,
+            2 => log::LevelFilter::Info,
+            3 => log::LevelFilter::Debug,
+            _ => log::LevelFilter::Trace,
+        }
+    }
 }
 
 /// Enumeration of subcommand
@@ -73,6 +109,24 @@ pub enum SubCommand {
 
     /// Convert pcon native output in other format
     Dump(Dump),
+
+    /// Count kmer for many kmer size and report spectrum information to help choose k
+    Sweep(Sweep),
+
+    /// Compute kmer spectrum of a pcon file
+    Spectrum(Spectrum),
+
+    /// Write presence matrix of solid kmer of many pcon files
+    Matrix(Matrix),
+
+    /// Compare count of two pcon files
+    Diff(Diff),
+
+    /// Write mean kmer count along each contig of a reference in bedgraph format
+    Coverage(Coverage),
+
+    /// Count kmer solid in both, in at least one of two pcon files and their Jaccard similarity
+    Shared(Shared),
 }
 
 /// Choose dump type
@@ -86,6 +140,77 @@ pub enum DumpType {
 
     /// Output in solid mode
     Solid,
+
+    #[cfg(feature = "kff")]
+    /// Output in kff mode
+    Kff,
+
+    /// Output flat uncompressed count array
+    Raw,
+
+    /// Output one fasta record by kmer with count in header
+    FastaCount,
+}
+
+/// Choose width of count
+#[derive(Copy, Clone, Eq, Debug, PartialEq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum CountWidth {
+    /// Count on 8 bits
+    U8,
+
+    /// Count on 16 bits
+    U16,
+
+    /// Count on 32 bits
+    U32,
+
+    /// Count on 64 bits
+    U64,
+}
+
+impl CountWidth {
+    /// Number of bytes use by one count
+    pub fn bytes(&self) -> usize {
+        match self {
+            CountWidth::U8 => 1,
+            CountWidth::U16 => 2,
+            CountWidth::U32 => 4,
+            CountWidth::U64 => 8,
+        }
+    }
+
+    /// Count width of `bytes` bytes, as store in pcon header
+    pub fn from_bytes(bytes: u8) -> error::Result<Self> {
+        match bytes {
+            1 => Ok(CountWidth::U8),
+            2 => Ok(CountWidth::U16),
+            4 => Ok(CountWidth::U32),
+            8 => Ok(CountWidth::U64),
+            width => Err(error::Error::CountWidthNotSupported(width).into()),
+        }
+    }
+
+    /// Next wider count width, None for the widest
+    pub fn wider(&self) -> Option<Self> {
+        match self {
+            CountWidth::U8 => Some(CountWidth::U16),
+            CountWidth::U16 => Some(CountWidth::U32),
+            CountWidth::U32 => Some(CountWidth::U64),
+            CountWidth::U64 => None,
+        }
+    }
+}
+
+impl std::default::Default for CountWidth {
+    /// Width of count type choose at compile time
+    fn default() -> Self {
+        match std::mem::size_of::<crate::CountTypeNoAtomic>() {
+            1 => CountWidth::U8,
+            2 => CountWidth::U16,
+            4 => CountWidth::U32,
+            _ => CountWidth::U64,
+        }
+    }
 }
 
 /// Choose input format
@@ -97,6 +222,115 @@ pub enum Format {
     #[cfg(feature = "fastq")]
     /// Input in format fastq
     Fastq,
+
+    #[cfg(feature = "gfa")]
+    /// Sequence of segments of a gfa
+    Gfa,
+
+    #[cfg(feature = "sam")]
+    /// Sequence of alignments of a sam, unmapped alignments are count
+    Sam,
+
+    /// Detect format from content of first input, fallback on file extension, default fasta
+    Auto,
+}
+
+impl Format {
+    /// Detect format from first bytes of an uncompressed input, None if content is ambiguous
+    pub fn from_content(content: &[u8]) -> Option<Self> {
+        let start = content.iter().position(|c| !c.is_ascii_whitespace())?;
+
+        match &content[start..] {
+            [b'>', ..] => Some(Format::Fasta),
+            #[cfg(feature = "sam")]
+            [b'@', b'H', b'D', b'\t', ..] | [b'@', b'S', b'Q', b'\t', ..] => Some(Format::Sam),
+            #[cfg(feature = "fastq")]
+            [b'@', ..] => Some(Format::Fastq),
+            #[cfg(feature = "gfa")]
+            [b'H' | b'S', b'\t', ..] => Some(Format::Gfa),
+            _ => None,
+        }
+    }
+
+    /// Detect format from extension of `path`, compression extension are ignored, None if extension is unknown
+    pub fn from_extension<P>(path: P) -> Option<Self>
+    where
+        P: std::convert::AsRef<std::path::Path>,
+    {
+        let path = path.as_ref();
+        let path = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz" | "bz2" | "xz" | "zst") => path.file_stem().map(std::path::Path::new)?,
+            _ => path,
+        };
+
+        match path.extension()?.to_str()? {
+            "fasta" | "fa" | "fna" => Some(Format::Fasta),
+            #[cfg(feature = "fastq")]
+            "fastq" | "fq" => Some(Format::Fastq),
+            #[cfg(feature = "gfa")]
+            "gfa" => Some(Format::Gfa),
+            #[cfg(feature = "sam")]
+            "sam" => Some(Format::Sam),
+            _ => None,
+        }
+    }
+
+    /// Replace [Format::Auto] by format detect from content of first path, then its extension, default fasta
+    pub fn resolve(self, paths: &[std::path::PathBuf]) -> Self {
+        if self != Format::Auto {
+            return self;
+        }
+
+        let path = match paths.first() {
+            Some(path) => path,
+            None => return Format::Fasta,
+        };
+
+        let mut content = [0u8; 64];
+        let from_content = std::fs::File::open(path)
+            .ok()
+            .and_then(|file| niffler::get_reader(Box::new(file)).ok())
+            .and_then(|(mut reader, _)| {
+                let len = reader.read(&mut content).ok()?;
+                Format::from_content(&content[..len])
+            });
+
+        from_content
+            .or_else(|| Format::from_extension(path))
+            .unwrap_or(Format::Fasta)
+    }
+}
+
+/// A solid output path, with an optional abundance specific to this output
+///
+/// Parse from `path` or `path:threshold`, if text after last `:` isn't a valid abundance, all text is the path
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolidOutput {
+    /// Path where solid is store
+    pub path: std::path::PathBuf,
+
+    /// Minimal abundance of this output, if None global abundance is used
+    pub abundance: Option<u64>,
+}
+
+impl std::str::FromStr for SolidOutput {
+    type Err = std::convert::Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some((path, threshold)) = value.rsplit_once(':') {
+            if let Ok(abundance) = threshold.parse::<u64>() {
+                return Ok(Self {
+                    path: path.into(),
+                    abundance: Some(abundance),
+                });
+            }
+        }
+
+        Ok(Self {
+            path: value.into(),
+            abundance: None,
+        })
+    }
 }
 
 /// SubCommand Count
@@ -110,6 +344,11 @@ pub struct Count {
     #[clap(short = 'i', long = "inputs")]
     inputs: Option<Vec<std::path::PathBuf>>,
 
+    #[cfg(feature = "fastq")]
+    /// Path to second mate of paired fastq inputs, count with inputs
+    #[clap(long = "inputs2")]
+    inputs2: Option<Vec<std::path::PathBuf>>,
+
     /// Format of input, default fasta
     #[clap(short = 'f', long = "formats")]
     format: Option<Format>,
@@ -122,17 +361,132 @@ pub struct Count {
     #[clap(short = 'c', long = "csv")]
     csv: Option<Vec<std::path::PathBuf>>,
 
-    /// Path where count are store
+    /// Path where count are store, `path:threshold` use threshold as abundance for this output
     #[clap(short = 's', long = "solid")]
-    solid: Option<Vec<std::path::PathBuf>>,
+    solid: Option<Vec<SolidOutput>>,
+
+    /// Path where count are store as a flat uncompressed little endian array, for mmap consumers
+    #[clap(long = "raw")]
+    raw: Option<Vec<std::path::PathBuf>>,
+
+    /// Path where kmer are store in fasta format, one record by kmer with count in header `>count_N`
+    #[clap(long = "fasta-count")]
+    fasta_count: Option<Vec<std::path::PathBuf>>,
 
     /// Minimal abundance, default value 0
     #[clap(short = 'a', long = "abundance")]
-    abundance: Option<crate::CountTypeNoAtomic>,
+    abundance: Option<u64>,
 
     /// Number of sequence record load in buffer, default 8192
     #[clap(short = 'b', long = "record_buffer")]
     record_buffer: Option<u64>,
+
+    /// Count only the first N bases of each record
+    #[clap(long = "prefix-len")]
+    prefix_len: Option<usize>,
+
+    /// Skip records shorter than N bases, record shorter than kmer size are always skip
+    #[clap(long = "min-record-len")]
+    min_record_len: Option<usize>,
+
+    /// Each thread count in its own buffer, reduce contention but use more memory (parallel only)
+    #[clap(long = "local-count")]
+    local_count: bool,
+
+    /// Path where a human readable summary of count is write
+    #[clap(long = "report")]
+    report: Option<std::path::PathBuf>,
+
+    /// Path to a fasta file of kmer, csv output contains only this kmer, require a csv output
+    #[clap(long = "whitelist", requires = "csv")]
+    whitelist: Option<std::path::PathBuf>,
+
+    /// Columns of csv output separate by comma, default kmer,count
+    #[clap(long = "columns", value_delimiter = ',')]
+    columns: Option<Vec<serialize::Column>>,
+
+    /// Keep kmer that contains base other than A, C, G or T
+    #[clap(long = "keep-ambiguous")]
+    keep_ambiguous: bool,
+
+    /// Check canonical kmer of each sequence and of its reverse complement are the same, abort if not, debug only
+    #[clap(long = "validate-kmers", hide = true)]
+    validate_kmers: bool,
+
+    /// Compute abundance from kmer spectrum with this method, replace abundance
+    #[clap(long = "auto-abundance", alias = "auto-threshold")]
+    auto_abundance: Option<spectrum::ThresholdMethod>,
+
+    /// Parameter of auto abundance method, default value 0.0
+    #[clap(long = "auto-abundance-params")]
+    auto_abundance_params: Option<f64>,
+
+    /// Minimal Shannon entropy of kmer nucleotides (between 0 and 2) to be write in csv or solid output, default value 0.0
+    #[clap(long = "min-complexity")]
+    min_complexity: Option<f64>,
+
+    /// Failed if no kmer are count, by default only a warning is emit
+    #[clap(long = "fail-on-empty")]
+    fail_on_empty: bool,
+
+    #[cfg(feature = "progress")]
+    /// Show a progress bar of input bytes read, only if stderr is a terminal
+    #[clap(long = "progress-bar")]
+    progress_bar: bool,
+
+    /// Path to a pcon file, count of inputs are add to count of this file
+    #[clap(long = "resume")]
+    resume: Option<std::path::PathBuf>,
+
+    /// Use relaxed memory ordering for atomic increment, faster with same result (parallel only)
+    #[clap(long = "relaxed-atomic")]
+    relaxed_atomic: bool,
+
+    /// Read and dispatch records one by one, memory is bound to a few records instead of record_buffer (parallel only)
+    #[clap(long = "streaming")]
+    streaming: bool,
+
+    /// Path to a bed file, only kmer that start in a region are count (fasta only)
+    #[clap(long = "region-bed")]
+    region_bed: Option<std::path::PathBuf>,
+
+    /// Path where distinct and total number of kmer of each group of records are write in csv, records are group by
+    /// name, first word of header, distinct kmer of each group are keep in memory (fasta only)
+    #[clap(long = "group-by-header", conflicts_with_all = ["region_bed", "every_nth"])]
+    group_by_header: Option<std::path::PathBuf>,
+
+    /// Print memory required and outputs destination without count
+    #[clap(long = "dry-run")]
+    dry_run: bool,
+
+    /// Order of csv output rows, by default rows follow kmer hash, sort store all rows in memory
+    #[clap(long = "sort")]
+    sort: Option<serialize::Sort>,
+
+    /// Add count to csv output if file exist, merged csv contains kmer,count columns in kmer order
+    #[clap(long = "append-csv", conflicts_with_all = ["columns", "sort", "whitelist"])]
+    append_csv: bool,
+
+    /// Count only one record every N records, first record is count
+    #[clap(long = "every-nth", conflicts_with = "region_bed")]
+    every_nth: Option<std::num::NonZeroUsize>,
+
+    /// Width of count, default value is width choose at compile time, abundance upper than max count is saturate
+    #[clap(long = "count-type")]
+    count_type: Option<CountWidth>,
+
+    /// If a count saturate, restart count with next wider count type, inputs are read again
+    #[clap(long = "auto-widen", requires = "inputs", conflicts_with = "resume")]
+    auto_widen: bool,
+
+    /// Create missing parent directories of outputs
+    #[clap(long = "mkdir")]
+    mkdir: bool,
+
+    #[cfg(feature = "checksum")]
+    /// Write sha256 of each output file in a `<output>.sha256` sidecar file
+    #[clap(long = "checksum")]
+    checksum: bool,
 }
 
 impl Count {
@@ -144,77 +498,170 @@ impl Count {
     /// Get inputs
     pub fn inputs(&self) -> error::Result<Box<dyn std::io::BufRead>> {
         match &self.inputs {
-            None => Ok(Box::new(std::io::stdin().lock())),
-            Some(paths) => {
-                let mut handle: Box<dyn std::io::Read> = Box::new(std::io::Cursor::new(vec![]));
-
-                for path in paths {
-                    let (file, _compression) =
-                        niffler::get_reader(Box::new(std::fs::File::open(path)?))?;
-                    handle = Box::new(handle.chain(file));
-                }
+            None => {
+                let input = std::io::stdin().lock();
+                #[cfg(feature = "progress")]
+                let input = self.progress(None).wrap_read(input);
 
-                Ok(Box::new(std::io::BufReader::new(handle)))
+                Ok(Box::new(input))
             }
+            Some(paths) => self.open(paths),
+        }
+    }
+
+    #[cfg(feature = "fastq")]
+    /// Return true if inputs of second mate are set
+    pub fn is_paired(&self) -> bool {
+        self.inputs2.is_some()
+    }
+
+    #[cfg(feature = "fastq")]
+    /// Get inputs of second mate, None if inputs isn't paired
+    pub fn inputs2(&self) -> Option<error::Result<Box<dyn std::io::BufRead>>> {
+        self.inputs2.as_ref().map(|paths| self.open(paths))
+    }
+
+    /// Open and chain `paths`, each file is decompress if needed
+    fn open(&self, paths: &[std::path::PathBuf]) -> error::Result<Box<dyn std::io::BufRead>> {
+        let mut handle: Box<dyn std::io::Read> = Box::new(std::io::Cursor::new(vec![]));
+
+        #[cfg(feature = "progress")]
+        let progress = self.progress(Some(
+            paths
+                .iter()
+                .filter_map(|path| std::fs::metadata(path).ok())
+                .map(|metadata| metadata.len())
+                .sum(),
+        ));
+
+        for path in paths {
+            let file = std::fs::File::open(path)?;
+            #[cfg(feature = "progress")]
+            let file = progress.clone().wrap_read(file);
+
+            let (file, _compression) =
+                niffler::get_reader(Box::new(file)).map_err(error::Error::from)?;
+            handle = Box::new(handle.chain(file));
         }
+
+        Ok(Box::new(std::io::BufReader::new(handle)))
     }
 
-    /// Get format inputs
+    #[cfg(feature = "progress")]
+    /// Get a progress bar on `len` input bytes, bar is hidden if it isn't request or stderr isn't a terminal
+    fn progress(&self, len: Option<u64>) -> indicatif::ProgressBar {
+        use std::io::IsTerminal as _;
+
+        if !self.progress_bar || !std::io::stderr().is_terminal() {
+            return indicatif::ProgressBar::hidden();
+        }
+
+        let progress = match len {
+            Some(len) => indicatif::ProgressBar::new(len),
+            None => indicatif::ProgressBar::new_spinner(),
+        };
+        progress.set_style(
+            indicatif::ProgressStyle::with_template(
+                "{spinner} [{elapsed_precise}] {bytes}/{total_bytes} ({bytes_per_sec})",
+            )
+            .unwrap_or_else(|_| indicatif::ProgressStyle::default_bar()),
+        );
+
+        progress
+    }
+
+    /// Get format inputs, [Format::Auto] is resolve on input files, stdin is read as fasta
     pub fn format(&self) -> Format {
-        self.format.unwrap_or(Format::Fasta)
+        self.format
+            .unwrap_or(Format::Fasta)
+            .resolve(self.inputs.as_deref().unwrap_or(&[]))
     }
 
-    /// Get output
+    /// Get output, with abundance specific to this output if any
     pub fn outputs(
         &self,
     ) -> Vec<(
         DumpType,
+        Option<u64>,
         error::Result<Box<dyn std::io::Write + std::marker::Send>>,
     )> {
-        let mut outputs: Vec<(
-            DumpType,
-            error::Result<Box<dyn std::io::Write + std::marker::Send>>,
-        )> = vec![];
+        self.destinations()
+            .into_iter()
+            .map(|(dump_type, abundance, path)| {
+                let output: error::Result<Box<dyn std::io::Write + std::marker::Send>> = match path
+                {
+                    Some(path) if self.mkdir => create_all(path),
+                    Some(path) => create(path),
+                    None => Ok(Box::new(std::io::BufWriter::new(std::io::stdout()))),
+                };
+
+                (dump_type, abundance, output)
+            })
+            .collect()
+    }
+
+    /// Get destination of each output without create it, None path is stdout
+    pub fn destinations(&self) -> Vec<(DumpType, Option<u64>, Option<&std::path::Path>)> {
+        let mut destinations: Vec<(DumpType, Option<u64>, Option<&std::path::Path>)> = vec![];
 
         match &self.csv {
             None => (),
             Some(paths) => {
                 for path in paths {
-                    outputs.push((DumpType::Csv, create(path)));
+                    destinations.push((DumpType::Csv, None, Some(path.as_path())));
                 }
             }
         }
 
         match &self.solid {
+            None => (),
+            Some(solids) => {
+                for solid in solids {
+                    destinations.push((
+                        DumpType::Solid,
+                        solid.abundance,
+                        Some(solid.path.as_path()),
+                    ));
+                }
+            }
+        }
+
+        match &self.raw {
             None => (),
             Some(paths) => {
                 for path in paths {
-                    outputs.push((DumpType::Solid, create(path)));
+                    destinations.push((DumpType::Raw, None, Some(path.as_path())));
+                }
+            }
+        }
+
+        match &self.fasta_count {
+            None => (),
+            Some(paths) => {
+                for path in paths {
+                    destinations.push((DumpType::FastaCount, None, Some(path.as_path())));
                 }
             }
         }
 
         match &self.pcon {
             None => {
-                if outputs.is_empty() {
-                    outputs.push((
-                        DumpType::Pcon,
-                        Ok(Box::new(std::io::BufWriter::new(std::io::stdout()))),
-                    ))
+                if destinations.is_empty() {
+                    destinations.push((DumpType::Pcon, None, None))
                 }
             }
             Some(paths) => {
                 for path in paths {
-                    outputs.push((DumpType::Pcon, create(path)));
+                    destinations.push((DumpType::Pcon, None, Some(path.as_path())));
                 }
             }
         }
 
-        outputs
+        destinations
     }
 
     /// Get abundance
-    pub fn abundance(&self) -> crate::CountTypeNoAtomic {
+    pub fn abundance(&self) -> u64 {
         self.abundance.unwrap_or(0)
     }
 
@@ -222,6 +669,184 @@ impl Count {
     pub fn record_buffer(&self) -> u64 {
         self.record_buffer.unwrap_or(8192)
     }
+
+    /// Get prefix_len
+    pub fn prefix_len(&self) -> Option<usize> {
+        self.prefix_len
+    }
+
+    /// Get min_record_len
+    pub fn min_record_len(&self) -> Option<usize> {
+        self.min_record_len
+    }
+
+    /// Get local_count
+    pub fn local_count(&self) -> bool {
+        self.local_count
+    }
+
+    /// Get relaxed_atomic
+    pub fn relaxed_atomic(&self) -> bool {
+        self.relaxed_atomic
+    }
+
+    /// Get streaming
+    pub fn streaming(&self) -> bool {
+        self.streaming
+    }
+
+    /// Get columns of csv output
+    pub fn columns(&self) -> Vec<serialize::Column> {
+        self.columns
+            .clone()
+            .unwrap_or_else(|| serialize::DEFAULT_COLUMNS.to_vec())
+    }
+
+    /// Get whitelist
+    pub fn whitelist(&self) -> Option<error::Result<Box<dyn std::io::BufRead>>> {
+        self.whitelist.as_ref().map(|path| {
+            let (file, _compression) = niffler::get_reader(Box::new(std::fs::File::open(path)?))
+                .map_err(error::Error::from)?;
+
+            Ok(Box::new(std::io::BufReader::new(file)) as Box<dyn std::io::BufRead>)
+        })
+    }
+
+    /// Return true if a region bed is set
+    pub fn has_region_bed(&self) -> bool {
+        self.region_bed.is_some()
+    }
+
+    /// Get region bed
+    pub fn region_bed(&self) -> Option<error::Result<Box<dyn std::io::BufRead>>> {
+        self.region_bed.as_ref().map(|path| {
+            let (file, _compression) = niffler::get_reader(Box::new(std::fs::File::open(path)?))
+                .map_err(error::Error::from)?;
+
+            Ok(Box::new(std::io::BufReader::new(file)) as Box<dyn std::io::BufRead>)
+        })
+    }
+
+    /// Get keep_ambiguous
+    pub fn keep_ambiguous(&self) -> bool {
+        self.keep_ambiguous
+    }
+
+    /// Get validate_kmers
+    pub fn validate_kmers(&self) -> bool {
+        self.validate_kmers
+    }
+
+    /// Get auto_abundance
+    pub fn auto_abundance(&self) -> Option<spectrum::ThresholdMethod> {
+        self.auto_abundance
+    }
+
+    /// Get auto_abundance_params
+    pub fn auto_abundance_params(&self) -> f64 {
+        self.auto_abundance_params.unwrap_or(0.0)
+    }
+
+    /// Get min_complexity
+    pub fn min_complexity(&self) -> f64 {
+        self.min_complexity.unwrap_or(0.0)
+    }
+
+    /// Get sort
+    pub fn sort(&self) -> Option<serialize::Sort> {
+        self.sort
+    }
+
+    /// Get fail_on_empty
+    pub fn fail_on_empty(&self) -> bool {
+        self.fail_on_empty
+    }
+
+    /// Get dry_run
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Get append_csv
+    pub fn append_csv(&self) -> bool {
+        self.append_csv
+    }
+
+    /// Get every_nth
+    pub fn every_nth(&self) -> Option<usize> {
+        self.every_nth.map(std::num::NonZeroUsize::get)
+    }
+
+    /// Get count_type
+    pub fn count_type(&self) -> CountWidth {
+        self.count_type.unwrap_or_default()
+    }
+
+    /// Get auto_widen
+    pub fn auto_widen(&self) -> bool {
+        self.auto_widen
+    }
+
+    /// Get mkdir
+    pub fn mkdir(&self) -> bool {
+        self.mkdir
+    }
+
+    #[cfg(feature = "checksum")]
+    /// Get checksum
+    pub fn checksum(&self) -> bool {
+        self.checksum
+    }
+
+    /// Get resume input
+    pub fn resume(&self) -> Option<error::Result<Box<dyn std::io::BufRead>>> {
+        self.resume.as_ref().map(|path| {
+            std::fs::File::open(path)
+                .map(|file| Box::new(std::io::BufReader::new(file)) as Box<dyn std::io::BufRead>)
+                .map_err(|e| e.into())
+        })
+    }
+
+    /// Get report output
+    pub fn report(&self) -> Option<error::Result<Box<dyn std::io::Write + std::marker::Send>>> {
+        self.report.as_ref().map(|path| create(path))
+    }
+
+    /// Get path of report output
+    pub fn report_path(&self) -> Option<&std::path::Path> {
+        self.report.as_deref()
+    }
+
+    /// Return true if records are count by group
+    pub fn has_group_by_header(&self) -> bool {
+        self.group_by_header.is_some()
+    }
+
+    /// Get group statistics output
+    pub fn group_by_header(
+        &self,
+    ) -> Option<error::Result<Box<dyn std::io::Write + std::marker::Send>>> {
+        self.group_by_header.as_ref().map(|path| create(path))
+    }
+
+    /// Get path of group statistics output
+    pub fn group_by_header_path(&self) -> Option<&std::path::Path> {
+        self.group_by_header.as_deref()
+    }
+
+    /// Get options use to split record in kmer
+    pub fn count_options(&self) -> counter::CountOptions {
+        counter::CountOptions {
+            prefix_len: self.prefix_len(),
+            min_record_len: self.min_record_len(),
+            local_count: self.local_count(),
+            keep_ambiguous: self.keep_ambiguous(),
+            relaxed: self.relaxed_atomic(),
+            streaming: self.streaming(),
+            validate_kmers: self.validate_kmers(),
+            ..Default::default()
+        }
+    }
 }
 
 /// SubCommand MiniCount
@@ -280,7 +905,8 @@ impl MiniCount {
 
                 for path in paths {
                     let (file, _compression) =
-                        niffler::get_reader(Box::new(std::fs::File::open(path)?))?;
+                        niffler::get_reader(Box::new(std::fs::File::open(path)?))
+                            .map_err(error::Error::from)?;
                     handle = Box::new(handle.chain(file));
                 }
 
@@ -289,9 +915,11 @@ impl MiniCount {
         }
     }
 
-    /// Get format inputs
+    /// Get format inputs, [Format::Auto] is resolve on input files, stdin is read as fasta
     pub fn format(&self) -> Format {
-        self.format.unwrap_or(Format::Fasta)
+        self.format
+            .unwrap_or(Format::Fasta)
+            .resolve(self.inputs.as_deref().unwrap_or(&[]))
     }
 
     /// Get output
@@ -340,9 +968,9 @@ impl MiniCount {
 /// SubCommand Dump
 #[derive(clap::Args, std::fmt::Debug)]
 pub struct Dump {
-    /// Path to inputs, default read stdin
+    /// Path to inputs, count of each inputs are sum, default read stdin
     #[clap(short = 'i', long = "inputs")]
-    input: Option<std::path::PathBuf>,
+    inputs: Option<Vec<std::path::PathBuf>>,
 
     /// Path where count are store, default write in stdout
     #[clap(short = 'c', long = "csv")]
@@ -356,21 +984,75 @@ pub struct Dump {
     #[clap(short = 's', long = "solid")]
     solid: Option<Vec<std::path::PathBuf>>,
 
+    #[cfg(feature = "kff")]
+    /// Path where count are store in kff format
+    #[clap(long = "kff")]
+    kff: Option<Vec<std::path::PathBuf>>,
+
+    /// Path where count are store as a flat uncompressed little endian array, for mmap consumers
+    #[clap(long = "raw")]
+    raw: Option<Vec<std::path::PathBuf>>,
+
+    /// Path where kmer are store in fasta format, one record by kmer with count in header `>count_N`
+    #[clap(long = "fasta-count")]
+    fasta_count: Option<Vec<std::path::PathBuf>>,
+
     /// Minimal abundance, default value 0
     #[clap(short = 'a', long = "abundance")]
-    abundance: crate::CountTypeNoAtomic,
+    abundance: u64,
+
+    /// Columns of csv output separate by comma, default kmer,count
+    #[clap(long = "columns", value_delimiter = ',')]
+    columns: Option<Vec<serialize::Column>>,
+
+    /// Minimal Shannon entropy of kmer nucleotides (between 0 and 2) to be write in csv or solid output, default value 0.0
+    #[clap(long = "min-complexity")]
+    min_complexity: Option<f64>,
+
+    /// Order of csv output rows, by default rows follow kmer hash, sort store all rows in memory
+    #[clap(long = "sort")]
+    sort: Option<serialize::Sort>,
+
+    /// Print k, count width, total, distinct and max count in stdout, no count are write
+    #[clap(long = "stats-only")]
+    stats_only: bool,
+
+    /// Print the N kmer with highest count upper than abundance in csv format in stdout sort by decreasing count, no count are write
+    #[clap(long = "top", conflicts_with = "stats_only")]
+    top: Option<usize>,
 }
 
 impl Dump {
-    /// Get inputs
+    /// Get first input, other inputs are ignored
+    #[deprecated(note = "dump accept many inputs, use Dump::inputs")]
     pub fn input(&self) -> error::Result<Box<dyn std::io::BufRead>> {
-        match &self.input {
-            None => Ok(Box::new(std::io::stdin().lock())),
-            Some(path) => {
-                let handle: Box<dyn std::io::Read> = Box::new(std::fs::File::open(path)?);
+        self.inputs()
+            .into_iter()
+            .next()
+            .map(|(_, input)| input)
+            .unwrap_or_else(|| Ok(Box::new(std::io::stdin().lock())))
+    }
 
-                Ok(Box::new(std::io::BufReader::new(handle)))
-            }
+    /// Get inputs with their name
+    pub fn inputs(&self) -> Vec<(String, error::Result<Box<dyn std::io::BufRead>>)> {
+        match &self.inputs {
+            None => vec![(
+                "stdin".to_string(),
+                Ok(Box::new(std::io::stdin().lock()) as Box<dyn std::io::BufRead>),
+            )],
+            Some(paths) => paths
+                .iter()
+                .map(|path| {
+                    (
+                        path.display().to_string(),
+                        std::fs::File::open(path)
+                            .map(|file| {
+                                Box::new(std::io::BufReader::new(file)) as Box<dyn std::io::BufRead>
+                            })
+                            .map_err(|e| e.into()),
+                    )
+                })
+                .collect(),
         }
     }
 
@@ -404,6 +1086,34 @@ impl Dump {
             }
         }
 
+        #[cfg(feature = "kff")]
+        match &self.kff {
+            None => (),
+            Some(paths) => {
+                for path in paths {
+                    outputs.push((DumpType::Kff, create(path)));
+                }
+            }
+        }
+
+        match &self.raw {
+            None => (),
+            Some(paths) => {
+                for path in paths {
+                    outputs.push((DumpType::Raw, create(path)));
+                }
+            }
+        }
+
+        match &self.fasta_count {
+            None => (),
+            Some(paths) => {
+                for path in paths {
+                    outputs.push((DumpType::FastaCount, create(path)));
+                }
+            }
+        }
+
         match &self.csv {
             None => {
                 if outputs.is_empty() {
@@ -424,15 +1134,322 @@ impl Dump {
     }
 
     /// Get abundance
-    pub fn abundance(&self) -> crate::CountTypeNoAtomic {
+    pub fn abundance(&self) -> u64 {
         self.abundance
     }
+
+    /// Get columns of csv output
+    pub fn columns(&self) -> Vec<serialize::Column> {
+        self.columns
+            .clone()
+            .unwrap_or_else(|| serialize::DEFAULT_COLUMNS.to_vec())
+    }
+
+    /// Get min_complexity
+    pub fn min_complexity(&self) -> f64 {
+        self.min_complexity.unwrap_or(0.0)
+    }
+
+    /// Get sort
+    pub fn sort(&self) -> Option<serialize::Sort> {
+        self.sort
+    }
+
+    /// Get stats_only
+    pub fn stats_only(&self) -> bool {
+        self.stats_only
+    }
+
+    /// Get top
+    pub fn top(&self) -> Option<usize> {
+        self.top
+    }
+}
+
+/// SubCommand Sweep
+#[derive(clap::Args, std::fmt::Debug)]
+pub struct Sweep {
+    /// Size of kmer, separate by comma
+    #[clap(
+        short = 'k',
+        long = "kmer-size",
+        value_delimiter = ',',
+        required = true,
+        value_parser = clap::value_parser!(u8).range(1..=counter::MAX_K as i64)
+    )]
+    kmer_sizes: Vec<u8>,
+
+    /// Path to inputs, inputs are read once by kmer size
+    #[clap(short = 'i', long = "inputs", required = true)]
+    inputs: Vec<std::path::PathBuf>,
+
+    /// Format of input, default fasta
+    #[clap(short = 'f', long = "formats")]
+    format: Option<Format>,
+
+    /// Path where table is store, default write in stdout
+    #[clap(short = 'o', long = "output")]
+    output: Option<std::path::PathBuf>,
+
+    /// Number of sequence record load in buffer, default 8192
+    #[clap(short = 'b', long = "record_buffer")]
+    record_buffer: Option<u64>,
+}
+
+impl Sweep {
+    /// Get size of kmer, even value are reduced to the nearest lower odd number
+    pub fn kmer_sizes(&self) -> Vec<u8> {
+        self.kmer_sizes
+            .iter()
+            .map(|k| k - (!(k & 0b1) & 0b1))
+            .collect()
+    }
+
+    /// Get inputs, each call reopen files
+    pub fn inputs(&self) -> error::Result<Box<dyn std::io::BufRead>> {
+        let mut handle: Box<dyn std::io::Read> = Box::new(std::io::Cursor::new(vec![]));
+
+        for path in &self.inputs {
+            let (file, _compression) = niffler::get_reader(Box::new(std::fs::File::open(path)?))
+                .map_err(error::Error::from)?;
+            handle = Box::new(handle.chain(file));
+        }
+
+        Ok(Box::new(std::io::BufReader::new(handle)))
+    }
+
+    /// Get format inputs, [Format::Auto] is resolve on input files
+    pub fn format(&self) -> Format {
+        self.format.unwrap_or(Format::Fasta).resolve(&self.inputs)
+    }
+
+    /// Get output
+    pub fn output(&self) -> error::Result<Box<dyn std::io::Write + std::marker::Send>> {
+        match &self.output {
+            None => Ok(Box::new(std::io::BufWriter::new(std::io::stdout()))),
+            Some(path) => create(path),
+        }
+    }
+
+    /// Get record_buffer
+    pub fn record_buffer(&self) -> u64 {
+        self.record_buffer.unwrap_or(8192)
+    }
+}
+
+/// SubCommand Spectrum
+#[derive(clap::Args, std::fmt::Debug)]
+pub struct Spectrum {
+    /// Path to pcon input
+    #[clap(short = 'i', long = "input")]
+    input: std::path::PathBuf,
+
+    /// Count upper than this value are store in last bucket, default 255
+    #[clap(short = 'm', long = "max-count")]
+    max_count: Option<usize>,
+
+    /// Print detected peaks and valleys of spectrum instead of full spectrum
+    #[clap(short = 'p', long = "peaks")]
+    peaks: bool,
+
+    /// Path where spectrum is store, default write in stdout
+    #[clap(short = 'o', long = "output")]
+    output: Option<std::path::PathBuf>,
+}
+
+impl Spectrum {
+    /// Get input path
+    pub fn input(&self) -> &std::path::Path {
+        &self.input
+    }
+
+    /// Get max_count
+    pub fn max_count(&self) -> usize {
+        self.max_count.unwrap_or(u8::MAX as usize)
+    }
+
+    /// Get peaks
+    pub fn peaks(&self) -> bool {
+        self.peaks
+    }
+
+    /// Get output
+    pub fn output(&self) -> error::Result<Box<dyn std::io::Write + std::marker::Send>> {
+        match &self.output {
+            None => Ok(Box::new(std::io::BufWriter::new(std::io::stdout()))),
+            Some(path) => create(path),
+        }
+    }
+}
+
+/// SubCommand Matrix
+#[derive(clap::Args, std::fmt::Debug)]
+pub struct Matrix {
+    /// Path to pcon inputs, one column by input
+    #[clap(short = 'i', long = "inputs", required = true, num_args = 1..)]
+    inputs: Vec<std::path::PathBuf>,
+
+    /// Minimal abundance, default value 0
+    #[clap(short = 'a', long = "abundance")]
+    abundance: Option<u64>,
+
+    /// Path where matrix is store, default write in stdout
+    #[clap(short = 'o', long = "output")]
+    output: Option<std::path::PathBuf>,
+}
+
+impl Matrix {
+    /// Get inputs path
+    pub fn inputs(&self) -> &[std::path::PathBuf] {
+        &self.inputs
+    }
+
+    /// Get abundance
+    pub fn abundance(&self) -> u64 {
+        self.abundance.unwrap_or(0)
+    }
+
+    /// Get output
+    pub fn output(&self) -> error::Result<Box<dyn std::io::Write + std::marker::Send>> {
+        match &self.output {
+            None => Ok(Box::new(std::io::BufWriter::new(std::io::stdout()))),
+            Some(path) => create(path),
+        }
+    }
+}
+
+/// SubCommand Diff
+#[derive(clap::Args, std::fmt::Debug)]
+pub struct Diff {
+    /// Path to the two pcon inputs to compare
+    #[clap(short = 'i', long = "inputs", required = true, num_args = 2)]
+    inputs: Vec<std::path::PathBuf>,
+
+    /// Write a summary of differences instead of count of each differing kmer
+    #[clap(short = 'r', long = "report")]
+    report: bool,
+
+    /// Path where differences are store, default write in stdout
+    #[clap(short = 'o', long = "output")]
+    output: Option<std::path::PathBuf>,
+}
+
+impl Diff {
+    /// Get inputs path
+    pub fn inputs(&self) -> &[std::path::PathBuf] {
+        &self.inputs
+    }
+
+    /// Get report
+    pub fn report(&self) -> bool {
+        self.report
+    }
+
+    /// Get output
+    pub fn output(&self) -> error::Result<Box<dyn std::io::Write + std::marker::Send>> {
+        match &self.output {
+            None => Ok(Box::new(std::io::BufWriter::new(std::io::stdout()))),
+            Some(path) => create(path),
+        }
+    }
+}
+
+/// SubCommand Coverage
+#[derive(clap::Args, std::fmt::Debug)]
+pub struct Coverage {
+    /// Path to reference in fasta format
+    #[clap(short = 'r', long = "reference")]
+    reference: std::path::PathBuf,
+
+    /// Path to pcon input
+    #[clap(short = 'i', long = "input")]
+    input: std::path::PathBuf,
+
+    /// Number of kmer start position by window, default value 1, one row by position
+    #[clap(short = 'w', long = "window")]
+    window: Option<std::num::NonZeroUsize>,
+
+    /// Path where bedgraph is store, default write in stdout
+    #[clap(short = 'o', long = "output")]
+    output: Option<std::path::PathBuf>,
+}
+
+impl Coverage {
+    /// Get reference
+    pub fn reference(&self) -> error::Result<Box<dyn std::io::BufRead>> {
+        let (file, _compression) =
+            niffler::get_reader(Box::new(std::fs::File::open(&self.reference)?))
+                .map_err(error::Error::from)?;
+
+        Ok(Box::new(std::io::BufReader::new(file)))
+    }
+
+    /// Get input path
+    pub fn input(&self) -> &std::path::Path {
+        &self.input
+    }
+
+    /// Get window
+    pub fn window(&self) -> usize {
+        self.window.map_or(1, std::num::NonZeroUsize::get)
+    }
+
+    /// Get output
+    pub fn output(&self) -> error::Result<Box<dyn std::io::Write + std::marker::Send>> {
+        match &self.output {
+            None => Ok(Box::new(std::io::BufWriter::new(std::io::stdout()))),
+            Some(path) => create(path),
+        }
+    }
+}
+
+/// SubCommand Shared
+#[derive(clap::Args, std::fmt::Debug)]
+pub struct Shared {
+    /// Path to the two pcon inputs to compare
+    #[clap(short = 'i', long = "inputs", required = true, num_args = 2)]
+    inputs: Vec<std::path::PathBuf>,
+
+    /// Minimal abundance, default value 0
+    #[clap(short = 'a', long = "abundance")]
+    abundance: Option<u64>,
+
+    /// Path where statistics are store, default write in stdout
+    #[clap(short = 'o', long = "output")]
+    output: Option<std::path::PathBuf>,
+}
+
+impl Shared {
+    /// Get inputs path
+    pub fn inputs(&self) -> &[std::path::PathBuf] {
+        &self.inputs
+    }
+
+    /// Get abundance
+    pub fn abundance(&self) -> u64 {
+        self.abundance.unwrap_or(0)
+    }
+
+    /// Get output
+    pub fn output(&self) -> error::Result<Box<dyn std::io::Write + std::marker::Send>> {
+        match &self.output {
+            None => Ok(Box::new(std::io::BufWriter::new(std::io::stdout()))),
+            Some(path) => create(path),
+        }
+    }
 }
 
 fn create<P>(path: P) -> error::Result<Box<dyn std::io::Write + std::marker::Send>>
 where
     P: std::convert::AsRef<std::path::Path>,
 {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() && !parent.is_dir() {
+            return Err(error::Error::OutputDirectoryNotExist(path.to_path_buf()).into());
+        }
+    }
+
     let file = std::fs::File::create(path)?;
     let buffer = std::io::BufWriter::new(file);
     let boxed = Box::new(buffer);
@@ -440,12 +1457,118 @@ where
     Ok(boxed)
 }
 
+/// Same as [create] but missing parent directories are create
+fn create_all<P>(path: P) -> error::Result<Box<dyn std::io::Write + std::marker::Send>>
+where
+    P: std::convert::AsRef<std::path::Path>,
+{
+    if let Some(parent) = path.as_ref().parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    create(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use std::io::Write as _;
 
+    #[test]
+    fn solid_output() {
+        assert_eq!(
+            "out.solid".parse::<SolidOutput>(),
+            Ok(SolidOutput {
+                path: "out.solid".into(),
+                abundance: None
+            })
+        );
+        assert_eq!(
+            "out.solid:5".parse::<SolidOutput>(),
+            Ok(SolidOutput {
+                path: "out.solid".into(),
+                abundance: Some(5)
+            })
+        );
+        assert_eq!(
+            "dir:name/out.solid".parse::<SolidOutput>(),
+            Ok(SolidOutput {
+                path: "dir:name/out.solid".into(),
+                abundance: None
+            })
+        );
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(Format::from_extension("reads.fa"), Some(Format::Fasta));
+        assert_eq!(Format::from_extension("reads.fasta"), Some(Format::Fasta));
+        assert_eq!(Format::from_extension("reads.fna"), Some(Format::Fasta));
+        assert_eq!(Format::from_extension("reads.fa.gz"), Some(Format::Fasta));
+        assert_eq!(
+            Format::from_extension("reads.fasta.gz"),
+            Some(Format::Fasta)
+        );
+
+        #[cfg(feature = "fastq")]
+        {
+            assert_eq!(Format::from_extension("reads.fq"), Some(Format::Fastq));
+            assert_eq!(Format::from_extension("reads.fastq"), Some(Format::Fastq));
+            assert_eq!(Format::from_extension("reads.fq.gz"), Some(Format::Fastq));
+            assert_eq!(
+                Format::from_extension("reads.fastq.gz"),
+                Some(Format::Fastq)
+            );
+        }
+
+        assert_eq!(Format::from_extension("reads.txt"), None);
+        assert_eq!(Format::from_extension("reads.gz"), None);
+        assert_eq!(Format::from_extension("reads"), None);
+    }
+
+    #[test]
+    fn format_from_content() {
+        assert_eq!(Format::from_content(b">1\nACGT\n"), Some(Format::Fasta));
+        assert_eq!(Format::from_content(b"\n>1\nACGT\n"), Some(Format::Fasta));
+        #[cfg(feature = "fastq")]
+        assert_eq!(
+            Format::from_content(b"@1\nACGT\n+\n!!!!\n"),
+            Some(Format::Fastq)
+        );
+        #[cfg(feature = "gfa")]
+        assert_eq!(Format::from_content(b"S\t1\tACGT\n"), Some(Format::Gfa));
+        #[cfg(feature = "sam")]
+        assert_eq!(Format::from_content(b"@HD\tVN:1.6\n"), Some(Format::Sam));
+        assert_eq!(Format::from_content(b""), None);
+        assert_eq!(Format::from_content(b"ACGT"), None);
+    }
+
+    #[test]
+    fn format_resolve() -> error::Result<()> {
+        let directory = tempfile::tempdir()?;
+
+        // content win on extension
+        let path = directory.path().join("reads.fq");
+        std::fs::write(&path, b">1\nACGT\n")?;
+        assert_eq!(Format::Auto.resolve(&[path.clone()]), Format::Fasta);
+
+        // empty file fallback on extension
+        let path = directory.path().join("empty.fa.gz");
+        std::fs::File::create(&path)?;
+        assert_eq!(Format::Auto.resolve(&[path]), Format::Fasta);
+
+        // unknow file fallback on fasta
+        let path = directory.path().join("missing.txt");
+        assert_eq!(Format::Auto.resolve(&[path]), Format::Fasta);
+        assert_eq!(Format::Auto.resolve(&[]), Format::Fasta);
+
+        // explicit format isn't change
+        assert_eq!(Format::Fasta.resolve(&[]), Format::Fasta);
+
+        Ok(())
+    }
+
     #[cfg(not(feature = "parallel"))]
     #[test]
     fn basic() {
@@ -458,18 +1581,55 @@ mod tests {
             kmer_size: 32,
             abundance: Some(0),
             record_buffer: None,
+            prefix_len: None,
+            local_count: false,
+            report: None,
+            auto_abundance: None,
+            auto_abundance_params: None,
+            keep_ambiguous: false,
+            whitelist: None,
+            columns: None,
+            min_complexity: None,
+            fail_on_empty: false,
+            #[cfg(feature = "progress")]
+            progress_bar: false,
+            resume: None,
+            relaxed_atomic: false,
+            region_bed: None,
+            dry_run: false,
+            sort: None,
+            append_csv: false,
+            every_nth: None,
+            #[cfg(feature = "fastq")]
+            inputs2: None,
+            count_type: None,
+            auto_widen: false,
+            mkdir: false,
+            streaming: false,
+            min_record_len: None,
+            raw: None,
+            #[cfg(feature = "checksum")]
+            checksum: false,
+            group_by_header: None,
+            validate_kmers: false,
+            fasta_count: None,
         };
 
         let cmd = Command {
             verbosity: 3,
             quiet: false,
             ts: None,
+            log_json: false,
+            seed: None,
             subcommand: SubCommand::Count(subcmd),
         };
 
         assert_eq!(cmd.verbosity(), 3);
         assert!(!cmd.quiet());
         assert!(matches!(cmd.timestamp(), stderrlog::Timestamp::Off));
+        assert!(!cmd.log_json());
+        assert_eq!(cmd.seed(), 0);
+        assert_eq!(cmd.log_level(), log::LevelFilter::Debug);
     }
 
     #[cfg(feature = "parallel")]
@@ -484,12 +1644,46 @@ mod tests {
             kmer_size: 32,
             abundance: None,
             record_buffer: None,
+            prefix_len: None,
+            local_count: false,
+            report: None,
+            auto_abundance: None,
+            auto_abundance_params: None,
+            keep_ambiguous: false,
+            whitelist: None,
+            columns: None,
+            min_complexity: None,
+            fail_on_empty: false,
+            #[cfg(feature = "progress")]
+            progress_bar: false,
+            resume: None,
+            relaxed_atomic: false,
+            region_bed: None,
+            dry_run: false,
+            sort: None,
+            append_csv: false,
+            every_nth: None,
+            #[cfg(feature = "fastq")]
+            inputs2: None,
+            count_type: None,
+            auto_widen: false,
+            mkdir: false,
+            streaming: false,
+            min_record_len: None,
+            raw: None,
+            #[cfg(feature = "checksum")]
+            checksum: false,
+            group_by_header: None,
+            validate_kmers: false,
+            fasta_count: None,
         };
 
         let cmd = Command {
             verbosity: 3,
             quiet: false,
             ts: None,
+            log_json: false,
+            seed: None,
             subcommand: SubCommand::Count(subcmd),
             threads: Some(8),
         };
@@ -517,10 +1711,45 @@ mod tests {
             format: None,
             pcon: None,
             csv: None,
-            solid: Some(vec![output.path().to_path_buf()]),
+            solid: Some(vec![SolidOutput {
+                path: output.path().to_path_buf(),
+                abundance: None,
+            }]),
             kmer_size: 32,
             abundance: Some(2),
             record_buffer: Some(512),
+            prefix_len: None,
+            local_count: false,
+            report: None,
+            auto_abundance: None,
+            auto_abundance_params: None,
+            keep_ambiguous: false,
+            whitelist: None,
+            columns: None,
+            min_complexity: None,
+            fail_on_empty: false,
+            #[cfg(feature = "progress")]
+            progress_bar: false,
+            resume: None,
+            relaxed_atomic: false,
+            region_bed: None,
+            dry_run: false,
+            sort: None,
+            append_csv: false,
+            every_nth: None,
+            #[cfg(feature = "fastq")]
+            inputs2: None,
+            count_type: None,
+            auto_widen: false,
+            mkdir: false,
+            streaming: false,
+            min_record_len: None,
+            raw: None,
+            #[cfg(feature = "checksum")]
+            checksum: false,
+            group_by_header: None,
+            validate_kmers: false,
+            fasta_count: None,
         };
 
         let mut content = Vec::new();
@@ -530,7 +1759,11 @@ mod tests {
         assert_eq!(count.kmer_size(), 31);
         assert_eq!(count.abundance(), 2);
         assert_eq!(count.outputs()[0].0, DumpType::Solid);
+        assert_eq!(count.outputs()[0].1, None);
         assert_eq!(count.record_buffer(), 512);
+        assert_eq!(count.prefix_len(), None);
+        assert_eq!(count.count_options().prefix_len, None);
+        assert!(!count.local_count());
 
         let count = Count {
             inputs: Some(vec![
@@ -544,6 +1777,38 @@ mod tests {
             kmer_size: 32,
             abundance: Some(2),
             record_buffer: Some(512),
+            prefix_len: None,
+            local_count: false,
+            report: None,
+            auto_abundance: None,
+            auto_abundance_params: None,
+            keep_ambiguous: false,
+            whitelist: None,
+            columns: None,
+            min_complexity: None,
+            fail_on_empty: false,
+            #[cfg(feature = "progress")]
+            progress_bar: false,
+            resume: None,
+            relaxed_atomic: false,
+            region_bed: None,
+            dry_run: false,
+            sort: None,
+            append_csv: false,
+            every_nth: None,
+            #[cfg(feature = "fastq")]
+            inputs2: None,
+            count_type: None,
+            auto_widen: false,
+            mkdir: false,
+            streaming: false,
+            min_record_len: None,
+            raw: None,
+            #[cfg(feature = "checksum")]
+            checksum: false,
+            group_by_header: None,
+            validate_kmers: false,
+            fasta_count: None,
         };
 
         assert_eq!(count.outputs()[0].0, DumpType::Pcon);
@@ -595,6 +1860,38 @@ mod tests {
             kmer_size: 32,
             abundance: Some(2),
             record_buffer: Some(512),
+            prefix_len: None,
+            local_count: false,
+            report: None,
+            auto_abundance: None,
+            auto_abundance_params: None,
+            keep_ambiguous: false,
+            whitelist: None,
+            columns: None,
+            min_complexity: None,
+            fail_on_empty: false,
+            #[cfg(feature = "progress")]
+            progress_bar: false,
+            resume: None,
+            relaxed_atomic: false,
+            region_bed: None,
+            dry_run: false,
+            sort: None,
+            append_csv: false,
+            every_nth: None,
+            #[cfg(feature = "fastq")]
+            inputs2: None,
+            count_type: None,
+            auto_widen: false,
+            mkdir: false,
+            streaming: false,
+            min_record_len: None,
+            raw: None,
+            #[cfg(feature = "checksum")]
+            checksum: false,
+            group_by_header: None,
+            validate_kmers: false,
+            fasta_count: None,
         };
 
         assert_eq!(count.outputs()[0].0, DumpType::Pcon);
@@ -610,15 +1907,28 @@ mod tests {
         let output = tempfile::NamedTempFile::new()?;
 
         let dump = Dump {
-            input: Some(input1.path().to_path_buf()),
+            inputs: Some(vec![input1.path().to_path_buf()]),
             pcon: None,
             csv: None,
             solid: Some(vec![output.path().to_path_buf()]),
+            #[cfg(feature = "kff")]
+            kff: None,
             abundance: 2,
+            columns: None,
+            min_complexity: None,
+            sort: None,
+            stats_only: false,
+            raw: None,
+            top: None,
+            fasta_count: None,
         };
 
+        let mut inputs = dump.inputs();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].0, input1.path().display().to_string());
+
         let mut content = Vec::new();
-        dump.input()?.read_to_end(&mut content)?;
+        inputs.remove(0).1?.read_to_end(&mut content)?;
         assert_eq!(content, b">test\nATCG\n");
 
         assert_eq!(dump.abundance(), 2);
@@ -626,4 +1936,47 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn sweep() -> error::Result<()> {
+        let mut input1 = tempfile::NamedTempFile::new()?;
+        input1.write_all(b">test\nATCG\n")?;
+
+        let sweep = Sweep {
+            kmer_sizes: vec![4, 5, 7],
+            inputs: vec![input1.path().to_path_buf()],
+            format: None,
+            output: None,
+            record_buffer: None,
+        };
+
+        for _ in 0..2 {
+            let mut content = Vec::new();
+            sweep.inputs()?.read_to_end(&mut content)?;
+            assert_eq!(content, b">test\nATCG\n");
+        }
+
+        assert_eq!(sweep.kmer_sizes(), vec![3, 5, 7]);
+        assert_eq!(sweep.format(), Format::Fasta);
+        assert_eq!(sweep.record_buffer(), 8192);
+
+        Ok(())
+    }
+
+    #[test]
+    fn count_width() {
+        assert_eq!(CountWidth::U8.bytes(), 1);
+        assert_eq!(CountWidth::U16.bytes(), 2);
+        assert_eq!(CountWidth::U32.bytes(), 4);
+        assert_eq!(CountWidth::U64.bytes(), 8);
+
+        assert_eq!(CountWidth::U8.wider(), Some(CountWidth::U16));
+        assert_eq!(CountWidth::U32.wider(), Some(CountWidth::U64));
+        assert_eq!(CountWidth::U64.wider(), None);
+
+        assert_eq!(
+            CountWidth::default().bytes(),
+            std::mem::size_of::<crate::CountTypeNoAtomic>()
+        );
+    }
 }
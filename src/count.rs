@@ -1,6 +1,7 @@
 //! Run count command
 
 /* std use */
+use std::io::BufRead as _;
 
 /* crate use */
 
@@ -9,41 +10,706 @@ use crate::cli;
 use crate::counter;
 use crate::error;
 
+#[cfg(feature = "signals")]
+/// Set by SIGINT or SIGTERM handler, count stop and partial count are write in outputs
+static STOP: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
 /// Run count
 pub fn count(params: cli::Count) -> error::Result<()> {
-    log::info!("Start init counter");
-    let mut counter = counter::Counter::<crate::CountType>::new(params.kmer_size());
-    log::info!("End init counter");
+    if params.dry_run() {
+        return dry_run(&params, std::io::stdout().lock());
+    }
+
+    #[cfg(feature = "signals")]
+    if let Err(error) = ctrlc::set_handler(|| {
+        // second signal kill process without wait end of current batch
+        if STOP.swap(true, std::sync::atomic::Ordering::Relaxed) {
+            std::process::exit(130);
+        }
+        log::warn!("Signal receive, stop count after current batch and write partial count");
+    }) {
+        log::warn!("Unable to install signal handler: {}", error);
+    }
+
+    #[cfg(feature = "fastq")]
+    if params.is_paired() && params.format() != cli::Format::Fastq {
+        return Err(error::Error::FormatNotSupported(
+            format!("{:?}", params.format()).to_lowercase(),
+            "--inputs2",
+        )
+        .into());
+    }
 
-    log::info!("Start count kmer");
-    match params.format() {
-        cli::Format::Fasta => counter.count_fasta(params.inputs()?, params.record_buffer()),
-        #[cfg(feature = "fastq")]
-        cli::Format::Fastq => counter.count_fastq(params.inputs()?, params.record_buffer()),
+    if params.has_region_bed() && params.format() != cli::Format::Fasta {
+        return Err(error::Error::FormatNotSupported(
+            format!("{:?}", params.format()).to_lowercase(),
+            "--region-bed",
+        )
+        .into());
     }
-    log::info!("End count kmer");
 
-    let serialize = counter.serialize();
+    if params.has_group_by_header()
+        && !matches!(params.format(), cli::Format::Fasta | cli::Format::Auto)
+    {
+        return Err(error::Error::FormatNotSupported(
+            format!("{:?}", params.format()).to_lowercase(),
+            "--group-by-header",
+        )
+        .into());
+    }
+
+    #[cfg(feature = "fastq")]
+    if params.is_paired() && params.every_nth().is_some() {
+        return Err(
+            error::Error::FormatNotSupported("paired fastq".to_string(), "--every-nth").into(),
+        );
+    }
+
+    let mut count_type = params.count_type();
+    loop {
+        let widen = match count_type.wider() {
+            Some(wider) if params.auto_widen() => Some(wider),
+            _ => None,
+        };
+
+        let done = match count_type {
+            cli::CountWidth::U8 => count_u8(&params, widen.is_some())?,
+            cli::CountWidth::U16 => count_u16(&params, widen.is_some())?,
+            cli::CountWidth::U32 => count_u32(&params, widen.is_some())?,
+            cli::CountWidth::U64 => count_u64(&params, widen.is_some())?,
+        };
+
+        match (done, widen) {
+            (false, Some(wider)) => {
+                log::warn!(
+                    "Count saturate with count type {:?}, restart count with {:?}",
+                    count_type,
+                    wider
+                );
+                count_type = wider;
+            }
+            _ => return Ok(()),
+        }
+    }
+}
+
+macro_rules! impl_count {
+    ($name:ident, $type:ty, $out_type:ty) => {
+        /// Count, compute abundance and write outputs with a counter of `$type`, if `widen` and a count saturate
+        /// nothing is write and false is return
+        fn $name(params: &cli::Count, widen: bool) -> error::Result<bool> {
+            let mut counter = match params.resume() {
+                Some(input) => {
+                    log::info!("Start load previous count");
+                    let counter = counter::Counter::<$type>::from_stream(input?)?;
+                    if counter.k() != params.kmer_size() {
+                        return Err(error::Error::KmerSizeNotMatch {
+                            expected: params.kmer_size(),
+                            found: counter.k(),
+                        }
+                        .into());
+                    }
+                    log::info!("End load previous count");
+
+                    counter
+                }
+                None => {
+                    log::info!("Start init counter");
+                    let counter = counter::Counter::<$type>::new(params.kmer_size());
+                    log::info!("End init counter");
+
+                    counter
+                }
+            };
+
+            log::info!("Start count kmer");
+            #[cfg(feature = "signals")]
+            let options = counter::CountOptions {
+                stop: Some(&STOP),
+                ..params.count_options()
+            };
+            #[cfg(not(feature = "signals"))]
+            let options = params.count_options();
+            match (params.format(), params.every_nth()) {
+                (cli::Format::Fasta | cli::Format::Auto, Some(nth)) => {
+                    let mut reader = noodles::fasta::Reader::new(params.inputs()?);
+                    let records = reader.records().map(|record| -> error::Result<Vec<u8>> {
+                        Ok(record?.sequence().as_ref().to_vec())
+                    });
+                    count_every_nth(records, nth, |sequences| {
+                        counter.count_sequences_with(sequences, &options)
+                    })?
+                }
+                (cli::Format::Fasta | cli::Format::Auto, None) if params.has_group_by_header() => {
+                    // each record is count alone, reader thread of pipeline isn't useful
+                    let group_options = counter::CountOptions {
+                        sync_read: true,
+                        ..options.clone()
+                    };
+                    let k = counter.k();
+                    let rows = count_groups(params.inputs()?, k, &group_options, |sequence| {
+                        counter.count_sequences_with(std::iter::once(sequence), &group_options)
+                    })?;
+
+                    if let Some(output) = params.group_by_header() {
+                        log::info!("Start write group statistics");
+                        write_checksum(params, output?, params.group_by_header_path(), |output| {
+                            write_groups(rows, output)
+                        })?;
+                        log::info!("End write group statistics");
+                    }
+                }
+                (cli::Format::Fasta | cli::Format::Auto, None) => match params.region_bed() {
+                    Some(input) => {
+                        let regions = load_bed(input?)?;
+                        // minimal length apply to records not to their regions
+                        let region_options = counter::CountOptions {
+                            min_record_len: None,
+                            ..options.clone()
+                        };
+                        count_regions(
+                            counter.k(),
+                            params.inputs()?,
+                            &regions,
+                            options.min_record_len,
+                            |sequences| counter.count_sequences_with(sequences, &region_options),
+                        )?
+                    }
+                    None => counter.count_fasta_with(
+                        params.inputs()?,
+                        params.record_buffer(),
+                        &options,
+                    )?,
+                },
+                #[cfg(feature = "fastq")]
+                (cli::Format::Fastq, Some(nth)) => {
+                    let mut reader = noodles::fastq::Reader::new(params.inputs()?);
+                    let records = reader.records().map(|record| -> error::Result<Vec<u8>> {
+                        Ok(record?.sequence().as_ref().to_vec())
+                    });
+                    count_every_nth(records, nth, |sequences| {
+                        counter.count_sequences_with(sequences, &options)
+                    })?
+                }
+                #[cfg(feature = "fastq")]
+                (cli::Format::Fastq, None) => match params.inputs2() {
+                    Some(inputs2) => counter.count_fastq_pairs_with(
+                        params.inputs()?,
+                        inputs2?,
+                        params.record_buffer(),
+                        &options,
+                    )?,
+                    None => counter.count_fastq_with(
+                        params.inputs()?,
+                        params.record_buffer(),
+                        &options,
+                    )?,
+                },
+                #[cfg(feature = "gfa")]
+                (cli::Format::Gfa, Some(nth)) => count_every_nth(
+                    crate::utils::gfa_segments(params.inputs()?),
+                    nth,
+                    |sequences| counter.count_sequences_with(sequences, &options),
+                )?,
+                #[cfg(feature = "gfa")]
+                (cli::Format::Gfa, None) => {
+                    counter.count_gfa_with(params.inputs()?, params.record_buffer(), &options)?
+                }
+                #[cfg(feature = "sam")]
+                (cli::Format::Sam, Some(nth)) => count_every_nth(
+                    crate::utils::sam_sequences(params.inputs()?),
+                    nth,
+                    |sequences| counter.count_sequences_with(sequences, &options),
+                )?,
+                #[cfg(feature = "sam")]
+                (cli::Format::Sam, None) => {
+                    counter.count_sam_with(params.inputs()?, params.record_buffer(), &options)?
+                }
+            }
+            log::info!("End count kmer");
+
+            let stopped = options.stopped();
+            if stopped {
+                log::warn!(
+                    "Count interrupt, outputs contains only kmer of records read before signal"
+                );
+            }
+
+            if counter
+                .raw_noatomic()
+                .iter()
+                .any(|count| *count == <$out_type>::MAX)
+            {
+                if widen && !stopped {
+                    return Ok(false);
+                }
+
+                log::warn!(
+                    "Some count reach maximum value {}, use a wider --count-type or --auto-widen",
+                    <$out_type>::MAX
+                );
+            }
+
+            if counter.total_kmers() == 0 {
+                if params.fail_on_empty() {
+                    return Err(error::Error::EmptyInput.into());
+                }
 
-    for (out_type, output) in params.outputs().into_iter() {
-        match out_type {
-            cli::DumpType::Pcon => {
-                log::info!("Start write count in pcon format");
-                serialize.pcon(output?)?;
-                log::info!("End write count in pcon format");
+                log::warn!(
+                    "No kmer count in input, input is empty or all records are shorter than k"
+                );
             }
-            cli::DumpType::Csv => {
-                log::info!("Start write count in csv format");
-                serialize.csv(params.abundance(), output?)?;
-                log::info!("End write count in csv format");
+
+            let abundance = <$out_type>::try_from(params.abundance()).unwrap_or(<$out_type>::MAX);
+            let abundance = match params.auto_abundance() {
+                Some(method) => {
+                    log::info!("Start compute abundance");
+                    let abundance = match counter.auto_abundance(
+                        method,
+                        params.auto_abundance_params(),
+                        u8::MAX as usize,
+                    ) {
+                        Some(threshold) => threshold,
+                        None => {
+                            log::warn!("Auto abundance failed, use abundance {}", abundance);
+                            abundance
+                        }
+                    };
+                    log::info!("End compute abundance {}", abundance);
+
+                    abundance
+                }
+                None => abundance,
+            };
+
+            if let Some(output) = params.report() {
+                log::info!("Start write report");
+                write_checksum(params, output?, params.report_path(), |output| {
+                    counter.write_report(output, abundance)
+                })?;
+                log::info!("End write report");
             }
-            cli::DumpType::Solid => {
-                log::info!("Start write count in solid format");
-                serialize.solid(params.abundance(), output?)?;
-                log::info!("End write count in solid format");
+
+            let whitelist = match params.whitelist() {
+                Some(input) => {
+                    log::info!("Start load whitelist");
+                    let kmers = load_whitelist(input?, counter.k())?;
+                    log::info!("End load whitelist");
+
+                    Some(kmers)
+                }
+                None => None,
+            };
+
+            let serialize = counter
+                .serialize()
+                .min_complexity(params.min_complexity())
+                .sort(params.sort());
+
+            // previous csv must be read before outputs are create
+            let mut previous = if params.append_csv() {
+                params
+                    .destinations()
+                    .into_iter()
+                    .filter_map(|(out_type, _, path)| match out_type {
+                        cli::DumpType::Csv => path.map(load_csv),
+                        _ => None,
+                    })
+                    .collect::<error::Result<Vec<_>>>()?
+            } else {
+                Vec::new()
             }
+            .into_iter();
+
+            let mut write = |out_type: cli::DumpType,
+                             out_abundance: Option<u64>,
+                             output: &mut dyn std::io::Write|
+             -> error::Result<()> {
+                match out_type {
+                    cli::DumpType::Pcon => {
+                        log::info!("Start write count in pcon format");
+                        serialize.pcon(output)?;
+                        log::info!("End write count in pcon format");
+                    }
+                    cli::DumpType::Csv => match (previous.next(), &whitelist) {
+                        (Some(counts), whitelist) => {
+                            log::info!("Start append count in csv format");
+                            let mut rows = Vec::new();
+                            match whitelist {
+                                Some(kmers) => serialize.csv_whitelist(kmers, &mut rows)?,
+                                None => serialize.csv(abundance, &mut rows)?,
+                            }
+                            append_csv(counts, &rows, output)?;
+                            log::info!("End append count in csv format");
+                        }
+                        (None, Some(kmers)) => {
+                            log::info!("Start write whitelist count in csv format");
+                            serialize.csv_whitelist(kmers, output)?;
+                            log::info!("End write whitelist count in csv format");
+                        }
+                        (None, None) => {
+                            log::info!("Start write count in csv format");
+                            serialize.csv_columns(abundance, &params.columns(), output)?;
+                            log::info!("End write count in csv format");
+                        }
+                    },
+                    cli::DumpType::Solid => {
+                        let abundance = out_abundance
+                            .map(|value| <$out_type>::try_from(value).unwrap_or(<$out_type>::MAX))
+                            .unwrap_or(abundance);
+                        log::info!("Start write count in solid format, abundance {}", abundance);
+                        serialize.solid(abundance, output)?;
+                        log::info!("End write count in solid format");
+                    }
+                    #[cfg(feature = "kff")]
+                    cli::DumpType::Kff => {
+                        log::info!("Start write count in kff format");
+                        serialize.kff(abundance, output)?;
+                        log::info!("End write count in kff format");
+                    }
+                    cli::DumpType::Raw => {
+                        log::info!("Start write count in raw format");
+                        serialize.raw(output)?;
+                        log::info!("End write count in raw format");
+                    }
+                    cli::DumpType::FastaCount => {
+                        log::info!("Start write count in fasta format");
+                        serialize.fasta_count(abundance, output)?;
+                        log::info!("End write count in fasta format");
+                    }
+                }
+
+                Ok(())
+            };
+
+            for ((out_type, out_abundance, output), (_, _, path)) in
+                params.outputs().into_iter().zip(params.destinations())
+            {
+                write_checksum(params, output?, path, |output| {
+                    write(out_type, out_abundance, output)
+                })?;
+            }
+
+            Ok(true)
+        }
+    };
+}
+
+#[cfg(feature = "parallel")]
+impl_count!(count_u8, std::sync::atomic::AtomicU8, u8);
+#[cfg(feature = "parallel")]
+impl_count!(count_u16, std::sync::atomic::AtomicU16, u16);
+#[cfg(feature = "parallel")]
+impl_count!(count_u32, std::sync::atomic::AtomicU32, u32);
+#[cfg(feature = "parallel")]
+impl_count!(count_u64, std::sync::atomic::AtomicU64, u64);
+
+#[cfg(not(feature = "parallel"))]
+impl_count!(count_u8, u8, u8);
+#[cfg(not(feature = "parallel"))]
+impl_count!(count_u16, u16, u16);
+#[cfg(not(feature = "parallel"))]
+impl_count!(count_u32, u32, u32);
+#[cfg(not(feature = "parallel"))]
+impl_count!(count_u64, u64, u64);
+
+/// Call `write` on `output`, with `--checksum` `output` is wrap in a [crate::utils::ChecksumWriter] and sha256
+/// sidecar of `path` is write after `write`
+fn write_checksum<F>(
+    params: &cli::Count,
+    mut output: Box<dyn std::io::Write + std::marker::Send>,
+    path: Option<&std::path::Path>,
+    write: F,
+) -> error::Result<()>
+where
+    F: FnOnce(&mut dyn std::io::Write) -> error::Result<()>,
+{
+    #[cfg(feature = "checksum")]
+    if let (true, Some(path)) = (params.checksum(), path) {
+        let mut output = crate::utils::ChecksumWriter::new(output, path);
+        write(&mut output)?;
+
+        return output.finish();
+    }
+
+    #[cfg(not(feature = "checksum"))]
+    let _ = (params, path);
+
+    write(&mut output)
+}
+
+/// Write memory required by counter and outputs destination, inputs and outputs aren't open
+fn dry_run<W>(params: &cli::Count, mut output: W) -> error::Result<()>
+where
+    W: std::io::Write,
+{
+    let k = params.kmer_size();
+    let memory = cocktail::kmer::get_hash_space_size(k) * params.count_type().bytes() as u64;
+
+    writeln!(output, "count {}-mer need {} bytes", k, memory)?;
+    for (dump_type, _abundance, path) in params.destinations() {
+        match path {
+            Some(path) => writeln!(
+                output,
+                "write {} in {}",
+                format!("{:?}", dump_type).to_lowercase(),
+                path.display()
+            )?,
+            None => writeln!(
+                output,
+                "write {} in stdout",
+                format!("{:?}", dump_type).to_lowercase()
+            )?,
         }
     }
 
     Ok(())
 }
+
+/// Read `kmer,count` rows of csv at `path`, a missing file is an empty count
+fn load_csv(path: &std::path::Path) -> error::Result<std::collections::BTreeMap<String, u64>> {
+    match std::fs::File::open(path) {
+        Ok(file) => parse_csv(
+            std::io::BufReader::new(file),
+            std::collections::BTreeMap::new(),
+        ),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            Ok(std::collections::BTreeMap::new())
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Add count of `kmer,count` rows of `input` to `counts`
+fn parse_csv<R>(
+    input: R,
+    mut counts: std::collections::BTreeMap<String, u64>,
+) -> error::Result<std::collections::BTreeMap<String, u64>>
+where
+    R: std::io::BufRead,
+{
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+
+        let (kmer, count) = line
+            .split_once(',')
+            .and_then(|(kmer, count)| Some((kmer, count.parse::<u64>().ok()?)))
+            .ok_or(error::Error::MalformedCsv(index + 1))?;
+
+        *counts.entry(kmer.to_string()).or_insert(0) += count;
+    }
+
+    Ok(counts)
+}
+
+/// Add `kmer,count` rows to `previous` count and write merged count in kmer order
+fn append_csv<W>(
+    previous: std::collections::BTreeMap<String, u64>,
+    rows: &[u8],
+    mut output: W,
+) -> error::Result<()>
+where
+    W: std::io::Write,
+{
+    for (kmer, count) in parse_csv(rows, previous)? {
+        writeln!(output, "{},{}", kmer, count)?;
+    }
+
+    Ok(())
+}
+
+/// Read each kmer of fasta records
+fn load_whitelist(input: Box<dyn std::io::BufRead>, k: u8) -> error::Result<Vec<u64>> {
+    let mut kmers = Vec::new();
+    let mut reader = noodles::fasta::Reader::new(input);
+
+    for record in reader.records() {
+        for kmer in record?.sequence().as_ref().windows(k as usize) {
+            kmers.push(cocktail::kmer::seq2bit(kmer));
+        }
+    }
+
+    Ok(kmers)
+}
+
+/// Read intervals of a bed file, group by contig name
+fn load_bed(
+    input: Box<dyn std::io::BufRead>,
+) -> error::Result<rustc_hash::FxHashMap<Vec<u8>, Vec<(usize, usize)>>> {
+    let mut regions: rustc_hash::FxHashMap<Vec<u8>, Vec<(usize, usize)>> =
+        rustc_hash::FxHashMap::default();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with("track")
+            || line.starts_with("browser")
+        {
+            continue;
+        }
+
+        let mut fields = line.split('\t');
+        let (contig, start, end) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(contig), Some(start), Some(end)) => (contig, start, end),
+            _ => return Err(error::Error::MalformedBed(index + 1).into()),
+        };
+
+        let start = start
+            .parse::<usize>()
+            .map_err(|_| error::Error::MalformedBed(index + 1))?;
+        let end = end
+            .parse::<usize>()
+            .map_err(|_| error::Error::MalformedBed(index + 1))?;
+
+        regions
+            .entry(contig.as_bytes().to_vec())
+            .or_default()
+            .push((start, end));
+    }
+
+    Ok(regions)
+}
+
+/// Pass to `count` slices of fasta records that start in `regions`, each region is extend by k - 1 bases to keep kmer that
+/// start at end of region. Records shorter than `min_record_len` are skip before slicing
+fn count_regions<F>(
+    k: u8,
+    input: Box<dyn std::io::BufRead>,
+    regions: &rustc_hash::FxHashMap<Vec<u8>, Vec<(usize, usize)>>,
+    min_record_len: Option<usize>,
+    count: F,
+) -> error::Result<()>
+where
+    F: FnOnce(&mut dyn Iterator<Item = Vec<u8>>),
+{
+    let context = k as usize - 1;
+    let mut reader = noodles::fasta::Reader::new(input);
+
+    let mut error = None;
+    let mut slices = reader
+        .records()
+        .map_while(|record| match record {
+            Ok(record) => Some(record),
+            Err(e) => {
+                error = Some(e);
+                None
+            }
+        })
+        .filter(|record| record.sequence().len() >= min_record_len.unwrap_or(0))
+        .flat_map(|record| {
+            let name: &[u8] = record.name().as_ref();
+            let sequence: &[u8] = record.sequence().as_ref();
+
+            regions
+                .get(name)
+                .into_iter()
+                .flatten()
+                .filter(|(start, end)| start < end && *start < sequence.len())
+                .map(|(start, end)| sequence[*start..(end + context).min(sequence.len())].to_vec())
+                .collect::<Vec<Vec<u8>>>()
+        });
+
+    count(&mut slices);
+
+    match error {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+/// Above this number of groups a warning about memory usage is log
+const GROUP_WARNING: usize = 64;
+
+/// Call `count` on each fasta record and tally its kmer in a sparse map of its group, return name, distinct and
+/// total number of kmer of each group. Group of a record is its name, first word of header, memory usage of a group
+/// is proportional to its number of distinct kmer not to hash space size. Stop at first record after
+/// [counter::CountOptions::stop] is set
+fn count_groups<F>(
+    input: Box<dyn std::io::BufRead>,
+    k: u8,
+    options: &counter::CountOptions,
+    mut count: F,
+) -> error::Result<Vec<(String, u64, u64)>>
+where
+    F: FnMut(Vec<u8>),
+{
+    let mut groups: rustc_hash::FxHashMap<String, rustc_hash::FxHashMap<u64, u64>> =
+        rustc_hash::FxHashMap::default();
+    let mut reader = noodles::fasta::Reader::new(input);
+
+    for record in reader.records() {
+        let record = record?;
+        let name = String::from_utf8_lossy(record.name().as_ref()).into_owned();
+        let sequence = record.sequence().as_ref().to_vec();
+
+        if !groups.contains_key(&name) && groups.len() == GROUP_WARNING {
+            log::warn!(
+                "More than {} groups, distinct kmer of each group are keep in memory",
+                GROUP_WARNING
+            );
+        }
+
+        let tally = groups.entry(name).or_default();
+        options.kmerize(&sequence, k, |canonical| {
+            *tally.entry(canonical >> 1).or_insert(0) += 1;
+        });
+        count(sequence);
+
+        if options.stopped() {
+            break;
+        }
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|(name, tally)| {
+            let total = tally.values().sum();
+            (name, tally.len() as u64, total)
+        })
+        .collect())
+}
+
+/// Write number of distinct and total kmer of each group in csv, rows are sort by group name
+fn write_groups<W>(mut rows: Vec<(String, u64, u64)>, mut output: W) -> error::Result<()>
+where
+    W: std::io::Write,
+{
+    rows.sort_unstable();
+
+    writeln!(output, "group,distinct,total")?;
+    for (name, distinct, total) in rows {
+        writeln!(output, "{},{},{}", name, distinct, total)?;
+    }
+
+    Ok(())
+}
+
+/// Pass to `count` sequence of one record every `nth` records, first record is keep, stop at first error
+fn count_every_nth<I, F>(records: I, nth: usize, count: F) -> error::Result<()>
+where
+    I: Iterator<Item = error::Result<Vec<u8>>>,
+    F: FnOnce(&mut dyn Iterator<Item = Vec<u8>>),
+{
+    let mut error = None;
+    let mut sequences = records
+        .enumerate()
+        .filter(|(index, record)| record.is_err() || index % nth == 0)
+        .map_while(|(_, record)| match record {
+            Ok(sequence) => Some(sequence),
+            Err(e) => {
+                error = Some(e);
+                None
+            }
+        });
+
+    count(&mut sequences);
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
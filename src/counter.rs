@@ -1,6 +1,7 @@
 //! Generic struct of counter and implementation for many type
 
 /* std use */
+use std::io::Write as _;
 
 /* crate use */
 
@@ -8,22 +9,516 @@
 use rayon::prelude::*;
 
 /* project use */
+use crate::cli;
 use crate::error;
 use crate::serialize;
+use crate::solid;
+use crate::spectrum;
 use crate::utils;
 
+/// Version of pcon format write by [serialize::Serialize::pcon]
+pub const FORMAT_VERSION: u8 = 1;
+
+/// If this bit of kmer size byte is set, pcon file store only `(index, count)` of non zero count
+pub const SPARSE_FLAG: u8 = 0b1000_0000;
+
+/// Largest kmer size supported, kmer are encode in a u64 with 2 bits by nucleotide
+pub const MAX_K: u8 = 32;
+
+/// Length of pcon header, kmer size, count width and format version
+pub(crate) const HEADER_LEN: u64 = 3;
+
+/// Last bytes of a pcon file with a block index
+const INDEX_MAGIC: &[u8; 8] = b"PCONIDX\x01";
+
+/// Number of count decode at once when a dense pcon file is read
+const STREAM_BLOCK: usize = 1 << 16;
+
+/// First byte of gzip magic number, legacy pcon file (version 0) have no version byte, compressed
+/// data start directly after kmer size and count width
+const GZIP_MAGIC: u8 = 0x1f;
+
+/// Check kmer size `k` is between 1 and [MAX_K]
+pub(crate) fn check_kmer_size(k: u8) -> error::Result<()> {
+    if k == 0 || k > MAX_K {
+        return Err(error::Error::KmerSizeOutOfRange { k }.into());
+    }
+
+    Ok(())
+}
+
+/// Read header of a pcon file, return kmer size, count width, if count are store in sparse
+/// format and a reader of compressed data
+///
+/// File without version byte are read as version 0, any other version than [FORMAT_VERSION] is rejected
+#[allow(clippy::type_complexity)]
+pub(crate) fn read_header<R>(
+    mut input: R,
+) -> error::Result<(u8, u8, bool, std::io::Chain<std::io::Cursor<Vec<u8>>, R>)>
+where
+    R: std::io::Read,
+{
+    let mut header = [0u8; 3];
+    input.read_exact(&mut header)?;
+
+    let remain = match header[2] {
+        GZIP_MAGIC => vec![GZIP_MAGIC],
+        FORMAT_VERSION => vec![],
+        got => return Err(error::Error::UnsupportedFormatVersion { got }.into()),
+    };
+
+    let k = header[0] & !SPARSE_FLAG;
+    check_kmer_size(k)?;
+
+    Ok((
+        k,
+        header[1],
+        header[0] & SPARSE_FLAG != 0,
+        std::io::Read::chain(std::io::Cursor::new(remain), input),
+    ))
+}
+
+/// Read count width in header of a pcon file, return width and a reader of whole file, header included
+pub(crate) fn read_width<R>(
+    mut input: R,
+) -> error::Result<(cli::CountWidth, std::io::Chain<std::io::Cursor<[u8; 3]>, R>)>
+where
+    R: std::io::Read,
+{
+    let mut header = [0u8; 3];
+    input.read_exact(&mut header)?;
+
+    let (_, width, _, _) = read_header(&header[..])?;
+
+    Ok((
+        cli::CountWidth::from_bytes(width)?,
+        std::io::Read::chain(std::io::Cursor::new(header), input),
+    ))
+}
+
+/// Write index of compressed block, `offsets` contains start of each block and end of last block
+///
+/// Index is write after last block: offsets, number of count by block and number of block as little endian u64,
+/// followed by [INDEX_MAGIC]
+pub(crate) fn write_index<W>(output: &mut W, offsets: &[u64], block_len: u64) -> error::Result<()>
+where
+    W: std::io::Write,
+{
+    for offset in offsets {
+        output.write_all(&offset.to_le_bytes())?;
+    }
+    output.write_all(&block_len.to_le_bytes())?;
+    output.write_all(&(offsets.len() as u64 - 1).to_le_bytes())?;
+    output.write_all(INDEX_MAGIC)?;
+
+    Ok(())
+}
+
+/// Read index of compressed block write by [write_index], return offsets and number of count by block
+fn read_index<R>(input: &mut R) -> error::Result<(Vec<u64>, u64)>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    let mut footer = [0u8; 24];
+    input.seek(std::io::SeekFrom::End(-(footer.len() as i64)))?;
+    input.read_exact(&mut footer)?;
+
+    if &footer[16..] != INDEX_MAGIC {
+        return Err(error::Error::NotIndexed.into());
+    }
+
+    let block_len = u64::from_le_bytes(footer[..8].try_into()?);
+    let nb_block = u64::from_le_bytes(footer[8..16].try_into()?);
+
+    let mut offsets = vec![0u8; (nb_block as usize + 1) * 8];
+    input.seek(std::io::SeekFrom::End(
+        -((footer.len() + offsets.len()) as i64),
+    ))?;
+    input.read_exact(&mut offsets)?;
+
+    Ok((
+        offsets
+            .chunks_exact(8)
+            .map(|offset| u64::from_le_bytes(offset.try_into().unwrap_or([0; 8])))
+            .collect(),
+        block_len,
+    ))
+}
+
+/// Handle on an indexed pcon file, count are read from disk, only block of last query kmer is keep in memory,
+/// see [Counter::open_indexed]
+pub struct IndexedCounter<T> {
+    k: u8,
+    input: std::fs::File,
+    offsets: Vec<u64>,
+    block_len: u64,
+    block: Option<(usize, Box<[T]>)>,
+}
+
+impl<T> IndexedCounter<T> {
+    /// Get value of k
+    pub fn k(&self) -> u8 {
+        self.k
+    }
+
+    /// Read and decompress block at `index`
+    fn read_block(&mut self, index: usize) -> error::Result<Vec<u8>> {
+        let (start, end) = match (self.offsets.get(index), self.offsets.get(index + 1)) {
+            (Some(start), Some(end)) => (*start, *end),
+            _ => return Err(std::io::Error::from(std::io::ErrorKind::InvalidData).into()),
+        };
+
+        let mut compressed = vec![0u8; (end - start) as usize];
+        std::io::Seek::seek(&mut self.input, std::io::SeekFrom::Start(start))?;
+        std::io::Read::read_exact(&mut self.input, &mut compressed)?;
+
+        let mut raw = Vec::new();
+        std::io::Read::read_to_end(&mut flate2::read::GzDecoder::new(&compressed[..]), &mut raw)?;
+
+        Ok(raw)
+    }
+}
+
+/// Fill `buffer` with next sparse entry, return false if `input` reach end before first byte of entry
+fn read_entry<R>(input: &mut R, buffer: &mut [u8]) -> error::Result<bool>
+where
+    R: std::io::Read,
+{
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match input.read(&mut buffer[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+            Ok(len) => filled += len,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(true)
+}
+
 /// A counter of kmer based on cocktail crate 2bit conversion, canonicalisation and hashing.
 /// Implement only for u8, std::sync::atomic::AtomicU8
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "bincode", derive(serde::Serialize, serde::Deserialize))]
 pub struct Counter<T> {
     k: u8,
     pub(crate) count: Box<[T]>,
 }
 
+/// Options that control how sequence of records are split in kmer
+#[derive(Clone, Debug, Default)]
+pub struct CountOptions {
+    /// Only the first `prefix_len` bases of each record are count
+    pub prefix_len: Option<usize>,
+
+    /// Records shorter than `min_record_len` are skip, check before `prefix_len`, record shorter than k are always skip
+    pub min_record_len: Option<usize>,
+
+    /// In parallel counter, each thread count in a local dense u32 buffer, buffers are sum at end of count.
+    /// Reduce atomic contention but use one u32 array of hash space size by thread
+    pub local_count: bool,
+
+    /// In parallel counter, read a batch of records and count it before read the next one, by default
+    /// a reader fill next batch while previous batch are count. Without `sync_read` up to [PIPELINE_DEPTH] + 2
+    /// batches of `record_buffer` records are in memory: batches wait in queue, one is count and one is fill
+    pub sync_read: bool,
+
+    /// By default any base other than A, C, G or T (in upper or lower case) break kmer, only kmer of exactly k
+    /// valid bases are count even if sequence end with ambiguous bases. If true ambiguous bases are keep and encode
+    /// like cocktail do
+    pub keep_ambiguous: bool,
+
+    /// In parallel counter, atomic increment use `Relaxed` memory ordering instead of `SeqCst`, count of each
+    /// kmer are independent so final count are the same
+    pub relaxed: bool,
+
+    /// In sequential counter, index of [PREFETCH_WINDOW] next kmer are buffer and their count are prefetch before
+    /// increment, reduce cache miss on large kmer size, final count are the same
+    pub prefetch: bool,
+
+    /// In parallel counter, records are read one by one and dispatch to worker threads through a queue of
+    /// [STREAMING_DEPTH] records by thread, memory is bound to a few records whatever `record_buffer`
+    pub streaming: bool,
+
+    /// When flag is set, count stop after the current record or batch of records, counter contains count of
+    /// records read before stop. Useful to interrupt a long run without lose already count kmer
+    pub stop: Option<&'static std::sync::atomic::AtomicBool>,
+
+    /// Canonical kmer of each sequence are compare to canonical kmer of its reverse complement, count panic if they
+    /// differ. Guard against regression of tokenizer or canonicalisation, slow
+    pub validate_kmers: bool,
+}
+
+impl CountOptions {
+    /// True if [CountOptions::stop] flag is set
+    pub(crate) fn stopped(&self) -> bool {
+        self.stop
+            .map(|flag| flag.load(std::sync::atomic::Ordering::Relaxed))
+            .unwrap_or(false)
+    }
+
+    #[cfg(feature = "parallel")]
+    /// Memory ordering use by parallel counter to increment count
+    pub(crate) fn ordering(&self) -> std::sync::atomic::Ordering {
+        if self.relaxed {
+            std::sync::atomic::Ordering::Relaxed
+        } else {
+            std::sync::atomic::Ordering::SeqCst
+        }
+    }
+
+    /// Call `action` on each canonical kmer of `sequence`
+    ///
+    /// Without `keep_ambiguous` only kmer of exactly k A, C, G or T bases are produce, ambiguous bases at start, middle
+    /// or end of sequence never generate partial kmer
+    pub(crate) fn kmerize<F>(&self, sequence: &[u8], k: u8, mut action: F)
+    where
+        F: FnMut(u64),
+    {
+        if sequence.len() < self.min_record_len.unwrap_or(0) {
+            return;
+        }
+
+        let sequence = match self.prefix_len {
+            Some(len) => &sequence[..sequence.len().min(len)],
+            None => sequence,
+        };
+
+        if self.validate_kmers {
+            assert!(
+                Self::check_canonical(sequence, k),
+                "canonical kmer of a sequence and of its reverse complement differ"
+            );
+        }
+
+        if self.keep_ambiguous {
+            Self::tokenize(sequence, k, &mut action);
+        } else {
+            for run in sequence
+                .split(|nuc| !matches!(nuc, b'A' | b'C' | b'G' | b'T' | b'a' | b'c' | b'g' | b't'))
+            {
+                Self::tokenize(run, k, &mut action);
+            }
+        }
+    }
+
+    /// Return true if `sequence` and its reverse complement produce the same canonical kmer, run of ambiguous bases
+    /// break kmer
+    pub(crate) fn check_canonical(sequence: &[u8], k: u8) -> bool {
+        let canonicals = |sequence: &[u8]| {
+            let mut kmers = Vec::new();
+            for run in sequence
+                .split(|nuc| !matches!(nuc, b'A' | b'C' | b'G' | b'T' | b'a' | b'c' | b'g' | b't'))
+            {
+                Self::tokenize(run, k, &mut |canonical| kmers.push(canonical));
+            }
+            kmers.sort_unstable();
+
+            kmers
+        };
+
+        canonicals(sequence) == canonicals(&utils::revcomp(sequence))
+    }
+
+    fn tokenize<F>(sequence: &[u8], k: u8, action: &mut F)
+    where
+        F: FnMut(u64),
+    {
+        if sequence.len() >= k as usize {
+            for canonical in cocktail::tokenizer::Canonical::new(sequence, k) {
+                action(canonical);
+            }
+        }
+    }
+}
+
+/// Number of kmer index buffer before increment when [CountOptions::prefetch] is set
+pub const PREFETCH_WINDOW: usize = 16;
+
+/// Hint cpu to load cache line of `data[index]`, no-op on architecture other than x86_64
+#[inline(always)]
+fn prefetch<T>(data: &[T], index: usize) {
+    #[cfg(target_arch = "x86_64")]
+    #[allow(unused_unsafe)]
+    // SAFETY: prefetch is only a hint, it never fault even on invalid address, sse is always available on x86_64
+    unsafe {
+        std::arch::x86_64::_mm_prefetch(
+            data.as_ptr().wrapping_add(index) as *const i8,
+            std::arch::x86_64::_MM_HINT_T0,
+        );
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = (data, index);
+}
+
+#[cfg(feature = "npz")]
+/// Write a npy array of `len` little endian unsigned integer of `width` bytes, array data are write by `data`
+fn write_npy<W, F>(mut output: W, width: usize, len: usize, data: F) -> error::Result<()>
+where
+    W: std::io::Write,
+    F: FnOnce(&mut W) -> error::Result<()>,
+{
+    let descr = match width {
+        1 => "|u1",
+        2 => "<u2",
+        4 => "<u4",
+        8 => "<u8",
+        width => return Err(error::Error::CountWidthNotSupported(width as u8).into()),
+    };
+
+    // magic, version and header length take 10 bytes, header end with a newline and is pad to align data on 64 bytes
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': ({},), }}",
+        descr, len
+    );
+    let padding = 63 - (10 + header.len()) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    output.write_all(b"\x93NUMPY\x01\x00")?;
+    output.write_all(&(header.len() as u16).to_le_bytes())?;
+    output.write_all(header.as_bytes())?;
+
+    data(&mut output)
+}
+
+#[cfg(feature = "npz")]
+/// Write a npz file with array `counts` write by `data` and array `metadata` contains `k`, `width` and `total`
+fn write_npz<W, F>(
+    output: W,
+    k: u8,
+    width: usize,
+    len: usize,
+    total: u64,
+    data: F,
+) -> error::Result<()>
+where
+    W: std::io::Write + std::io::Seek,
+    F: FnOnce(&mut std::io::BufWriter<&mut zip::ZipWriter<W>>) -> error::Result<()>,
+{
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+    let mut archive = zip::ZipWriter::new(output);
+
+    archive
+        .start_file("counts.npy", options)
+        .map_err(error::Error::from)?;
+    let mut buffer = std::io::BufWriter::new(&mut archive);
+    write_npy(&mut buffer, width, len, |out| data(out))?;
+    buffer.flush()?;
+    drop(buffer);
+
+    archive
+        .start_file("metadata.npy", options)
+        .map_err(error::Error::from)?;
+    write_npy(&mut archive, 8, 3, |out| {
+        for value in [k as u64, width as u64, total] {
+            out.write_all(&value.to_le_bytes())?;
+        }
+
+        Ok(())
+    })?;
+
+    archive.finish().map_err(error::Error::from)?;
+
+    Ok(())
+}
+
+/// Summary of differences between count of two counter, see [Counter::diff_report]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DiffReport {
+    /// Number of kmer absent of first counter and present in second
+    pub gained: u64,
+
+    /// Number of kmer present in first counter and absent of second
+    pub lost: u64,
+
+    /// Number of kmer present in both counter with a different count
+    pub changed: u64,
+
+    /// Sum of absolute difference of count of each kmer
+    pub abs_diff: u128,
+}
+
+impl DiffReport {
+    /// Compare count of `first` and `second` index by index
+    fn from_counts<T>(first: &[T], second: &[T]) -> Self
+    where
+        T: std::marker::Copy + std::convert::Into<u128>,
+    {
+        let mut report = Self::default();
+
+        for (a, b) in first.iter().zip(second.iter()) {
+            let (a, b): (u128, u128) = ((*a).into(), (*b).into());
+            match (a, b) {
+                (0, 0) => (),
+                (0, _) => report.gained += 1,
+                (_, 0) => report.lost += 1,
+                (a, b) if a != b => report.changed += 1,
+                _ => (),
+            }
+            report.abs_diff += a.abs_diff(b);
+        }
+
+        report
+    }
+
+    /// Write a human readable summary of differences
+    pub fn write<W>(&self, mut output: W) -> error::Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(output, "kmers gained: {}", self.gained)?;
+        writeln!(output, "kmers lost: {}", self.lost)?;
+        writeln!(output, "kmers changed: {}", self.changed)?;
+        writeln!(output, "total absolute count difference: {}", self.abs_diff)?;
+
+        Ok(())
+    }
+}
+
+/// Number of record batch read in advance by parallel counter
+#[cfg(feature = "parallel")]
+pub const PIPELINE_DEPTH: usize = 2;
+
+/// Number of sequences by batch when parallel counter read sequences from an iterator
+#[cfg(feature = "parallel")]
+const SEQUENCES_BUFFER: usize = 8192;
+
+/// Number of records wait in queue for each worker thread in streaming mode
+#[cfg(feature = "parallel")]
+pub const STREAMING_DEPTH: usize = 2;
+
+#[cfg(all(feature = "parallel", feature = "fastq"))]
+fn fastq_sequence(record: &noodles::fastq::Record) -> &[u8] {
+    record.sequence().as_ref()
+}
+
 /**************************/
 /* generic implementation */
 /**************************/
 impl<T> Counter<T> {
+    /// Create a counter from precomputed `count`, `count` length must be hash space size of `k`, see
+    /// [Counter::into_raw]
+    pub fn from_raw(k: u8, count: Box<[T]>) -> error::Result<Self> {
+        check_kmer_size(k)?;
+
+        let expected = cocktail::kmer::get_hash_space_size(k) as usize;
+        if count.len() != expected {
+            return Err(error::Error::BufferSizeNotMatch {
+                expected,
+                found: count.len(),
+            }
+            .into());
+        }
+
+        Ok(Self { k, count })
+    }
+
     /// Get value of k
     pub fn k(&self) -> u8 {
         self.k
@@ -45,10 +540,25 @@ impl<T> Counter<T> {
         &mut self.count
     }
 
+    /// Consume counter and return raw data, can be reuse as buffer of `from_stream_into`
+    pub fn into_raw(self) -> Box<[T]> {
+        self.count
+    }
+
     /// Convert counter in serializer
     pub fn serialize(self) -> serialize::Serialize<T> {
         serialize::Serialize::new(self)
     }
+
+    #[cfg(feature = "bincode")]
+    /// Create a new kmer Counter by read a bincode file, see [serialize::Serialize::bincode]
+    pub fn from_bincode<R>(input: R) -> error::Result<Self>
+    where
+        R: std::io::Read,
+        T: serde::de::DeserializeOwned,
+    {
+        Ok(bincode::deserialize_from(input)?)
+    }
 }
 
 /*****************************/
@@ -66,22 +576,32 @@ macro_rules! impl_sequential (
 		}
 	    }
 
+	    /// Create a new kmer by read a compressed or not stream, compression is detect by niffler. Same as
+	    /// [Counter::from_stream] for plain stream, keep name of previous binding API
+	    pub fn deserialize<R>(input: R) -> error::Result<Self>
+	    where
+		R: std::io::Read,
+	    {
+		let (readable, _compression) = niffler::get_reader(Box::new(input)).map_err(error::Error::from)?;
+
+		Self::from_stream(readable)
+	    }
+
 	    /// Create a new kmer by read a file
-	    pub fn from_stream<R>(mut input: R) -> error::Result<Self>
+	    pub fn from_stream<R>(input: R) -> error::Result<Self>
 		where R: std::io::Read
 	    {
-		let mut read_buffer = [0u8; 2];
-		input.read_exact(&mut read_buffer)?;
-		let k = read_buffer[0];
+		let (k, width, sparse, input) = read_header(input)?;
 
-		if std::mem::size_of::<$type>() != read_buffer[1] as usize {
-		    return Err(error::Error::TypeNotMatch.into());
+		if std::mem::size_of::<$type>() != width as usize {
+		    return Err(error::Error::TypeNotMatch {
+			expected_bytes: std::mem::size_of::<$type>(),
+			found_bytes: width as usize,
+		    }.into());
 		}
 
-		let mut deflate = flate2::read::MultiGzDecoder::new(input);
 		let mut data = $init(k, 0 as $type);
-
-		$read(&mut deflate, &mut data)?;
+		Self::decode(input, sparse, &mut data)?;
 
 		Ok(Self {
 		    k,
@@ -89,39 +609,319 @@ macro_rules! impl_sequential (
 		})
 	    }
 
-	    /// Perform count on fasta input
-	    pub fn count_fasta(&mut self, fasta: Box<dyn std::io::BufRead>, _record_buffer: u64) {
+	    /// Create a new kmer by read a file, count are decode block by block directly in `buffer`, no other
+	    /// full size array is allocate. `buffer` length must match hash space size of file kmer size, its
+	    /// previous content is erase
+	    pub fn from_stream_into<R>(input: R, mut buffer: Box<[$type]>) -> error::Result<Self>
+		where R: std::io::Read
+	    {
+		let (k, width, sparse, input) = read_header(input)?;
+
+		if std::mem::size_of::<$type>() != width as usize {
+		    return Err(error::Error::TypeNotMatch {
+			expected_bytes: std::mem::size_of::<$type>(),
+			found_bytes: width as usize,
+		    }.into());
+		}
+
+		let expected = cocktail::kmer::get_hash_space_size(k) as usize;
+		if buffer.len() != expected {
+		    return Err(error::Error::BufferSizeNotMatch { expected, found: buffer.len() }.into());
+		}
+
+		if sparse {
+		    buffer.fill(0 as $type);
+		}
+		Self::decode(input, sparse, &mut buffer)?;
+
+		Ok(Self {
+		    k,
+		    count: buffer,
+		})
+	    }
+
+	    /// Decode compressed count of `input` in `data`, dense count are read by block of [STREAM_BLOCK]
+	    fn decode<R>(input: R, sparse: bool, data: &mut [$type]) -> error::Result<()>
+		where R: std::io::Read
+	    {
+		let mut deflate = flate2::read::MultiGzDecoder::new(input);
+
+		if sparse {
+		    let mut entry = [0u8; 8 + std::mem::size_of::<$type>()];
+		    while read_entry(&mut deflate, &mut entry)? {
+			let index = u64::from_le_bytes(entry[..8].try_into()?) as usize;
+			let count = data.get_mut(index).ok_or_else(|| std::io::Error::from(std::io::ErrorKind::InvalidData))?;
+			*count = <$type>::from_le_bytes(entry[8..].try_into()?);
+		    }
+		} else {
+		    for block in data.chunks_mut(STREAM_BLOCK) {
+			$read(&mut deflate, block)?;
+		    }
+		}
+
+		Ok(())
+	    }
+
+	    /// Open a pcon file write by [serialize::Serialize::pcon_indexed], count are read from disk when kmer is query
+	    pub fn open_indexed<P>(path: P) -> error::Result<IndexedCounter<$type>>
+		where P: std::convert::AsRef<std::path::Path>
+	    {
+		let mut input = std::fs::File::open(path)?;
+		let (k, width, sparse, _) = read_header(&mut input)?;
+
+		if sparse {
+		    return Err(error::Error::NotIndexed.into());
+		}
+
+		if std::mem::size_of::<$type>() != width as usize {
+		    return Err(error::Error::TypeNotMatch {
+			expected_bytes: std::mem::size_of::<$type>(),
+			found_bytes: width as usize,
+		    }.into());
+		}
+
+		let (offsets, block_len) = read_index(&mut input)?;
+
+		Ok(IndexedCounter {
+		    k,
+		    input,
+		    offsets,
+		    block_len,
+		    block: None,
+		})
+	    }
+
+	    /// Perform count on `reader` in `format`, input is decompress if needed
+	    pub fn count_reader<R>(&mut self, reader: R, format: cli::Format, record_buffer: u64) -> error::Result<()>
+	    where
+		R: std::io::Read + 'static,
+	    {
+		let (input, _compression) = niffler::get_reader(Box::new(reader)).map_err(error::Error::from)?;
+		let mut input: Box<dyn std::io::BufRead> = Box::new(std::io::BufReader::new(input));
+
+		let format = match format {
+		    cli::Format::Auto => input
+			.fill_buf()
+			.ok()
+			.and_then(cli::Format::from_content)
+			.unwrap_or(cli::Format::Fasta),
+		    format => format,
+		};
+
+		match format {
+		    cli::Format::Fasta | cli::Format::Auto => self.count_fasta(input, record_buffer),
+		    #[cfg(feature = "fastq")]
+		    cli::Format::Fastq => self.count_fastq(input, record_buffer),
+		    #[cfg(feature = "gfa")]
+		    cli::Format::Gfa => self.count_gfa(input, record_buffer),
+		    #[cfg(feature = "sam")]
+		    cli::Format::Sam => self.count_sam(input, record_buffer),
+		}
+	    }
+
+	    /// Perform count on fasta input, stop at first malformed record
+	    pub fn count_fasta(&mut self, fasta: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
+		self.count_fasta_with(fasta, record_buffer, &CountOptions::default())
+	    }
+
+	    /// Perform count on fasta input, sequence of records are preprocess according to `options`
+	    pub fn count_fasta_with(&mut self, fasta: Box<dyn std::io::BufRead>, _record_buffer: u64, options: &CountOptions) -> error::Result<()> {
 		let mut reader = noodles::fasta::Reader::new(fasta);
-		let mut records = reader.records();
+		for record in reader.records() {
+		    self.count_sequence(record?.sequence().as_ref(), options);
+		    if options.stopped() {
+			break;
+		    }
+		}
+
+		Ok(())
+	    }
 
-		while let Some(Ok(record)) = records.next() {
-		    if record.sequence().len() >= self.k() as usize {
-			let kmerizer =
-			    cocktail::tokenizer::Canonical::new(record.sequence().as_ref(), self.k());
+	    /// Perform count on sequences produce by any iterator, useful for input not read by noodles
+	    pub fn count_sequences<I>(&mut self, seqs: I)
+	    where
+		I: IntoIterator<Item = Vec<u8>>,
+	    {
+		self.count_sequences_with(seqs, &CountOptions::default())
+	    }
 
-			for canonical in kmerizer {
-			    Self::inc(&mut self.count, (canonical >> 1) as usize);
-			}
+	    /// Perform count on sequences produce by any iterator, sequences are preprocess according to `options`
+	    pub fn count_sequences_with<I>(&mut self, seqs: I, options: &CountOptions)
+	    where
+		I: IntoIterator<Item = Vec<u8>>,
+	    {
+		for sequence in seqs {
+		    self.count_sequence(&sequence, options);
+		    if options.stopped() {
+			break;
 		    }
 		}
 	    }
 
+	    #[cfg(feature = "gfa")]
+	    /// Perform count on sequence of gfa segments, stop at first io error
+	    pub fn count_gfa(&mut self, gfa: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
+		self.count_gfa_with(gfa, record_buffer, &CountOptions::default())
+	    }
+
+	    #[cfg(feature = "gfa")]
+	    /// Perform count on sequence of gfa segments, sequences are preprocess according to `options`
+	    pub fn count_gfa_with(&mut self, gfa: Box<dyn std::io::BufRead>, _record_buffer: u64, options: &CountOptions) -> error::Result<()> {
+		for segment in utils::gfa_segments(gfa) {
+		    self.count_sequence(&segment?, options);
+		    if options.stopped() {
+			break;
+		    }
+		}
+
+		Ok(())
+	    }
+
+	    #[cfg(feature = "sam")]
+	    /// Perform count on sequence of sam alignments, stop at first malformed alignment
+	    pub fn count_sam(&mut self, sam: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
+		self.count_sam_with(sam, record_buffer, &CountOptions::default())
+	    }
+
+	    #[cfg(feature = "sam")]
+	    /// Perform count on sequence of sam alignments, sequences are preprocess according to `options`
+	    pub fn count_sam_with(&mut self, sam: Box<dyn std::io::BufRead>, _record_buffer: u64, options: &CountOptions) -> error::Result<()> {
+		for sequence in utils::sam_sequences(sam) {
+		    self.count_sequence(&sequence?, options);
+		    if options.stopped() {
+			break;
+		    }
+		}
+
+		Ok(())
+	    }
+
 	    #[cfg(feature = "fastq")]
-	    /// Perform count on fastq input
-	    pub fn count_fastq(&mut self, fastq: Box<dyn std::io::BufRead>, _record_buffer: u64) {
+	    /// Perform count on both mates of paired fastq inputs, stop at first malformed record
+	    pub fn count_fastq_pairs(&mut self, r1: Box<dyn std::io::BufRead>, r2: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
+		self.count_fastq_pairs_with(r1, r2, record_buffer, &CountOptions::default())
+	    }
+
+	    #[cfg(feature = "fastq")]
+	    /// Perform count on both mates of paired fastq inputs, `r1` is count before `r2`, sequence of records are
+	    /// preprocess according to `options`
+	    pub fn count_fastq_pairs_with(&mut self, r1: Box<dyn std::io::BufRead>, r2: Box<dyn std::io::BufRead>, record_buffer: u64, options: &CountOptions) -> error::Result<()> {
+		self.count_fastq_with(r1, record_buffer, options)?;
+		self.count_fastq_with(r2, record_buffer, options)
+	    }
+
+	    #[cfg(feature = "fastq")]
+	    /// Perform count on fastq input, stop at first malformed record
+	    pub fn count_fastq(&mut self, fastq: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
+		self.count_fastq_with(fastq, record_buffer, &CountOptions::default())
+	    }
+
+	    #[cfg(feature = "fastq")]
+	    /// Perform count on fastq input, sequence of records are preprocess according to `options`
+	    pub fn count_fastq_with(&mut self, fastq: Box<dyn std::io::BufRead>, _record_buffer: u64, options: &CountOptions) -> error::Result<()> {
 		let mut reader = noodles::fastq::Reader::new(fastq);
-		let mut records = reader.records();
+		for record in reader.records() {
+		    self.count_sequence(record?.sequence().as_ref(), options);
+		    if options.stopped() {
+			break;
+		    }
+		}
+
+		Ok(())
+	    }
 
-		while let Some(Ok(record)) = records.next() {
-		    if record.sequence().len() >= self.k() as usize {
-			let kmerizer =
-			    cocktail::tokenizer::Canonical::new(record.sequence().as_ref(), self.k());
+	    /// Compare count with `other`, both side are saturate to max value of the narrower type, useful to compare
+	    /// counter of different width
+	    pub fn counts_equal_saturating(&self, other: &[u16]) -> bool {
+		let max = (<$type>::MAX as u128).min(u16::MAX as u128);
+
+		self.count.len() == other.len()
+		    && self
+			.count
+			.iter()
+			.zip(other.iter())
+			.all(|(a, b)| (*a as u128).min(max) == (*b as u128).min(max))
+	    }
 
-			for canonical in kmerizer {
-			    Self::inc(&mut self.count, (canonical >> 1) as usize);
-			}
+	    /// Add count of `other` to count of self
+	    pub fn merge(&mut self, other: &Self) -> error::Result<()> {
+		if self.k != other.k {
+		    return Err(error::Error::KmerSizeNotMatch { expected: self.k, found: other.k }.into());
+		}
+
+		for (count, value) in self.count.iter_mut().zip(other.count.iter()) {
+		    *count = count.saturating_add(*value);
+		}
+
+		Ok(())
+	    }
+
+	    /// Write counter in pcon format in `output`, count lower or equal than `abundance` are write as 0. Replace
+	    /// `serialize(output, abundance)` of previous binding API, name is take by [Counter::serialize]
+	    pub fn serialize_pcon<W>(mut self, output: W, abundance: $type) -> error::Result<()>
+	    where
+		W: std::io::Write,
+	    {
+		for count in self.count.iter_mut() {
+		    if *count <= abundance {
+			*count = 0;
 		    }
 		}
+
+		self.serialize().pcon(output)
+	    }
+
+	    /// Write counter in `path` in `format`, output is compressed according to path extension see
+	    /// [serialize::codec_from_path], solid output is always compressed. Pcon blocks are already gzip
+	    /// compressed and raw count must stay at fixed offset, these two formats ignore extension codec so
+	    /// `.pcon.gz` or `.pcon.zst` file are plain pcon readable by [Counter::from_stream] and `pcon dump -i`
+	    pub fn serialize_to_path<P>(self, path: P, abundance: $type, format: cli::DumpType) -> error::Result<()>
+	    where
+		P: std::convert::AsRef<std::path::Path>,
+	    {
+		let codec = serialize::codec_from_path(&path);
+		let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+		let writer = |file: std::io::BufWriter<std::fs::File>| {
+		    niffler::get_writer(Box::new(file), codec, niffler::compression::Level::One).map_err(error::Error::from)
+		};
+
+		match format {
+		    cli::DumpType::Pcon => self.serialize().pcon(file),
+		    cli::DumpType::Csv => self.serialize().csv(abundance, writer(file)?),
+		    cli::DumpType::Solid => solid::Solid::from_count(self.k, self.raw_noatomic(), abundance).serialize(
+			file,
+			match codec {
+			    solid::Codec::No => solid::Codec::Gzip,
+			    codec => codec,
+			},
+		    ),
+		    #[cfg(feature = "kff")]
+		    cli::DumpType::Kff => self.serialize().kff(abundance, writer(file)?),
+		    cli::DumpType::Raw => self.serialize().raw(file),
+		    cli::DumpType::FastaCount => self.serialize().fasta_count(abundance, writer(file)?),
+		}
+	    }
+
+	    /// Compare count of self and `other`, kmer present only in `other` are gained, count width is check by type
+	    pub fn diff_report(&self, other: &Self) -> error::Result<DiffReport> {
+		if self.k != other.k {
+		    return Err(error::Error::KmerSizeNotMatch { expected: self.k, found: other.k }.into());
+		}
+
+		Ok(DiffReport::from_counts(self.raw_noatomic(), other.raw_noatomic()))
+	    }
+
+	    /// Jaccard similarity of kmer with count upper than `abundance` in self and `other`, 0.0 if no kmer are solid
+	    pub fn jaccard(&self, other: &Self, abundance: $type) -> error::Result<f64> {
+		if self.k != other.k {
+		    return Err(error::Error::KmerSizeNotMatch { expected: self.k, found: other.k }.into());
+		}
+
+		let lhs = solid::Solid::from_count(self.k, self.raw_noatomic(), abundance);
+		let rhs = solid::Solid::from_count(other.k, other.raw_noatomic(), abundance);
+
+		Ok(lhs.jaccard(&rhs))
 	    }
 
 	    /// Increment value at index
@@ -129,40 +929,248 @@ macro_rules! impl_sequential (
 		count[index] = count[index].saturating_add(1);
 	    }
 
-	    /// Get count of a kmer
+	    /// Increment count of each kmer of `sequence`, with [CountOptions::prefetch] count of a kmer is prefetch
+	    /// [PREFETCH_WINDOW] kmer before its increment
+	    fn count_sequence(&mut self, sequence: &[u8], options: &CountOptions) {
+		if !options.prefetch {
+		    options.kmerize(sequence, self.k, |canonical| {
+			Self::inc(&mut self.count, (canonical >> 1) as usize);
+		    });
+
+		    return;
+		}
+
+		let mut window = [0usize; PREFETCH_WINDOW];
+		let mut len = 0;
+		options.kmerize(sequence, self.k, |canonical| {
+		    let slot = len % PREFETCH_WINDOW;
+		    if len >= PREFETCH_WINDOW {
+			Self::inc(&mut self.count, window[slot]);
+		    }
+
+		    window[slot] = (canonical >> 1) as usize;
+		    prefetch(&self.count, window[slot]);
+		    len += 1;
+		});
+
+		for index in len.saturating_sub(PREFETCH_WINDOW)..len {
+		    Self::inc(&mut self.count, window[index % PREFETCH_WINDOW]);
+		}
+	    }
+
+	    /// Get count of a kmer, 0 if kmer is larger than counter kmer size
 	    pub fn get(&self, kmer: u64) -> $type {
-		self.get_canonic(cocktail::kmer::canonical(kmer, self.k))
+		self.try_get(kmer).unwrap_or(0)
+	    }
+
+	    /// Get count of a kmer in ASCII, 0 if kmer length isn't counter kmer size
+	    pub fn get_seq(&self, kmer: &[u8]) -> $type {
+		if kmer.len() != self.k as usize {
+		    return 0;
+		}
+
+		self.get(cocktail::kmer::seq2bit(kmer))
+	    }
+
+	    /// Get count of a kmer, None if kmer is larger than counter kmer size
+	    pub fn try_get(&self, kmer: u64) -> Option<$type> {
+		if kmer >= cocktail::kmer::get_kmer_space_size(self.k) {
+		    return None;
+		}
+
+		Some(self.get_canonic(cocktail::kmer::canonical(kmer, self.k)))
 	    }
 
 	    /// Get the counter of a canonical kmer
-	    fn get_canonic(&self, canonical: u64) -> $type {
+	    pub fn get_canonic(&self, canonical: u64) -> $type {
 		self.count[(canonical >> 1) as usize]
 	    }
 
+	    /// Get raw data in no atomic type, same as raw for sequential counter
+	    pub fn raw_noatomic(&self) -> &[$type] {
+		&self.count
+	    }
+
+	    #[cfg(feature = "parallel")]
+	    /// Parallel iterator on canonical kmer and count of kmer with a count upper than 0
+	    pub fn par_iter(&self) -> impl rayon::iter::ParallelIterator<Item = (u64, $type)> + '_ {
+		self.raw_noatomic()
+		    .par_iter()
+		    .enumerate()
+		    .filter(|(_, count)| **count != 0)
+		    .map(|(hash, count)| (utils::hash2kmer(hash as u64), *count))
+	    }
+
+	    /// Get total number of kmer, sum of all count
+	    pub fn total_kmers(&self) -> u64 {
+		self.raw_noatomic().iter().map(|count| *count as u64).sum()
+	    }
+
+	    /// Get number of kmer with a count upper than 0
+	    pub fn distinct_kmers(&self) -> u64 {
+		self.distinct_kmers_above(0)
+	    }
+
+	    /// Get number of kmer with a count upper than `abundance`
+	    pub fn distinct_kmers_above(&self, abundance: $type) -> u64 {
+		self.raw_noatomic().iter().filter(|count| **count > abundance).count() as u64
+	    }
+
+	    /// Get abundance threshold found by `method` on spectrum of count, count upper than `max_count` are store in
+	    /// last bucket of spectrum, None if method failed
+	    pub fn auto_abundance(&self, method: spectrum::ThresholdMethod, param: f64, max_count: usize) -> Option<$type> {
+		spectrum::Spectrum::from_count_bounded(self.raw_noatomic(), max_count)
+		    .get_threshold(method, param)
+		    .map(<$type>::from)
+	    }
+
+	    /// Get hash index and count of the `n` kmer with highest count upper than `abundance`, sort by decreasing
+	    /// count then increasing hash, only `n` kmer are store in memory
+	    pub fn top(&self, n: usize, abundance: $type) -> Vec<(u64, $type)> {
+		self.top_with(n, abundance, |_| true)
+	    }
+
+	    /// Same as [Counter::top] but only kmer where `keep` return true for their hash index are consider
+	    pub fn top_with<F>(&self, n: usize, abundance: $type, keep: F) -> Vec<(u64, $type)>
+		where F: Fn(u64) -> bool
+	    {
+		let mut heap = std::collections::BinaryHeap::with_capacity(n + 1);
+
+		for (hash, count) in self.raw_noatomic().iter().enumerate() {
+		    if *count > abundance && keep(hash as u64) {
+			heap.push(std::cmp::Reverse((*count, std::cmp::Reverse(hash))));
+			if heap.len() > n {
+			    heap.pop();
+			}
+		    }
+		}
+
+		heap.into_sorted_vec()
+		    .into_iter()
+		    .map(|std::cmp::Reverse((count, std::cmp::Reverse(hash)))| (hash as u64, count))
+		    .collect()
+	    }
+
+	    /// Get the first canonical kmer with maximal count and its count, None if counter is empty
+	    pub fn max_count(&self) -> Option<(u64, $type)> {
+		let mut max: Option<(usize, $type)> = None;
+
+		for (hash, count) in self.raw_noatomic().iter().enumerate() {
+		    if *count != 0 && max.map_or(true, |(_, value)| *count > value) {
+			max = Some((hash, *count));
+		    }
+		}
+
+		max.map(|(hash, count)| (utils::hash2kmer(hash as u64), count))
+	    }
+
+	    #[cfg(feature = "npz")]
+	    /// Write count in a numpy npz file, with array `counts` and array `metadata` contains kmer size, count width in
+	    /// bytes and total number of kmer
+	    pub fn write_npz<W>(&self, output: W) -> error::Result<()>
+	    where
+		W: std::io::Write + std::io::Seek,
+	    {
+		write_npz(output, self.k, std::mem::size_of::<$type>(), self.count.len(), self.total_kmers(), |out| {
+		    for count in self.count.iter() {
+			out.write_all(&count.to_le_bytes())?;
+		    }
+
+		    Ok(())
+		})
+	    }
+
+	    /// Write a human readable summary of counter
+	    pub fn write_report<W>(&self, mut output: W, abundance: $type) -> error::Result<()>
+	    where
+		W: std::io::Write,
+	    {
+		writeln!(output, "k: {}", self.k)?;
+		writeln!(output, "count width: {} bytes", std::mem::size_of::<$type>())?;
+		writeln!(output, "total kmers: {}", self.total_kmers())?;
+		writeln!(output, "distinct kmers: {}", self.distinct_kmers())?;
+		writeln!(output, "distinct kmers above {}: {}", abundance, self.distinct_kmers_above(abundance))?;
+		match self.max_count() {
+		    Some((kmer, count)) => writeln!(output, "max count: {} {}", count, cocktail::kmer::kmer2seq(kmer, self.k))?,
+		    None => writeln!(output, "max count: 0")?,
+		}
+
+		Ok(())
+	    }
+	}
+
+	impl std::ops::Index<u64> for Counter<$type> {
+	    type Output = $type;
+
+	    /// Get count of a kmer, panic if kmer is larger than counter kmer size
+	    fn index(&self, kmer: u64) -> &Self::Output {
+		assert!(kmer < cocktail::kmer::get_kmer_space_size(self.k), "kmer is larger than counter kmer size");
+
+		&self.count[(cocktail::kmer::canonical(kmer, self.k) >> 1) as usize]
+	    }
+	}
+    }
+);
+
+macro_rules! impl_indexed (
+    ($type:ty) => {
+	impl IndexedCounter<$type> {
+	    /// Get count of a kmer, 0 if kmer is larger than counter kmer size, block of kmer is decompress if it
+	    /// isn't the block of previous query
+	    pub fn get(&mut self, kmer: u64) -> error::Result<$type> {
+		if kmer >= cocktail::kmer::get_kmer_space_size(self.k) {
+		    return Ok(0);
+		}
+
+		let hash = cocktail::kmer::canonical(kmer, self.k) >> 1;
+		let index = (hash / self.block_len) as usize;
+
+		if self.block.as_ref().map(|(block_index, _)| *block_index) != Some(index) {
+		    let raw = self.read_block(index)?;
+		    let block = raw
+			.chunks_exact(std::mem::size_of::<$type>())
+			.map(|bytes| bytes.try_into().map(<$type>::from_le_bytes))
+			.collect::<Result<Box<[$type]>, _>>()?;
+
+		    self.block = Some((index, block));
+		}
+
+		Ok(self
+		    .block
+		    .as_ref()
+		    .and_then(|(_, block)| block.get((hash % self.block_len) as usize).copied())
+		    .unwrap_or(0))
+	    }
 	}
     }
 );
 
+impl_indexed!(u8);
+impl_indexed!(u16);
+impl_indexed!(u32);
+impl_indexed!(u64);
+impl_indexed!(u128);
+
 impl_sequential!(u8, utils::init_data, std::io::Read::read_exact);
 impl_sequential!(
     u16,
     utils::init_data,
-    byteorder::ReadBytesExt::read_u16_into::<crate::ByteOrder>
+    byteorder::ReadBytesExt::read_u16_into::<byteorder::LittleEndian>
 );
 impl_sequential!(
     u32,
     utils::init_data,
-    byteorder::ReadBytesExt::read_u32_into::<crate::ByteOrder>
+    byteorder::ReadBytesExt::read_u32_into::<byteorder::LittleEndian>
 );
 impl_sequential!(
     u64,
     utils::init_data,
-    byteorder::ReadBytesExt::read_u64_into::<crate::ByteOrder>
+    byteorder::ReadBytesExt::read_u64_into::<byteorder::LittleEndian>
 );
 impl_sequential!(
     u128,
     utils::init_data,
-    byteorder::ReadBytesExt::read_u128_into::<crate::ByteOrder>
+    byteorder::ReadBytesExt::read_u128_into::<byteorder::LittleEndian>
 );
 
 /***************************/
@@ -180,22 +1188,64 @@ macro_rules! impl_atomic (
 		}
 	    }
 
+	    /// Create a new kmer by read a compressed or not stream, compression is detect by niffler. Same as
+	    /// [Counter::from_stream] for plain stream, keep name of previous binding API
+	    pub fn deserialize<R>(input: R) -> error::Result<Self>
+	    where
+		R: std::io::Read,
+	    {
+		let (readable, _compression) = niffler::get_reader(Box::new(input)).map_err(error::Error::from)?;
+
+		Self::from_stream(readable)
+	    }
+
 	    /// Create a new kmer by read a file
-	    pub fn from_stream<R>(mut input: R) -> error::Result<Self>
+	    pub fn from_stream<R>(input: R) -> error::Result<Self>
 		where R: std::io::Read
 	    {
-		let mut read_buffer = [0u8; 2];
-		input.read_exact(&mut read_buffer)?;
-		let k = read_buffer[0];
+		let (k, width, sparse, input) = read_header(input)?;
 
-		if std::mem::size_of::<$type>() != read_buffer[1] as usize {
-		    return Err(error::Error::TypeNotMatch.into());
+		if std::mem::size_of::<$type>() != width as usize {
+		    return Err(error::Error::TypeNotMatch {
+			expected_bytes: std::mem::size_of::<$type>(),
+			found_bytes: width as usize,
+		    }.into());
 		}
 
-		let mut deflate = flate2::read::MultiGzDecoder::new(input);
 		let mut data = $init(k, 0 as $out_type);
+		Self::decode(input, sparse, &mut data)?;
 
-		$read(&mut deflate, &mut data)?;
+		Ok(Self {
+		    k,
+		    count: utils::transmute_box(data),
+		})
+	    }
+
+	    /// Create a new kmer by read a file, count are decode block by block directly in `buffer`, no other
+	    /// full size array is allocate. `buffer` length must match hash space size of file kmer size, its
+	    /// previous content is erase
+	    pub fn from_stream_into<R>(input: R, buffer: Box<[$type]>) -> error::Result<Self>
+		where R: std::io::Read
+	    {
+		let (k, width, sparse, input) = read_header(input)?;
+
+		if std::mem::size_of::<$type>() != width as usize {
+		    return Err(error::Error::TypeNotMatch {
+			expected_bytes: std::mem::size_of::<$type>(),
+			found_bytes: width as usize,
+		    }.into());
+		}
+
+		let expected = cocktail::kmer::get_hash_space_size(k) as usize;
+		if buffer.len() != expected {
+		    return Err(error::Error::BufferSizeNotMatch { expected, found: buffer.len() }.into());
+		}
+
+		let mut data: Box<[$out_type]> = utils::transmute_box(buffer);
+		if sparse {
+		    data.fill(0 as $out_type);
+		}
+		Self::decode(input, sparse, &mut data)?;
 
 		Ok(Self {
 		    k,
@@ -203,77 +1253,729 @@ macro_rules! impl_atomic (
 		})
 	    }
 
-	    /// Perform count on fasta input
-	    pub fn count_fasta(&mut self, fasta: Box<dyn std::io::BufRead>, record_buffer: u64) {
+	    /// Decode compressed count of `input` in `data`, dense count are read by block of [STREAM_BLOCK]
+	    fn decode<R>(input: R, sparse: bool, data: &mut [$out_type]) -> error::Result<()>
+		where R: std::io::Read
+	    {
+		let mut deflate = flate2::read::MultiGzDecoder::new(input);
+
+		if sparse {
+		    let mut entry = [0u8; 8 + std::mem::size_of::<$out_type>()];
+		    while read_entry(&mut deflate, &mut entry)? {
+			let index = u64::from_le_bytes(entry[..8].try_into()?) as usize;
+			let count = data.get_mut(index).ok_or_else(|| std::io::Error::from(std::io::ErrorKind::InvalidData))?;
+			*count = <$out_type>::from_le_bytes(entry[8..].try_into()?);
+		    }
+		} else {
+		    for block in data.chunks_mut(STREAM_BLOCK) {
+			$read(&mut deflate, block)?;
+		    }
+		}
+
+		Ok(())
+	    }
+
+	    /// Perform count on `reader` in `format`, input is decompress if needed
+	    pub fn count_reader<R>(&mut self, reader: R, format: cli::Format, record_buffer: u64) -> error::Result<()>
+	    where
+		R: std::io::Read + 'static,
+	    {
+		let (input, _compression) = niffler::get_reader(Box::new(reader)).map_err(error::Error::from)?;
+		let mut input: Box<dyn std::io::BufRead> = Box::new(std::io::BufReader::new(input));
+
+		let format = match format {
+		    cli::Format::Auto => input
+			.fill_buf()
+			.ok()
+			.and_then(cli::Format::from_content)
+			.unwrap_or(cli::Format::Fasta),
+		    format => format,
+		};
+
+		match format {
+		    cli::Format::Fasta | cli::Format::Auto => self.count_fasta(input, record_buffer),
+		    #[cfg(feature = "fastq")]
+		    cli::Format::Fastq => self.count_fastq(input, record_buffer),
+		    #[cfg(feature = "gfa")]
+		    cli::Format::Gfa => self.count_gfa(input, record_buffer),
+		    #[cfg(feature = "sam")]
+		    cli::Format::Sam => self.count_sam(input, record_buffer),
+		}
+	    }
+
+	    /// Perform count on fasta input, stop at first malformed record
+	    pub fn count_fasta(&mut self, fasta: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
+		self.count_fasta_with(fasta, record_buffer, &CountOptions::default())
+	    }
+
+	    /// Perform count on fasta input, sequence of records are preprocess according to `options`
+	    pub fn count_fasta_with(&mut self, fasta: Box<dyn std::io::BufRead>, record_buffer: u64, options: &CountOptions) -> error::Result<()> {
 		let mut reader = noodles::fasta::Reader::new(fasta);
-		let mut iter = reader.records();
-		let mut records = Vec::with_capacity(record_buffer as usize);
-
-		let mut end = true;
-		while end {
-		    log::info!("Start populate buffer");
-		    end = utils::populate_buffer(&mut iter, &mut records, record_buffer);
-		    log::info!("End populate buffer {}", records.len());
-
-		    records.par_iter().for_each(|record| {
-			if record.sequence().len() >= self.k as usize {
-			    let tokenizer =
-				cocktail::tokenizer::Canonical::new(record.sequence().as_ref(), self.k);
-
-			    for canonical in tokenizer {
-				Self::inc(&self.count, (canonical >> 1) as usize);
+
+		let locals = self.init_locals(options);
+
+		if options.streaming && !Self::single_thread() {
+		    self.count_streaming(
+			|| {
+			    let mut sequences = Vec::with_capacity(1);
+			    utils::refill_buffer(&mut reader, &mut sequences, 1)?;
+			    Ok(sequences.pop())
+			},
+			Vec::as_slice,
+			options,
+			&locals,
+		    )?;
+		} else if options.sync_read || Self::single_thread() {
+		    let mut sequences = Vec::with_capacity(record_buffer as usize);
+
+		    let mut end = true;
+		    while end && !options.stopped() {
+			log::info!("Start populate buffer");
+			end = utils::refill_buffer(&mut reader, &mut sequences, record_buffer)?;
+			log::info!("End populate buffer {}", sequences.len());
+
+			self.count_batch(&sequences, Vec::as_slice, options, &locals);
+		    }
+		} else {
+		    self.count_pipeline(
+			|sequences| utils::refill_buffer(&mut reader, sequences, record_buffer),
+			Vec::as_slice,
+			record_buffer,
+			options,
+			&locals,
+		    )?;
+		}
+
+		self.reduce_locals(locals);
+
+		Ok(())
+	    }
+
+	    /// Perform count on sequences produce by any iterator, useful for input not read by noodles.
+	    /// Sequences are read by batch of `SEQUENCES_BUFFER` in current thread
+	    pub fn count_sequences<I>(&mut self, seqs: I)
+	    where
+		I: IntoIterator<Item = Vec<u8>>,
+	    {
+		self.count_sequences_with(seqs, &CountOptions::default())
+	    }
+
+	    /// Perform count on sequences produce by any iterator, sequences are preprocess according to `options`
+	    pub fn count_sequences_with<I>(&mut self, seqs: I, options: &CountOptions)
+	    where
+		I: IntoIterator<Item = Vec<u8>>,
+	    {
+		let mut iter = seqs.into_iter();
+		let mut populate = |sequences: &mut Vec<Vec<u8>>| -> error::Result<bool> {
+		    sequences.clear();
+		    sequences.extend(iter.by_ref().take(SEQUENCES_BUFFER));
+		    Ok(sequences.len() == SEQUENCES_BUFFER)
+		};
+
+		let locals = self.init_locals(options);
+
+		if options.sync_read || Self::single_thread() {
+		    let mut sequences = Vec::with_capacity(SEQUENCES_BUFFER);
+
+		    let mut end = true;
+		    while end && !options.stopped() {
+			// populate never fail
+			end = populate(&mut sequences).unwrap_or(false);
+
+			self.count_batch(&sequences, Vec::as_slice, options, &locals);
+		    }
+		} else {
+		    // populate never fail
+		    let _ = self.count_pipeline(populate, Vec::as_slice, SEQUENCES_BUFFER as u64, options, &locals);
+		}
+
+		self.reduce_locals(locals);
+	    }
+
+	    #[cfg(feature = "gfa")]
+	    /// Perform count on sequence of gfa segments, stop at first io error
+	    pub fn count_gfa(&mut self, gfa: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
+		self.count_gfa_with(gfa, record_buffer, &CountOptions::default())
+	    }
+
+	    #[cfg(feature = "gfa")]
+	    /// Perform count on sequence of gfa segments, sequences are preprocess according to `options`
+	    pub fn count_gfa_with(&mut self, gfa: Box<dyn std::io::BufRead>, record_buffer: u64, options: &CountOptions) -> error::Result<()> {
+		let mut iter = utils::gfa_segments(gfa);
+
+		let locals = self.init_locals(options);
+
+		if options.sync_read || Self::single_thread() {
+		    let mut segments = Vec::with_capacity(record_buffer as usize);
+
+		    let mut end = true;
+		    while end && !options.stopped() {
+			log::info!("Start populate buffer");
+			end = utils::populate_segments(&mut iter, &mut segments, record_buffer)?;
+			log::info!("End populate buffer {}", segments.len());
+
+			self.count_batch(&segments, Vec::as_slice, options, &locals);
+		    }
+		} else {
+		    self.count_pipeline(
+			|segments| utils::populate_segments(&mut iter, segments, record_buffer),
+			Vec::as_slice,
+			record_buffer,
+			options,
+			&locals,
+		    )?;
+		}
+
+		self.reduce_locals(locals);
+
+		Ok(())
+	    }
+
+	    #[cfg(feature = "sam")]
+	    /// Perform count on sequence of sam alignments, stop at first malformed alignment
+	    pub fn count_sam(&mut self, sam: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
+		self.count_sam_with(sam, record_buffer, &CountOptions::default())
+	    }
+
+	    #[cfg(feature = "sam")]
+	    /// Perform count on sequence of sam alignments, sequences are preprocess according to `options`
+	    pub fn count_sam_with(&mut self, sam: Box<dyn std::io::BufRead>, record_buffer: u64, options: &CountOptions) -> error::Result<()> {
+		let mut iter = utils::sam_sequences(sam);
+
+		let locals = self.init_locals(options);
+
+		if options.sync_read || Self::single_thread() {
+		    let mut sequences = Vec::with_capacity(record_buffer as usize);
+
+		    let mut end = true;
+		    while end && !options.stopped() {
+			log::info!("Start populate buffer");
+			end = utils::populate_segments(&mut iter, &mut sequences, record_buffer)?;
+			log::info!("End populate buffer {}", sequences.len());
+
+			self.count_batch(&sequences, Vec::as_slice, options, &locals);
+		    }
+		} else {
+		    self.count_pipeline(
+			|sequences| utils::populate_segments(&mut iter, sequences, record_buffer),
+			Vec::as_slice,
+			record_buffer,
+			options,
+			&locals,
+		    )?;
+		}
+
+		self.reduce_locals(locals);
+
+		Ok(())
+	    }
+
+	    #[cfg(feature = "fastq")]
+	    /// Perform count on both mates of paired fastq inputs, stop at first malformed record
+	    pub fn count_fastq_pairs(&mut self, r1: Box<dyn std::io::BufRead>, r2: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
+		self.count_fastq_pairs_with(r1, r2, record_buffer, &CountOptions::default())
+	    }
+
+	    #[cfg(feature = "fastq")]
+	    /// Perform count on both mates of paired fastq inputs, `r1` is count before `r2`, sequence of records are
+	    /// preprocess according to `options`
+	    pub fn count_fastq_pairs_with(&mut self, r1: Box<dyn std::io::BufRead>, r2: Box<dyn std::io::BufRead>, record_buffer: u64, options: &CountOptions) -> error::Result<()> {
+		self.count_fastq_with(r1, record_buffer, options)?;
+		self.count_fastq_with(r2, record_buffer, options)
+	    }
+
+	    #[cfg(feature = "fastq")]
+	    /// Perform count on fastq input, stop at first malformed record
+	    pub fn count_fastq(&mut self, fastq: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
+		self.count_fastq_with(fastq, record_buffer, &CountOptions::default())
+	    }
+
+	    #[cfg(feature = "fastq")]
+	    /// Perform count on fastq input, sequence of records are preprocess according to `options`
+	    pub fn count_fastq_with(&mut self, fastq: Box<dyn std::io::BufRead>, record_buffer: u64, options: &CountOptions) -> error::Result<()> {
+		let mut reader = noodles::fastq::Reader::new(fastq);
+
+		let locals = self.init_locals(options);
+
+		if options.streaming && !Self::single_thread() {
+		    self.count_streaming(
+			|| {
+			    let mut records = Vec::with_capacity(1);
+			    utils::refill_bufferq(&mut reader, &mut records, 1)?;
+			    Ok(records.pop())
+			},
+			fastq_sequence,
+			options,
+			&locals,
+		    )?;
+		} else if options.sync_read || Self::single_thread() {
+		    let mut records = Vec::with_capacity(record_buffer as usize);
+
+		    let mut end = true;
+		    while end && !options.stopped() {
+			log::info!("Start populate buffer");
+			end = utils::refill_bufferq(&mut reader, &mut records, record_buffer)?;
+			log::info!("End populate buffer {}", records.len());
+
+			self.count_batch(&records, fastq_sequence, options, &locals);
+		    }
+		} else {
+		    self.count_pipeline(
+			|records| utils::refill_bufferq(&mut reader, records, record_buffer),
+			fastq_sequence,
+			record_buffer,
+			options,
+			&locals,
+		    )?;
+		}
+
+		self.reduce_locals(locals);
+
+		Ok(())
+	    }
+
+	    /// Read batch of records with `populate` in current thread, while a worker thread count previous batch,
+	    /// counted batch are send back to reader to reuse their allocation
+	    fn count_pipeline<R, P, S>(
+		&self,
+		mut populate: P,
+		sequence: S,
+		record_buffer: u64,
+		options: &CountOptions,
+		locals: &[std::sync::Mutex<Vec<u32>>],
+	    ) -> error::Result<()>
+	    where
+		R: std::marker::Send + std::marker::Sync,
+		P: FnMut(&mut Vec<R>) -> error::Result<bool>,
+		S: Fn(&R) -> &[u8] + std::marker::Sync,
+	    {
+		let (sender, receiver) = crossbeam_channel::bounded::<Vec<R>>(PIPELINE_DEPTH);
+		let (recycler, recycled) = crossbeam_channel::bounded::<Vec<R>>(PIPELINE_DEPTH + 1);
+		let sequence = &sequence;
+
+		std::thread::scope(|scope| {
+		    let worker = scope.spawn(move || {
+			for records in receiver {
+			    self.count_batch(&records, sequence, options, locals);
+
+			    // reader could be stop, buffer is just drop
+			    let _ = recycler.try_send(records);
+			}
+		    });
+
+		    let mut result = Ok(());
+		    loop {
+			let mut records = recycled
+			    .try_recv()
+			    .unwrap_or_else(|_| Vec::with_capacity(record_buffer as usize));
+
+			log::info!("Start populate buffer");
+			let end = match populate(&mut records) {
+			    Ok(end) => end,
+			    Err(error) => {
+				result = Err(error);
+				break;
+			    }
+			};
+			log::info!("End populate buffer {}", records.len());
+
+			if !records.is_empty() && sender.send(records).is_err() {
+			    break;
+			}
+
+			if !end || options.stopped() {
+			    break;
+			}
+		    }
+		    drop(sender);
+
+		    if let Err(payload) = worker.join() {
+			std::panic::resume_unwind(payload);
+		    }
+
+		    result
+		})
+	    }
+
+	    /// Read records one by one with `next` in current thread, records are send in a shared queue where each
+	    /// worker thread take the next available record, at most [STREAMING_DEPTH] records by worker are in memory
+	    fn count_streaming<R, N, S>(
+		&self,
+		mut next: N,
+		sequence: S,
+		options: &CountOptions,
+		locals: &[std::sync::Mutex<Vec<u32>>],
+	    ) -> error::Result<()>
+	    where
+		R: std::marker::Send,
+		N: FnMut() -> error::Result<Option<R>>,
+		S: Fn(&R) -> &[u8] + std::marker::Sync,
+	    {
+		let nb_worker = rayon::current_num_threads();
+		let (sender, receiver) = crossbeam_channel::bounded::<R>(STREAMING_DEPTH * nb_worker);
+		let sequence = &sequence;
+		let ordering = options.ordering();
+
+		std::thread::scope(|scope| {
+		    let workers = (0..nb_worker)
+			.map(|index| {
+			    let receiver = receiver.clone();
+			    scope.spawn(move || {
+				for record in receiver {
+				    if locals.is_empty() {
+					options.kmerize(sequence(&record), self.k, |canonical| {
+					    Self::inc_with(&self.count, (canonical >> 1) as usize, ordering);
+					});
+				    } else {
+					let mut local = locals[index % locals.len()]
+					    .lock()
+					    .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+					options.kmerize(sequence(&record), self.k, |canonical| {
+					    let hash = (canonical >> 1) as usize;
+					    local[hash] = local[hash].saturating_add(1);
+					});
+				    }
+				}
+			    })
+			})
+			.collect::<Vec<_>>();
+		    drop(receiver);
+
+		    let mut result = Ok(());
+		    while !options.stopped() {
+			match next() {
+			    Ok(Some(record)) => {
+				if sender.send(record).is_err() {
+				    break;
+				}
+			    }
+			    Ok(None) => break,
+			    Err(error) => {
+				result = Err(error);
+				break;
 			    }
 			}
+		    }
+		    drop(sender);
+
+		    for worker in workers {
+			if let Err(payload) = worker.join() {
+			    std::panic::resume_unwind(payload);
+			}
+		    }
+
+		    result
+		})
+	    }
+
+	    /// Allocate one dense u32 buffer by rayon thread if `options` ask for local count
+	    fn init_locals(&self, options: &CountOptions) -> Vec<std::sync::Mutex<Vec<u32>>> {
+		if options.local_count && !Self::single_thread() {
+		    (0..rayon::current_num_threads())
+			.map(|_| std::sync::Mutex::new(vec![0u32; self.count.len()]))
+			.collect()
+		} else {
+		    Vec::new()
+		}
+	    }
+
+	    /// Count kmer of a batch of sequence, in thread local buffer if `locals` isn't empty. With a single
+	    /// thread pool, batch is count in current thread without rayon dispatch
+	    fn count_batch<R, S>(&self, records: &[R], sequence: S, options: &CountOptions, locals: &[std::sync::Mutex<Vec<u32>>])
+	    where
+		R: std::marker::Sync,
+		S: Fn(&R) -> &[u8] + std::marker::Send + std::marker::Sync,
+	    {
+		if locals.is_empty() {
+		    let ordering = options.ordering();
+		    let count = |sequence: &[u8]| {
+			options.kmerize(sequence, self.k, |canonical| {
+			    Self::inc_with(&self.count, (canonical >> 1) as usize, ordering);
+			});
+		    };
+
+		    if Self::single_thread() {
+			records.iter().map(sequence).for_each(count);
+		    } else {
+			records.par_iter().map(sequence).for_each(count);
+		    }
+		} else {
+		    records.par_iter().map(sequence).for_each(|sequence| {
+			let index = rayon::current_thread_index().unwrap_or(0) % locals.len();
+			let mut local = locals[index].lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+
+			options.kmerize(sequence, self.k, |canonical| {
+			    let hash = (canonical >> 1) as usize;
+			    local[hash] = local[hash].saturating_add(1);
+			});
 		    });
 		}
 	    }
 
-	    #[cfg(feature = "fastq")]
-	    /// Perform count on fastq input
-	    pub fn count_fastq(&mut self, fastq: Box<dyn std::io::BufRead>, record_buffer: u64) {
-		let mut reader = noodles::fastq::Reader::new(fastq);
-		let mut iter = reader.records();
-		let mut records = Vec::with_capacity(record_buffer as usize);
-
-		let mut end = true;
-		while end {
-		    log::info!("Start populate buffer");
-		    end = utils::populate_bufferq(&mut iter, &mut records, record_buffer);
-		    log::info!("End populate buffer {}", records.len());
-
-		    records.par_iter().for_each(|record| {
-			if record.sequence().len() >= self.k as usize {
-			    let tokenizer =
-				cocktail::tokenizer::Canonical::new(record.sequence().as_ref(), self.k);
-
-			    for canonical in tokenizer {
-				Self::inc(&self.count, (canonical >> 1) as usize);
-			    }
+	    /// True if rayon pool have only one thread, count is perform in current thread without reader thread
+	    fn single_thread() -> bool {
+		rayon::current_num_threads() == 1
+	    }
+
+	    /// Sum thread local buffer in counter, value are saturate to counter type
+	    fn reduce_locals(&self, locals: Vec<std::sync::Mutex<Vec<u32>>>) {
+		for local in locals {
+		    let local = local.into_inner().unwrap_or_else(std::sync::PoisonError::into_inner);
+
+		    self.count.par_iter().zip(local.par_iter()).for_each(|(count, value)| {
+			if *value != 0 {
+			    let value = <$out_type>::try_from(*value).unwrap_or($max);
+			    let _ = count.fetch_update(
+				std::sync::atomic::Ordering::SeqCst,
+				std::sync::atomic::Ordering::SeqCst,
+				|x| Some(x.saturating_add(value)),
+			    );
+			}
+		    });
+		}
+	    }
+
+	    /// Add count of `other` to count of self
+	    pub fn merge(&mut self, other: &Self) -> error::Result<()> {
+		if self.k != other.k {
+		    return Err(error::Error::KmerSizeNotMatch { expected: self.k, found: other.k }.into());
+		}
+
+		self.count.par_iter().zip(other.raw_noatomic().par_iter()).for_each(|(count, value)| {
+		    let _ = count.fetch_update(std::sync::atomic::Ordering::SeqCst, std::sync::atomic::Ordering::SeqCst, |c| Some(c.saturating_add(*value)));
+		});
+
+		Ok(())
+	    }
+
+	    /// Write counter in pcon format in `output`, count lower or equal than `abundance` are write as 0. Replace
+	    /// `serialize(output, abundance)` of previous binding API, name is take by [Counter::serialize]
+	    pub fn serialize_pcon<W>(mut self, output: W, abundance: $out_type) -> error::Result<()>
+	    where
+		W: std::io::Write,
+	    {
+		for count in self.count.iter_mut() {
+		    if *count.get_mut() <= abundance {
+			*count.get_mut() = 0;
+		    }
+		}
+
+		self.serialize().pcon(output)
+	    }
+
+	    /// Write counter in `path` in `format`, output is compressed according to path extension see
+	    /// [serialize::codec_from_path], solid output is always compressed. Pcon blocks are already gzip
+	    /// compressed and raw count must stay at fixed offset, these two formats ignore extension codec so
+	    /// `.pcon.gz` or `.pcon.zst` file are plain pcon readable by [Counter::from_stream] and `pcon dump -i`
+	    pub fn serialize_to_path<P>(self, path: P, abundance: $out_type, format: cli::DumpType) -> error::Result<()>
+	    where
+		P: std::convert::AsRef<std::path::Path>,
+	    {
+		let codec = serialize::codec_from_path(&path);
+		let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+		let writer = |file: std::io::BufWriter<std::fs::File>| {
+		    niffler::get_writer(Box::new(file), codec, niffler::compression::Level::One).map_err(error::Error::from)
+		};
+
+		match format {
+		    cli::DumpType::Pcon => self.serialize().pcon(file),
+		    cli::DumpType::Csv => self.serialize().csv(abundance, writer(file)?),
+		    cli::DumpType::Solid => solid::Solid::from_count(self.k, self.raw_noatomic(), abundance).serialize(
+			file,
+			match codec {
+			    solid::Codec::No => solid::Codec::Gzip,
+			    codec => codec,
+			},
+		    ),
+		    #[cfg(feature = "kff")]
+		    cli::DumpType::Kff => self.serialize().kff(abundance, writer(file)?),
+		    cli::DumpType::Raw => self.serialize().raw(file),
+		    cli::DumpType::FastaCount => self.serialize().fasta_count(abundance, writer(file)?),
+		}
+	    }
+
+	    /// Compare count of self and `other`, kmer present only in `other` are gained, count width is check by type
+	    pub fn diff_report(&self, other: &Self) -> error::Result<DiffReport> {
+		if self.k != other.k {
+		    return Err(error::Error::KmerSizeNotMatch { expected: self.k, found: other.k }.into());
+		}
+
+		Ok(DiffReport::from_counts(self.raw_noatomic(), other.raw_noatomic()))
+	    }
+
+	    /// Jaccard similarity of kmer with count upper than `abundance` in self and `other`, 0.0 if no kmer are solid
+	    pub fn jaccard(&self, other: &Self, abundance: $out_type) -> error::Result<f64> {
+		if self.k != other.k {
+		    return Err(error::Error::KmerSizeNotMatch { expected: self.k, found: other.k }.into());
+		}
+
+		let lhs = solid::Solid::from_count(self.k, self.raw_noatomic(), abundance);
+		let rhs = solid::Solid::from_count(other.k, other.raw_noatomic(), abundance);
+
+		Ok(lhs.jaccard(&rhs))
+	    }
+
+	    /// Increment value at index
+	    pub(crate) fn inc(count: &[$type], index: usize) {
+		Self::inc_with(count, index, std::sync::atomic::Ordering::SeqCst)
+	    }
+
+	    /// Increment value at index with `ordering` memory ordering
+	    pub(crate) fn inc_with(count: &[$type], index: usize, ordering: std::sync::atomic::Ordering) {
+		if count[index].load(ordering) != $max {
+		    count[index].fetch_add(1, ordering);
+		}
+	    }
+
+	    /// Get count of a kmer, 0 if kmer is larger than counter kmer size
+	    pub fn get(&self, kmer: u64) -> $out_type {
+		self.try_get(kmer).unwrap_or(0)
+	    }
+
+	    /// Get count of a kmer by value, [std::ops::Index] can't return a reference on an atomic count, panic if
+	    /// kmer is larger than counter kmer size
+	    pub fn index(&self, kmer: u64) -> $out_type {
+		assert!(kmer < cocktail::kmer::get_kmer_space_size(self.k), "kmer is larger than counter kmer size");
+
+		self.get_canonic(cocktail::kmer::canonical(kmer, self.k))
+	    }
+
+	    /// Get count of a kmer in ASCII, 0 if kmer length isn't counter kmer size
+	    pub fn get_seq(&self, kmer: &[u8]) -> $out_type {
+		if kmer.len() != self.k as usize {
+		    return 0;
+		}
+
+		self.get(cocktail::kmer::seq2bit(kmer))
+	    }
+
+	    /// Get count of a kmer, None if kmer is larger than counter kmer size
+	    pub fn try_get(&self, kmer: u64) -> Option<$out_type> {
+		if kmer >= cocktail::kmer::get_kmer_space_size(self.k) {
+		    return None;
+		}
+
+		Some(self.get_canonic(cocktail::kmer::canonical(kmer, self.k)))
+	    }
+
+	    /// Get the counter of a canonical kmer
+	    pub fn get_canonic(&self, canonical: u64) -> $out_type {
+		self.count[(canonical >> 1) as usize].load(std::sync::atomic::Ordering::SeqCst)
+	    }
+
+	    /// Get raw data in no atomic type
+	    pub fn raw_noatomic(&self) -> &[$out_type] {
+		utils::transmute(&self.count)
+	    }
+
+	    #[cfg(feature = "parallel")]
+	    /// Parallel iterator on canonical kmer and count of kmer with a count upper than 0
+	    pub fn par_iter(&self) -> impl rayon::iter::ParallelIterator<Item = (u64, $out_type)> + '_ {
+		self.raw_noatomic()
+		    .par_iter()
+		    .enumerate()
+		    .filter(|(_, count)| **count != 0)
+		    .map(|(hash, count)| (utils::hash2kmer(hash as u64), *count))
+	    }
+
+	    /// Get total number of kmer, sum of all count
+	    pub fn total_kmers(&self) -> u64 {
+		self.raw_noatomic().iter().map(|count| *count as u64).sum()
+	    }
+
+	    /// Get number of kmer with a count upper than 0
+	    pub fn distinct_kmers(&self) -> u64 {
+		self.distinct_kmers_above(0)
+	    }
+
+	    /// Get number of kmer with a count upper than `abundance`
+	    pub fn distinct_kmers_above(&self, abundance: $out_type) -> u64 {
+		self.raw_noatomic().iter().filter(|count| **count > abundance).count() as u64
+	    }
+
+	    /// Get abundance threshold found by `method` on spectrum of count, count upper than `max_count` are store in
+	    /// last bucket of spectrum, None if method failed
+	    pub fn auto_abundance(&self, method: spectrum::ThresholdMethod, param: f64, max_count: usize) -> Option<$out_type> {
+		spectrum::Spectrum::from_count_bounded(self.raw_noatomic(), max_count)
+		    .get_threshold(method, param)
+		    .map(<$out_type>::from)
+	    }
+
+	    /// Get hash index and count of the `n` kmer with highest count upper than `abundance`, sort by decreasing
+	    /// count then increasing hash, only `n` kmer are store in memory
+	    pub fn top(&self, n: usize, abundance: $out_type) -> Vec<(u64, $out_type)> {
+		self.top_with(n, abundance, |_| true)
+	    }
+
+	    /// Same as [Counter::top] but only kmer where `keep` return true for their hash index are consider
+	    pub fn top_with<F>(&self, n: usize, abundance: $out_type, keep: F) -> Vec<(u64, $out_type)>
+		where F: Fn(u64) -> bool
+	    {
+		let mut heap = std::collections::BinaryHeap::with_capacity(n + 1);
+
+		for (hash, count) in self.raw_noatomic().iter().enumerate() {
+		    if *count > abundance && keep(hash as u64) {
+			heap.push(std::cmp::Reverse((*count, std::cmp::Reverse(hash))));
+			if heap.len() > n {
+			    heap.pop();
 			}
-		    });
+		    }
 		}
+
+		heap.into_sorted_vec()
+		    .into_iter()
+		    .map(|std::cmp::Reverse((count, std::cmp::Reverse(hash)))| (hash as u64, count))
+		    .collect()
 	    }
 
-	    /// Increment value at index
-	    pub(crate) fn inc(count: &[$type], index: usize) {
-		if count[index].load(std::sync::atomic::Ordering::SeqCst) != $max {
-		    count[index].fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+	    /// Get the first canonical kmer with maximal count and its count, None if counter is empty
+	    pub fn max_count(&self) -> Option<(u64, $out_type)> {
+		let mut max: Option<(usize, $out_type)> = None;
+
+		for (hash, count) in self.raw_noatomic().iter().enumerate() {
+		    if *count != 0 && max.map_or(true, |(_, value)| *count > value) {
+			max = Some((hash, *count));
+		    }
 		}
-	    }
 
-	    /// Get count of a kmer
-	    pub fn get(&self, kmer: u64) -> $out_type {
-		self.get_canonic(cocktail::kmer::canonical(kmer, self.k))
+		max.map(|(hash, count)| (utils::hash2kmer(hash as u64), count))
 	    }
 
-	    /// Get the counter of a canonical kmer
-	    pub fn get_canonic(&self, canonical: u64) -> $out_type {
-		self.count[(canonical >> 1) as usize].load(std::sync::atomic::Ordering::SeqCst)
+	    #[cfg(feature = "npz")]
+	    /// Write count in a numpy npz file, with array `counts` and array `metadata` contains kmer size, count width in
+	    /// bytes and total number of kmer
+	    pub fn write_npz<W>(&self, output: W) -> error::Result<()>
+	    where
+		W: std::io::Write + std::io::Seek,
+	    {
+		let counts = self.raw_noatomic();
+		write_npz(output, self.k, std::mem::size_of::<$out_type>(), counts.len(), self.total_kmers(), |out| {
+		    for count in counts {
+			out.write_all(&count.to_le_bytes())?;
+		    }
+
+		    Ok(())
+		})
 	    }
 
-	    /// Get raw data in no atomic type
-	    pub fn raw_noatomic(&self) -> &[$out_type] {
-		utils::transmute(&self.count)
+	    /// Write a human readable summary of counter
+	    pub fn write_report<W>(&self, mut output: W, abundance: $out_type) -> error::Result<()>
+	    where
+		W: std::io::Write,
+	    {
+		writeln!(output, "k: {}", self.k)?;
+		writeln!(output, "count width: {} bytes", std::mem::size_of::<$type>())?;
+		writeln!(output, "total kmers: {}", self.total_kmers())?;
+		writeln!(output, "distinct kmers: {}", self.distinct_kmers())?;
+		writeln!(output, "distinct kmers above {}: {}", abundance, self.distinct_kmers_above(abundance))?;
+		match self.max_count() {
+		    Some((kmer, count)) => writeln!(output, "max count: {} {}", count, cocktail::kmer::kmer2seq(kmer, self.k))?,
+		    None => writeln!(output, "max count: 0")?,
+		}
+
+		Ok(())
 	    }
 	}
 
@@ -294,7 +1996,7 @@ impl_atomic!(
     u16,
     u16::MAX,
     utils::init_data,
-    byteorder::ReadBytesExt::read_u16_into::<crate::ByteOrder>
+    byteorder::ReadBytesExt::read_u16_into::<byteorder::LittleEndian>
 );
 #[cfg(feature = "parallel")]
 impl_atomic!(
@@ -302,7 +2004,7 @@ impl_atomic!(
     u32,
     u32::MAX,
     utils::init_data,
-    byteorder::ReadBytesExt::read_u32_into::<crate::ByteOrder>
+    byteorder::ReadBytesExt::read_u32_into::<byteorder::LittleEndian>
 );
 #[cfg(feature = "parallel")]
 impl_atomic!(
@@ -310,13 +2012,16 @@ impl_atomic!(
     u64,
     u64::MAX,
     utils::init_data,
-    byteorder::ReadBytesExt::read_u64_into::<crate::ByteOrder>
+    byteorder::ReadBytesExt::read_u64_into::<byteorder::LittleEndian>
 );
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use std::io::Read as _;
+    use std::io::Write as _;
+
     const FASTA_FILE: &[u8] = b">random_seq 0
 GTTCTGCAAATTAGAACAGACAATACACTGGCAGGCGTTGCGTTGGGGGAGATCTTCCGTAACGAGCCGGCATTTGTAAGAAAGAGATTTCGAGTAAATG
 >random_seq 1
@@ -372,7 +2077,7 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
             fn $name() {
                 let mut counter = Counter::<$type>::new(5);
 
-                counter.count_fasta(Box::new(FASTA_FILE), 1);
+                counter.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
 
                 assert_eq!(*counter.get_raw(14), 2);
 
@@ -397,6 +2102,1049 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
     sequential_fasta!(u64, sequential_fasta_u64, TRUTH_COUNT_U64);
     sequential_fasta!(u128, sequential_fasta_u128, TRUTH_COUNT_U128);
 
+    #[test]
+    fn counts_equal_saturating() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut wider = counter
+            .raw()
+            .iter()
+            .map(|x| *x as u16)
+            .collect::<Vec<u16>>();
+        assert!(counter.counts_equal_saturating(&wider));
+
+        counter.count[0] = u8::MAX;
+        wider[0] = 300;
+        assert!(counter.counts_equal_saturating(&wider));
+
+        wider[0] = 254;
+        assert!(!counter.counts_equal_saturating(&wider));
+        assert!(!counter.counts_equal_saturating(&wider[1..]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_canonic() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(&b">1\nAAAAAAAA\n"[..]), 1)?;
+
+        assert_eq!(counter.get_canonic(0), 4);
+        assert_eq!(
+            counter.get_canonic(0),
+            counter.get(cocktail::kmer::seq2bit(b"TTTTT"))
+        );
+
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        for kmer in 0..cocktail::kmer::get_kmer_space_size(5) {
+            assert_eq!(
+                counter.get_canonic(cocktail::kmer::canonical(kmer, 5)),
+                counter.get(kmer)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn index() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        assert_eq!(counter[cocktail::kmer::seq2bit(b"GTTCT")], 2);
+        for kmer in 0..cocktail::kmer::get_kmer_space_size(5) {
+            assert_eq!(counter[kmer], counter.get(kmer));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "kmer is larger than counter kmer size")]
+    fn index_oversized() {
+        let counter = Counter::<u8>::new(5);
+        let _ = counter[cocktail::kmer::get_kmer_space_size(5)];
+    }
+
+    #[test]
+    fn get_seq() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        assert_eq!(
+            counter.get_seq(b"GTTCT"),
+            counter.get(cocktail::kmer::seq2bit(b"GTTCT"))
+        );
+        assert_ne!(counter.get_seq(b"GTTCT"), 0);
+        assert_eq!(counter.get_seq(b"AGAAC"), counter.get_seq(b"GTTCT"));
+
+        assert_eq!(counter.get_seq(b"GTTC"), 0);
+        assert_eq!(counter.get_seq(b"GTTCTG"), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn diff_report() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        assert_eq!(counter.diff_report(&counter)?, DiffReport::default());
+
+        let present = counter
+            .raw()
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 1)
+            .map(|(hash, _)| hash)
+            .take(2)
+            .collect::<Vec<usize>>();
+        let absent = counter.raw().iter().position(|count| *count == 0).unwrap();
+
+        let mut modified = counter.clone();
+        modified.count[present[0]] = 0;
+        modified.count[present[1]] += 3;
+        modified.count[absent] = 2;
+
+        let report = counter.diff_report(&modified)?;
+        assert_eq!(
+            report,
+            DiffReport {
+                gained: 1,
+                lost: 1,
+                changed: 1,
+                abs_diff: counter.count[present[0]] as u128 + 3 + 2,
+            }
+        );
+
+        let mut output = Vec::new();
+        report.write(&mut output)?;
+        assert_eq!(
+            String::from_utf8(output)?,
+            format!(
+                "kmers gained: 1\nkmers lost: 1\nkmers changed: 1\ntotal absolute count difference: {}\n",
+                report.abs_diff
+            )
+        );
+
+        let err = counter.diff_report(&Counter::<u8>::new(7)).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::KmerSizeNotMatch {
+                expected: 5,
+                found: 7
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn jaccard() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+        assert_eq!(counter.jaccard(&counter, 0)?, 1.0);
+
+        let mut poly_a = Counter::<u8>::new(5);
+        poly_a.count_fasta(Box::new(&b">1\nAAAAAAAA\n"[..]), 1)?;
+        let mut poly_c = Counter::<u8>::new(5);
+        poly_c.count_fasta(Box::new(&b">1\nCCCCCCCC\n"[..]), 1)?;
+        assert_eq!(poly_a.jaccard(&poly_c, 0)?, 0.0);
+
+        let mut both = Counter::<u8>::new(5);
+        both.count_fasta(Box::new(&b">1\nAAAAAAAA\n>2\nCCCCCCCC\n"[..]), 1)?;
+        assert_eq!(poly_a.jaccard(&both, 0)?, 0.5);
+
+        assert_eq!(poly_a.jaccard(&both, 4)?, 0.0);
+
+        let err = counter.jaccard(&Counter::<u8>::new(7), 0).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::KmerSizeNotMatch {
+                expected: 5,
+                found: 7
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut merged = counter.clone();
+        merged.merge(&counter)?;
+        assert_eq!(merged.total_kmers(), 2 * counter.total_kmers());
+
+        let err = merged.merge(&Counter::<u8>::new(7)).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::KmerSizeNotMatch {
+                expected: 5,
+                found: 7
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn type_not_match() -> error::Result<()> {
+        let mut file = vec![];
+        Counter::<u16>::new(5).serialize().pcon(&mut file)?;
+
+        let err = Counter::<u8>::from_stream(&file[..]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::TypeNotMatch {
+                expected_bytes: 1,
+                found_bytes: 2
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn oversized_kmer() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let oversized = cocktail::kmer::seq2bit(b"GTTCTGC");
+
+        assert_eq!(counter.try_get(oversized), None);
+        assert_eq!(counter.get(oversized), 0);
+        assert_eq!(counter.try_get(u64::MAX), None);
+        assert_eq!(
+            counter.try_get(cocktail::kmer::seq2bit(b"GTTCT")),
+            Some(counter.get(cocktail::kmer::seq2bit(b"GTTCT")))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn open_indexed() -> error::Result<()> {
+        let mut counter = Counter::<u32>::new(11);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let temp = tempfile::NamedTempFile::new()?;
+        counter
+            .clone()
+            .serialize()
+            .pcon_indexed(std::fs::File::create(temp.path())?)?;
+
+        // indexed file stay readable as a classic pcon file
+        assert_eq!(
+            Counter::<u32>::from_stream(std::fs::File::open(temp.path())?)?,
+            counter
+        );
+
+        let mut indexed = Counter::<u32>::open_indexed(temp.path())?;
+        assert_eq!(indexed.k(), 11);
+        for kmer in [
+            b"GTTCTGCAAAT",
+            b"TGAGCTCAGGA",
+            b"AAAAAAAAAAA",
+            b"CATTTGTAAGA",
+            b"GGGGGGGGGGG",
+            b"AGGATAGAAGC",
+        ] {
+            let kmer = cocktail::kmer::seq2bit(kmer);
+            assert_eq!(indexed.get(kmer)?, counter.get(kmer));
+        }
+        assert!(indexed.get(cocktail::kmer::seq2bit(b"GTTCTGCAAAT"))? > 0);
+
+        let err = Counter::<u8>::open_indexed(temp.path()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::TypeNotMatch { .. })
+        ));
+
+        counter
+            .serialize()
+            .sparse_density(0.0)
+            .pcon(std::fs::File::create(temp.path())?)?;
+        let err = Counter::<u32>::open_indexed(temp.path()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::NotIndexed)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn binding_shim() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut file = vec![];
+        counter.clone().serialize_pcon(&mut file, 0)?;
+        assert_eq!(Counter::<u8>::deserialize(&file[..])?, counter);
+
+        // compressed stream is detect
+        let mut compressed = vec![];
+        {
+            let mut writer = niffler::get_writer(
+                Box::new(&mut compressed),
+                niffler::compression::Format::Gzip,
+                niffler::compression::Level::One,
+            )?;
+            std::io::Write::write_all(&mut writer, &file)?;
+        }
+        assert_eq!(Counter::<u8>::deserialize(&compressed[..])?, counter);
+
+        // count lower or equal than abundance are remove
+        let mut file = vec![];
+        counter.clone().serialize_pcon(&mut file, 1)?;
+        let filtered = Counter::<u8>::deserialize(&file[..])?;
+        for (count, kept) in counter.raw().iter().zip(filtered.raw().iter()) {
+            assert_eq!(*kept, if *count > 1 { *count } else { 0 });
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn little_endian() -> error::Result<()> {
+        // 258 = 0x0102, bytes order in file reveal endianness
+        let mut counter = Counter::<u16>::new(5);
+        counter.count_sequences(std::iter::repeat(b"AAAAA".to_vec()).take(258));
+        assert_eq!(counter.get_canonic(0), 258);
+
+        let mut raw = vec![];
+        counter.clone().serialize().raw(&mut raw)?;
+        assert_eq!(
+            &raw[serialize::RAW_HEADER_LEN..serialize::RAW_HEADER_LEN + 2],
+            &[0x02, 0x01]
+        );
+
+        let mut file = vec![];
+        counter.clone().serialize().pcon(&mut file)?;
+        assert_eq!(Counter::<u16>::from_stream(&file[..])?, counter);
+
+        Ok(())
+    }
+
+    #[test]
+    fn format_version() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut file = vec![];
+        counter.clone().serialize().pcon(&mut file)?;
+        assert_eq!(&file[..3], &[5, 1, FORMAT_VERSION]);
+        assert_eq!(Counter::<u8>::from_stream(&file[..])?, counter);
+
+        // legacy file have only kmer size and count width in header
+        let mut legacy = file[..2].to_vec();
+        legacy.extend(&file[3..]);
+        assert_eq!(Counter::<u8>::from_stream(&legacy[..])?, counter);
+
+        file[2] = FORMAT_VERSION + 1;
+        let err = Counter::<u8>::from_stream(&file[..]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::UnsupportedFormatVersion { got: 2 })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn count_reader() -> error::Result<()> {
+        let mut fasta = Counter::<u8>::new(5);
+        fasta.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(FASTA_FILE)?;
+        let compressed = encoder.finish()?;
+
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_reader(std::io::Cursor::new(compressed), cli::Format::Fasta, 1)?;
+        assert_eq!(counter, fasta);
+
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_reader(FASTA_FILE, cli::Format::Fasta, 1)?;
+        assert_eq!(counter, fasta);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_count_reader() -> error::Result<()> {
+        let mut fasta = Counter::<u8>::new(5);
+        fasta.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(FASTA_FILE)?;
+        let compressed = encoder.finish()?;
+
+        let mut counter = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        counter.count_reader(std::io::Cursor::new(compressed), cli::Format::Fasta, 1)?;
+        assert_eq!(counter.raw_noatomic(), fasta.raw_noatomic());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_index() -> error::Result<()> {
+        let mut counter = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        for kmer in 0..cocktail::kmer::get_kmer_space_size(5) {
+            assert_eq!(counter.index(kmer), counter.get(kmer));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_to_path() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut truth = Vec::new();
+        counter.clone().serialize().pcon(&mut truth)?;
+
+        let directory = tempfile::tempdir()?;
+        for name in ["count.pcon", "count.pcon.gz", "count.pcon.zst"] {
+            let path = directory.path().join(name);
+            counter
+                .clone()
+                .serialize_to_path(&path, 0, cli::DumpType::Pcon)?;
+
+            assert_eq!(std::fs::read(&path)?, truth);
+            assert_eq!(
+                Counter::<u8>::from_stream(std::fs::File::open(&path)?)?.raw(),
+                counter.raw()
+            );
+        }
+
+        let path = directory.path().join("count.raw.gz");
+        counter
+            .clone()
+            .serialize_to_path(&path, 0, cli::DumpType::Raw)?;
+        let mut truth = Vec::new();
+        counter.clone().serialize().raw(&mut truth)?;
+        assert_eq!(std::fs::read(&path)?, truth);
+
+        let path = directory.path().join("count.csv.gz");
+        counter
+            .clone()
+            .serialize_to_path(&path, 0, cli::DumpType::Csv)?;
+        let (mut input, _) = niffler::from_path(&path)?;
+        let mut csv = Vec::new();
+        input.read_to_end(&mut csv)?;
+        let mut truth = Vec::new();
+        counter.clone().serialize().csv(0, &mut truth)?;
+        assert_eq!(csv, truth);
+
+        let path = directory.path().join("count.solid");
+        counter
+            .clone()
+            .serialize_to_path(&path, 0, cli::DumpType::Solid)?;
+        assert_eq!(
+            solid::Solid::from_path(&path)?,
+            solid::Solid::from_count(5, counter.raw(), 0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_stream_into() -> error::Result<()> {
+        let mut counter = Counter::<u16>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut dense = vec![];
+        counter
+            .clone()
+            .serialize()
+            .sparse_density(0.0)
+            .pcon(&mut dense)?;
+        let mut sparse = vec![];
+        counter
+            .clone()
+            .serialize()
+            .sparse_density(1.0)
+            .pcon(&mut sparse)?;
+        assert_ne!(dense, sparse);
+
+        for file in [&dense, &sparse] {
+            let buffer = vec![u16::MAX; counter.raw().len()].into_boxed_slice();
+            let loaded = Counter::<u16>::from_stream_into(&file[..], buffer)?;
+
+            assert_eq!(loaded, Counter::<u16>::from_stream(&file[..])?);
+            assert_eq!(loaded, counter);
+        }
+
+        let err = Counter::<u16>::from_stream_into(&dense[..], vec![0u16; 10].into_boxed_slice())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::BufferSizeNotMatch {
+                expected: 512,
+                found: 10
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_from_stream_into() -> error::Result<()> {
+        let mut counter = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut file = vec![];
+        counter.serialize().sparse_density(0.0).pcon(&mut file)?;
+
+        let buffer = Counter::<std::sync::atomic::AtomicU8>::new(5).into_raw();
+        let loaded = Counter::<std::sync::atomic::AtomicU8>::from_stream_into(&file[..], buffer)?;
+
+        assert_eq!(loaded.raw_noatomic(), &TRUTH_COUNT_U8[..]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn kmer_size_out_of_range() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut file = vec![];
+        counter.serialize().pcon(&mut file)?;
+
+        for k in [0, 40] {
+            file[0] = k;
+            let err = Counter::<u8>::from_stream(&file[..]).unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<error::Error>(),
+                Some(error::Error::KmerSizeOutOfRange { k: value }) if *value == k
+            ));
+        }
+
+        let counter = Counter::<u8> {
+            k: 0,
+            count: vec![0u8; 1].into_boxed_slice(),
+        };
+        let err = counter.serialize().pcon(&mut vec![]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::KmerSizeOutOfRange { k: 0 })
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "gfa")]
+    const GFA_FILE: &[u8] = b"H\tVN:Z:1.0
+S\t1\tGTTCTGCAAATTAGAACAGACAATACACTGGCAGGCGTTGCGTTGGGGGAGATCTTCCGTAACGAGCCGGCATTTGTAAGAAAGAGATTTCGAGTAAATG
+S\t2\tAGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTATTACAGTGCCGCCTGTTGAAAGCCCCAATCCCGCTTCAATTGTTGAGCTCAG\tLN:i:100
+S\t3\t*
+L\t1\t+\t2\t+\t0M
+";
+
+    #[cfg(feature = "sam")]
+    const SAM_FILE: &[u8] = b"@HD\tVN:1.6\tSO:unsorted
+@SQ\tSN:ref\tLN:100
+r0\t0\tref\t1\t60\t100M\t*\t0\t0\tGTTCTGCAAATTAGAACAGACAATACACTGGCAGGCGTTGCGTTGGGGGAGATCTTCCGTAACGAGCCGGCATTTGTAAGAAAGAGATTTCGAGTAAATG\t*
+r1\t4\t*\t0\t0\t*\t*\t0\t0\tAGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTATTACAGTGCCGCCTGTTGAAAGCCCCAATCCCGCTTCAATTGTTGAGCTCAG\t*
+r0\t256\tref\t1\t60\t100M\t*\t0\t0\t*\t*
+r0\t2048\tref\t51\t60\t50H50M\t*\t0\t0\tGATCTTCCGTAACGAGCCGGCATTTGTAAGAAAGAGATTTCGAGTAAATG\t*
+";
+
+    fn fasta_sequences() -> Vec<Vec<u8>> {
+        FASTA_FILE
+            .split(|c| *c == b'\n')
+            .filter(|line| !line.is_empty() && line[0] != b'>')
+            .map(<[u8]>::to_vec)
+            .collect()
+    }
+
+    #[test]
+    fn count_sequences() -> error::Result<()> {
+        let mut fasta = Counter::<u8>::new(5);
+        fasta.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_sequences(vec![b"GTTCTGCAAATTAGAACAGACAATACACTGGCAGGCGTTGCGTTGGGGGAGATCTTCCGTAACGAGCCGGCATTTGTAAGAAAGAGATTTCGAGTAAATG".to_vec()]);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"GTTCT")), 2);
+
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_sequences(fasta_sequences());
+        assert_eq!(counter, fasta);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "gfa")]
+    #[test]
+    fn count_gfa() -> error::Result<()> {
+        let mut fasta = Counter::<u8>::new(5);
+        fasta.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut gfa = Counter::<u8>::new(5);
+        gfa.count_gfa(Box::new(GFA_FILE), 1)?;
+
+        assert_eq!(gfa, fasta);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sam")]
+    #[test]
+    fn count_sam() -> error::Result<()> {
+        let mut fasta = Counter::<u8>::new(5);
+        fasta.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        // second alignment is unmapped, secondary and supplementary alignments are skip
+        let mut sam = Counter::<u8>::new(5);
+        sam.count_sam(Box::new(SAM_FILE), 1)?;
+
+        assert_eq!(sam, fasta);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_pcon() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(13);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut dense = vec![];
+        counter
+            .clone()
+            .serialize()
+            .sparse_density(0.0)
+            .pcon(&mut dense)?;
+        assert_eq!(dense[0], 13);
+
+        let mut sparse = vec![];
+        counter.clone().serialize().pcon(&mut sparse)?;
+        assert_eq!(sparse[0], 13 | SPARSE_FLAG);
+
+        assert!(sparse.len() < dense.len());
+        assert_eq!(Counter::<u8>::from_stream(&sparse[..])?, counter);
+        assert_eq!(Counter::<u8>::from_stream(&dense[..])?, counter);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequential_report() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        assert_eq!(counter.total_kmers(), 192);
+        assert_eq!(counter.distinct_kmers(), 158);
+        assert_eq!(counter.distinct_kmers_above(1), 34);
+        assert_eq!(counter.max_count().map(|(_, count)| count), Some(2));
+
+        let mut report = vec![];
+        counter.write_report(&mut report, 1)?;
+        let report = String::from_utf8(report)?;
+
+        assert!(report.contains("k: 5\n"));
+        assert!(report.contains("count width: 1 bytes\n"));
+        assert!(report.contains("total kmers: 192\n"));
+        assert!(report.contains("distinct kmers: 158\n"));
+        assert!(report.contains("distinct kmers above 1: 34\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn top() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        // AACGC, AATAC and AATTA
+        assert_eq!(counter.top(3, 0), vec![(14, 2), (16, 2), (20, 2)]);
+        assert_eq!(counter.top(3, 1), vec![(14, 2), (16, 2), (20, 2)]);
+        assert!(counter.top(3, 2).is_empty());
+        assert!(counter.top(0, 0).is_empty());
+
+        assert_eq!(counter.top(1000, 0).len(), 158);
+        assert_eq!(counter.top(1000, 1).len(), 34);
+        assert_eq!(counter.top(1000, 0)[157], (511, 1));
+
+        // AATTG, AAGCT and ACAAT
+        assert_eq!(
+            counter.top_with(3, 0, |hash| hash % 2 == 1),
+            vec![(21, 2), (27, 2), (33, 2)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn auto_abundance() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        // spectrum decrease from count 1, no local minimum
+        assert_eq!(
+            counter.auto_abundance(spectrum::ThresholdMethod::FirstMinimum, 0.0, 255),
+            None
+        );
+        assert_eq!(
+            counter.auto_abundance(spectrum::ThresholdMethod::PercentAtLeast, 0.5, 255),
+            Some(1)
+        );
+
+        // each kmer is count 3 or 6 times, only absent kmer are below threshold
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(std::io::Cursor::new(FASTA_FILE.repeat(3))), 1)?;
+        let threshold = counter.auto_abundance(spectrum::ThresholdMethod::FirstMinimum, 0.0, 255);
+        assert_eq!(threshold, Some(2));
+        assert_eq!(
+            counter.distinct_kmers_above(threshold.unwrap_or(0)),
+            counter.distinct_kmers()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn ambiguous_break_kmer() {
+        let mut counter = Counter::<u8>::new(5);
+        counter
+            .count_fasta(Box::new(&b">ambiguous\nACGTANacgtaRACGTA\n"[..]), 1)
+            .unwrap();
+
+        assert_eq!(counter.total_kmers(), 3);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"ACGTA")), 3);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"GTANA")), 0);
+
+        let mut counter = Counter::<u8>::new(5);
+        counter
+            .count_fasta_with(
+                Box::new(&b">ambiguous\nACGTANacgtaRACGTA\n"[..]),
+                1,
+                &CountOptions {
+                    keep_ambiguous: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(counter.total_kmers(), 13);
+    }
+
+    #[test]
+    fn trailing_ambiguous() {
+        let mut counter = Counter::<u8>::new(5);
+        counter
+            .count_fasta(
+                Box::new(&b">1\nACGTACGNN\n>2\nacgtacgnn\n>3\nACGTN\n>4\nNN\n"[..]),
+                1,
+            )
+            .unwrap();
+
+        // only ACGTA, CGTAC and GTACG of first two records
+        assert_eq!(counter.total_kmers(), 6);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"ACGTA")), 2);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"CGTAC")), 2);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"GTACG")), 2);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"TACGN")), 0);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"ACGNN")), 0);
+
+        let mut sequences = Counter::<u8>::new(5);
+        sequences.count_sequences(vec![b"ACGTACGNN".to_vec(), b"acgtacgnn".to_vec()]);
+        assert_eq!(sequences.raw(), counter.raw());
+    }
+
+    #[test]
+    fn wrapped_fasta() {
+        let sequence = FASTA_FILE
+            .split(|c| *c == b'\n')
+            .filter(|line| !line.starts_with(b">"))
+            .flatten()
+            .copied()
+            .collect::<Vec<u8>>();
+
+        let mut unwrapped = b">long\n".to_vec();
+        unwrapped.extend(&sequence);
+        unwrapped.push(b'\n');
+
+        let mut wrapped = b">long\n".to_vec();
+        for line in sequence.chunks(60) {
+            wrapped.extend(line);
+            wrapped.push(b'\n');
+        }
+
+        let mut expected = Counter::<u8>::new(5);
+        expected
+            .count_fasta(Box::new(std::io::Cursor::new(unwrapped)), 1)
+            .unwrap();
+
+        let mut counter = Counter::<u8>::new(5);
+        counter
+            .count_fasta(Box::new(std::io::Cursor::new(wrapped)), 1)
+            .unwrap();
+
+        // kmer that overlap line break are count
+        assert_eq!(counter.total_kmers(), sequence.len() as u64 - 4);
+        assert_eq!(counter.raw(), expected.raw());
+    }
+
+    #[test]
+    fn sequential_prefetch() {
+        for k in [5, 11] {
+            let mut naive = Counter::<u8>::new(k);
+            naive.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+            let mut prefetch = Counter::<u8>::new(k);
+            prefetch
+                .count_fasta_with(
+                    Box::new(FASTA_FILE),
+                    1,
+                    &CountOptions {
+                        prefetch: true,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+
+            assert_eq!(prefetch.raw(), naive.raw());
+        }
+
+        // sequence shorter than prefetch window
+        let mut naive = Counter::<u8>::new(5);
+        naive.count_sequences(vec![b"ACGTACGTAC".to_vec()]);
+
+        let mut prefetch = Counter::<u8>::new(5);
+        prefetch.count_sequences_with(
+            vec![b"ACGTACGTAC".to_vec()],
+            &CountOptions {
+                prefetch: true,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(prefetch.raw(), naive.raw());
+    }
+
+    #[cfg(feature = "npz")]
+    #[test]
+    fn write_npz() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut output = std::io::Cursor::new(Vec::new());
+        counter.write_npz(&mut output)?;
+
+        let mut archive = zip::ZipArchive::new(output)?;
+        let mut names = archive.file_names().collect::<Vec<&str>>();
+        names.sort_unstable();
+        assert_eq!(names, vec!["counts.npy", "metadata.npy"]);
+
+        let mut counts = Vec::new();
+        archive.by_name("counts.npy")?.read_to_end(&mut counts)?;
+        assert_eq!(&counts[..6], b"\x93NUMPY");
+        let header_len = u16::from_le_bytes([counts[8], counts[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        assert_eq!(&counts[10 + header_len..], counter.raw());
+
+        let mut metadata = Vec::new();
+        archive
+            .by_name("metadata.npy")?
+            .read_to_end(&mut metadata)?;
+        let header_len = u16::from_le_bytes([metadata[8], metadata[9]]) as usize;
+        let values = metadata[10 + header_len..]
+            .chunks(8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect::<Vec<u64>>();
+        assert_eq!(values, vec![5, 1, counter.total_kmers()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_kind() -> error::Result<()> {
+        let mut counter = Counter::<u8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let temp = tempfile::NamedTempFile::new()?;
+        counter
+            .serialize()
+            .pcon(std::fs::File::create(temp.path())?)?;
+
+        let file = std::fs::OpenOptions::new().write(true).open(temp.path())?;
+        file.set_len(file.metadata()?.len() / 2)?;
+
+        let error = Counter::<u8>::from_stream(std::fs::File::open(temp.path())?).unwrap_err();
+        assert_eq!(error::kind_of(&error), Some(error::ErrorKind::Truncated));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_raw() -> error::Result<()> {
+        let mut raw = vec![0u8; cocktail::kmer::get_hash_space_size(5) as usize];
+        raw[(cocktail::kmer::seq2bit(b"AAAAA") >> 1) as usize] = 3;
+        raw[(cocktail::kmer::canonical(cocktail::kmer::seq2bit(b"ACGTT"), 5) >> 1) as usize] = 7;
+
+        let counter = Counter::<u8>::from_raw(5, raw.clone().into_boxed_slice())?;
+        assert_eq!(counter.k(), 5);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"AAAAA")), 3);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"TTTTT")), 3);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"ACGTT")), 7);
+        assert_eq!(counter.get(cocktail::kmer::seq2bit(b"CCCCC")), 0);
+        assert_eq!(counter.into_raw().to_vec(), raw);
+
+        let err = Counter::<u8>::from_raw(5, vec![0u8; 10].into_boxed_slice()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::BufferSizeNotMatch {
+                expected: 512,
+                found: 10
+            })
+        ));
+
+        assert!(Counter::<u8>::from_raw(0, vec![0u8; 1].into_boxed_slice()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequential_prefix_len() {
+        let mut full = Counter::<u8>::new(5);
+        full.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+        let mut prefix = Counter::<u8>::new(5);
+        prefix
+            .count_fasta_with(
+                Box::new(FASTA_FILE),
+                1,
+                &CountOptions {
+                    prefix_len: Some(10),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let full_total: u64 = full.raw().iter().map(|x| *x as u64).sum();
+        let prefix_total: u64 = prefix.raw().iter().map(|x| *x as u64).sum();
+
+        assert_eq!(prefix_total, 12);
+        assert!(prefix_total < full_total);
+    }
+
+    #[test]
+    fn sequential_min_record_len() {
+        // last record is longer than k but shorter than 20
+        let mut fasta = FASTA_FILE.to_vec();
+        fasta.extend_from_slice(b">short\nAAGATAATTC\n");
+
+        let mut full = Counter::<u8>::new(5);
+        full.count_fasta(Box::new(std::io::Cursor::new(fasta.clone())), 1)
+            .unwrap();
+
+        let mut filter = Counter::<u8>::new(5);
+        filter
+            .count_fasta_with(
+                Box::new(std::io::Cursor::new(fasta)),
+                1,
+                &CountOptions {
+                    min_record_len: Some(20),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let mut truth = Counter::<u8>::new(5);
+        truth.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+        assert_eq!(full.total_kmers(), truth.total_kmers() + 6);
+        assert_eq!(filter.raw(), truth.raw());
+    }
+
+    #[test]
+    fn validate_kmers() -> error::Result<()> {
+        let forward = &FASTA_FILE[14..114];
+        let reverse = utils::revcomp(forward);
+
+        assert!(CountOptions::check_canonical(forward, 5));
+        assert!(CountOptions::check_canonical(b"ACGTNacgtaRAC", 3));
+
+        let options = CountOptions {
+            validate_kmers: true,
+            ..Default::default()
+        };
+
+        let mut fasta = b">forward\n".to_vec();
+        fasta.extend_from_slice(forward);
+        fasta.extend_from_slice(b"\n>reverse\n");
+        fasta.extend_from_slice(&reverse);
+        fasta.push(b'\n');
+
+        let mut both = Counter::<u8>::new(5);
+        both.count_fasta_with(Box::new(std::io::Cursor::new(fasta)), 1, &options)?;
+
+        let mut single = Counter::<u8>::new(5);
+        single.count_sequences_with(vec![forward.to_vec()], &options);
+
+        // each canonical kmer of forward is count one more time by reverse complement
+        for (double, simple) in both.raw().iter().zip(single.raw().iter()) {
+            assert_eq!(*double, simple * 2);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sequential_stop() -> error::Result<()> {
+        static STOP: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+        let sequences = FASTA_FILE
+            .split(|c| *c == b'\n')
+            .filter(|line| !line.is_empty() && line[0] != b'>')
+            .map(|line| line.to_vec())
+            .collect::<Vec<Vec<u8>>>();
+
+        // flag is set when second record is read, like a signal receive in middle of count
+        let mut partial = Counter::<u8>::new(5);
+        partial.count_sequences_with(
+            sequences
+                .iter()
+                .chain(sequences.iter())
+                .enumerate()
+                .map(|(index, sequence)| {
+                    if index == 1 {
+                        STOP.store(true, std::sync::atomic::Ordering::Relaxed);
+                    }
+                    sequence.clone()
+                }),
+            &CountOptions {
+                stop: Some(&STOP),
+                ..Default::default()
+            },
+        );
+
+        let mut truth = Counter::<u8>::new(5);
+        truth.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        assert_eq!(partial, truth);
+
+        let mut file = vec![];
+        partial.clone().serialize().pcon(&mut file)?;
+        assert_eq!(Counter::<u8>::from_stream(&file[..])?, partial);
+
+        Ok(())
+    }
+
     #[cfg(feature = "fastq")]
     macro_rules! sequential_fastq {
         ($type:ty, $name:ident, $truth:ident) => {
@@ -404,7 +3152,7 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
             fn $name() {
                 let mut counter = Counter::<$type>::new(5);
 
-                counter.count_fastq(Box::new(FASTQ_FILE), 1);
+                counter.count_fastq(Box::new(FASTQ_FILE), 1).unwrap();
 
                 assert_eq!(*counter.get_raw(14), 2);
 
@@ -434,6 +3182,32 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
     #[cfg(feature = "fastq")]
     sequential_fastq!(u128, sequential_fastq_u128, TRUTH_COUNT_U128);
 
+    #[cfg(feature = "fastq")]
+    fn fastq_mates() -> (&'static [u8], &'static [u8]) {
+        let middle = FASTQ_FILE
+            .windows(13)
+            .position(|w| w == b"@random_seq 1")
+            .unwrap();
+
+        FASTQ_FILE.split_at(middle)
+    }
+
+    #[cfg(feature = "fastq")]
+    #[test]
+    fn count_fastq_pairs() -> error::Result<()> {
+        let (r1, r2) = fastq_mates();
+
+        let mut concatenated = Counter::<u8>::new(5);
+        concatenated.count_fastq(Box::new(FASTQ_FILE), 1)?;
+
+        let mut paired = Counter::<u8>::new(5);
+        paired.count_fastq_pairs(Box::new(r1), Box::new(r2), 1)?;
+
+        assert_eq!(paired, concatenated);
+
+        Ok(())
+    }
+
     macro_rules! sequential_serialize {
         ($type:ty, $failled_type:ty, $name:ident, $failled_name:ident) => {
             #[test]
@@ -441,7 +3215,7 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
                 let mut file = vec![];
 
                 let mut counter = Counter::<$type>::new(5);
-                counter.count_fasta(Box::new(FASTA_FILE), 1);
+                counter.count_fasta(Box::new(FASTA_FILE), 1)?;
 
                 let serialize = counter.clone().serialize();
                 serialize.pcon(std::io::Cursor::new(&mut file))?;
@@ -458,7 +3232,7 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
                 let mut file = vec![];
 
                 let mut counter = Counter::<$type>::new(5);
-                counter.count_fasta(Box::new(FASTA_FILE), 1);
+                counter.count_fasta(Box::new(FASTA_FILE), 1)?;
 
                 let serialize = counter.serialize();
                 serialize.pcon(std::io::Cursor::new(&mut file))?;
@@ -503,6 +3277,42 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
         failled_sequential_serialize_u128
     );
 
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn sequential_bincode() -> error::Result<()> {
+        let mut file = vec![];
+
+        let mut counter = Counter::<u16>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        counter.clone().serialize().bincode(&mut file)?;
+
+        let second_counter = Counter::<u16>::from_bincode(&file[..])?;
+
+        assert_eq!(counter, second_counter);
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "bincode", feature = "parallel"))]
+    #[test]
+    fn parallel_bincode() -> error::Result<()> {
+        let mut file = vec![];
+
+        let mut counter = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let expected = counter.raw_noatomic().to_vec();
+        counter.serialize().bincode(&mut file)?;
+
+        let second_counter = Counter::<std::sync::atomic::AtomicU8>::from_bincode(&file[..])?;
+
+        assert_eq!(second_counter.k(), 5);
+        assert_eq!(second_counter.raw_noatomic(), &expected[..]);
+
+        Ok(())
+    }
+
     #[cfg(feature = "parallel")]
     macro_rules! parallel_fasta {
         ($type:ty, $out_type:ty, $name:ident, $truth:ident) => {
@@ -510,7 +3320,7 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
             fn $name() {
                 let mut counter = Counter::<$type>::new(5);
 
-                counter.count_fasta(Box::new(FASTA_FILE), 1);
+                counter.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
 
                 assert_eq!(
                     counter
@@ -563,6 +3373,302 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
         TRUTH_COUNT_U64
     );
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn par_iter() {
+        let mut counter = Counter::<u16>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+        let total: u64 = counter.par_iter().map(|(_, count)| count as u64).sum();
+        assert_eq!(total, counter.total_kmers());
+        assert_eq!(counter.par_iter().count() as u64, counter.distinct_kmers());
+        assert!(counter
+            .par_iter()
+            .all(|(kmer, count)| counter.get(kmer) == count));
+
+        let mut atomic = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        atomic.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+        let total: u64 = atomic.par_iter().map(|(_, count)| count as u64).sum();
+        assert_eq!(total, atomic.total_kmers());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_report() -> error::Result<()> {
+        let mut counter = Counter::<std::sync::atomic::AtomicU16>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1)?;
+
+        let mut report = vec![];
+        counter.write_report(&mut report, 0)?;
+        let report = String::from_utf8(report)?;
+
+        assert!(report.contains("k: 5\n"));
+        assert!(report.contains("count width: 2 bytes\n"));
+        assert!(report.contains("total kmers: 192\n"));
+        assert!(report.contains("distinct kmers above 0: 158\n"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_prefix_len() {
+        let mut full = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        full.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+        let mut prefix = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        prefix
+            .count_fasta_with(
+                Box::new(FASTA_FILE),
+                1,
+                &CountOptions {
+                    prefix_len: Some(10),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let full_total: u64 = full.raw_noatomic().iter().map(|x| *x as u64).sum();
+        let prefix_total: u64 = prefix.raw_noatomic().iter().map(|x| *x as u64).sum();
+
+        assert_eq!(prefix_total, 12);
+        assert!(prefix_total < full_total);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_oversized_kmer() {
+        let mut counter = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        counter.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+        let oversized = cocktail::kmer::seq2bit(b"GTTCTGC");
+
+        assert_eq!(counter.try_get(oversized), None);
+        assert_eq!(counter.get(oversized), 0);
+        assert!(
+            counter
+                .try_get(cocktail::kmer::seq2bit(b"GTTCT"))
+                .unwrap_or(0)
+                > 0
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_relaxed() {
+        for sync_read in [true, false] {
+            let mut counter = Counter::<std::sync::atomic::AtomicU8>::new(5);
+            counter
+                .count_fasta_with(
+                    Box::new(FASTA_FILE),
+                    1,
+                    &CountOptions {
+                        relaxed: true,
+                        sync_read,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+
+            assert_eq!(counter.raw_noatomic(), &TRUTH_COUNT_U8[..]);
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_streaming() {
+        let mut fasta = Vec::new();
+        for _ in 0..10 {
+            fasta.extend_from_slice(FASTA_FILE);
+            fasta.push(b'\n');
+        }
+
+        let mut buffered = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        buffered
+            .count_fasta(Box::new(std::io::Cursor::new(fasta.clone())), 4)
+            .unwrap();
+
+        for local_count in [false, true] {
+            let mut streaming = Counter::<std::sync::atomic::AtomicU8>::new(5);
+            streaming
+                .count_fasta_with(
+                    Box::new(std::io::Cursor::new(fasta.clone())),
+                    4,
+                    &CountOptions {
+                        streaming: true,
+                        local_count,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+
+            assert_eq!(streaming.total_kmers(), buffered.total_kmers());
+            assert_eq!(streaming.raw_noatomic(), buffered.raw_noatomic());
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_count_sequences() {
+        let mut sequential = Counter::<u8>::new(5);
+        sequential.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+        let mut parallel = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        parallel.count_sequences(fasta_sequences());
+        assert_eq!(parallel.raw_noatomic(), sequential.raw_noatomic());
+
+        let mut parallel = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        parallel.count_sequences_with(
+            fasta_sequences(),
+            &CountOptions {
+                sync_read: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(parallel.raw_noatomic(), sequential.raw_noatomic());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_count_gfa() {
+        let mut sequential = Counter::<u8>::new(5);
+        sequential.count_gfa(Box::new(GFA_FILE), 1).unwrap();
+
+        let mut parallel = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        parallel.count_gfa(Box::new(GFA_FILE), 1).unwrap();
+
+        assert_eq!(parallel.total_kmers(), 192);
+        assert_eq!(parallel.raw_noatomic(), sequential.raw_noatomic());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_reuse_buffer() {
+        // long record followed by short one, reused sequence buffer must be clean
+        let mut fasta = Vec::new();
+        for i in 0..20 {
+            fasta.extend_from_slice(b">long\n");
+            fasta.extend_from_slice(&FASTA_FILE[14..114]);
+            fasta.extend_from_slice(b"\n>short\n");
+            fasta.extend_from_slice(&FASTA_FILE[14..(20 + i)]);
+            fasta.push(b'\n');
+        }
+
+        let mut sequential = Counter::<u8>::new(5);
+        sequential
+            .count_fasta(Box::new(std::io::Cursor::new(fasta.clone())), 1)
+            .unwrap();
+
+        for sync_read in [true, false] {
+            let mut parallel = Counter::<std::sync::atomic::AtomicU8>::new(5);
+            parallel
+                .count_fasta_with(
+                    Box::new(std::io::Cursor::new(fasta.clone())),
+                    3,
+                    &CountOptions {
+                        sync_read,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+
+            assert_eq!(parallel.raw_noatomic(), sequential.raw_noatomic());
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_shrink_buffer() {
+        // record longer than retained capacity, its buffer is shrink before reuse
+        let mut fasta = b">long\n".to_vec();
+        while fasta.len() < 2 * utils::MAX_RETAINED_CAPACITY {
+            fasta.extend_from_slice(&FASTA_FILE[14..114]);
+        }
+        for i in 0..10 {
+            fasta.extend_from_slice(b"\n>short\n");
+            fasta.extend_from_slice(&FASTA_FILE[14..(20 + i)]);
+        }
+        fasta.push(b'\n');
+
+        let mut sequential = Counter::<u32>::new(5);
+        sequential
+            .count_fasta(Box::new(std::io::Cursor::new(fasta.clone())), 1)
+            .unwrap();
+
+        for sync_read in [true, false] {
+            let mut parallel = Counter::<std::sync::atomic::AtomicU32>::new(5);
+            parallel
+                .count_fasta_with(
+                    Box::new(std::io::Cursor::new(fasta.clone())),
+                    2,
+                    &CountOptions {
+                        sync_read,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+
+            assert_eq!(parallel.raw_noatomic(), sequential.raw_noatomic());
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_pipeline() {
+        let mut fasta = Vec::new();
+        for _ in 0..50 {
+            fasta.extend_from_slice(FASTA_FILE);
+        }
+
+        let mut sequential = Counter::<u16>::new(5);
+        sequential
+            .count_fasta(Box::new(std::io::Cursor::new(fasta.clone())), 1)
+            .unwrap();
+
+        let mut sync = Counter::<std::sync::atomic::AtomicU16>::new(5);
+        sync.count_fasta_with(
+            Box::new(std::io::Cursor::new(fasta.clone())),
+            7,
+            &CountOptions {
+                sync_read: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut pipeline = Counter::<std::sync::atomic::AtomicU16>::new(5);
+        pipeline
+            .count_fasta(Box::new(std::io::Cursor::new(fasta)), 7)
+            .unwrap();
+
+        assert_eq!(pipeline.total_kmers(), 100 * 96);
+        assert_eq!(pipeline.raw_noatomic(), sync.raw_noatomic());
+        assert_eq!(pipeline.raw_noatomic(), sequential.raw_noatomic());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_local_count() {
+        let mut atomic = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        atomic.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+        let mut local = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        local
+            .count_fasta_with(
+                Box::new(FASTA_FILE),
+                1,
+                &CountOptions {
+                    local_count: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(local.raw_noatomic(), atomic.raw_noatomic());
+        assert_eq!(local.raw_noatomic(), TRUTH_COUNT_U8);
+    }
+
     #[cfg(feature = "parallel")]
     macro_rules! parallel_fastq {
         ($type:ty, $out_type:ty, $name:ident, $truth:ident) => {
@@ -570,7 +3676,7 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
             fn $name() {
                 let mut counter = Counter::<$type>::new(5);
 
-                counter.count_fastq(Box::new(FASTQ_FILE), 1);
+                counter.count_fastq(Box::new(FASTQ_FILE), 1).unwrap();
 
                 assert_eq!(
                     counter
@@ -623,6 +3729,19 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
         TRUTH_COUNT_U64
     );
 
+    #[cfg(all(feature = "parallel", feature = "fastq"))]
+    #[test]
+    fn parallel_count_fastq_pairs() -> error::Result<()> {
+        let (r1, r2) = fastq_mates();
+
+        let mut paired = Counter::<std::sync::atomic::AtomicU8>::new(5);
+        paired.count_fastq_pairs(Box::new(r1), Box::new(r2), 1)?;
+
+        assert_eq!(paired.raw_noatomic(), &TRUTH_COUNT_U8[..]);
+
+        Ok(())
+    }
+
     #[cfg(feature = "parallel")]
     macro_rules! parallel_serialize {
         ($type:ty, $out_type:ty, $failled_type:ty, $name:ident, $failled_name:ident) => {
@@ -632,14 +3751,14 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
 
                 {
                     let mut counter = Counter::<$type>::new(5);
-                    counter.count_fasta(Box::new(FASTA_FILE), 1);
+                    counter.count_fasta(Box::new(FASTA_FILE), 1)?;
 
                     let serialize = counter.serialize();
                     serialize.pcon(std::io::Cursor::new(&mut file))?;
                 }
 
                 let mut counter = Counter::<$type>::new(5);
-                counter.count_fasta(Box::new(FASTA_FILE), 1);
+                counter.count_fasta(Box::new(FASTA_FILE), 1)?;
 
                 let second_counter = Counter::<$type>::from_stream(&file[..])?;
 
@@ -656,7 +3775,7 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTAttACAGTGCCGCCTGTTGAAAGCCCCAATCCC
                 let mut file = vec![];
 
                 let mut counter = Counter::<$type>::new(5);
-                counter.count_fasta(Box::new(FASTA_FILE), 1);
+                counter.count_fasta(Box::new(FASTA_FILE), 1)?;
 
                 let serialize = counter.serialize();
                 serialize.pcon(std::io::Cursor::new(&mut file))?;
@@ -0,0 +1,123 @@
+//! Run coverage command
+
+/* std use */
+
+/* crate use */
+use anyhow::Context as _;
+
+/* project use */
+use crate::cli;
+use crate::counter;
+use crate::error;
+
+/// Run coverage, count type is read in header of input
+pub fn coverage(params: cli::Coverage) -> error::Result<()> {
+    log::info!("Start load count of {}", params.input().display());
+    let (width, input) = counter::read_width(std::io::BufReader::new(std::fs::File::open(
+        params.input(),
+    )?))
+    .with_context(|| format!("Can't load count of {}", params.input().display()))?;
+
+    match width {
+        cli::CountWidth::U8 => coverage_u8(&params, input),
+        cli::CountWidth::U16 => coverage_u16(&params, input),
+        cli::CountWidth::U32 => coverage_u32(&params, input),
+        cli::CountWidth::U64 => coverage_u64(&params, input),
+    }
+}
+
+macro_rules! impl_coverage {
+    ($name:ident, $type:ty) => {
+        /// Load `input` in a counter of `$type` and write coverage of each contig of reference
+        fn $name<R>(params: &cli::Coverage, input: R) -> error::Result<()>
+        where
+            R: std::io::Read,
+        {
+            let counter = counter::Counter::<$type>::from_stream(input)
+                .with_context(|| format!("Can't load count of {}", params.input().display()))?;
+            log::info!("End load count of {}", params.input().display());
+
+            let mut output = params.output()?;
+            let mut reader = noodles::fasta::Reader::new(params.reference()?);
+
+            log::info!("Start write coverage");
+            for record in reader.records() {
+                let record = record?;
+                let name = String::from_utf8_lossy(record.name().as_ref()).into_owned();
+
+                write_contig(
+                    &mut output,
+                    &name,
+                    record.sequence().as_ref(),
+                    params.window(),
+                    counter.k(),
+                    |kmer| counter.get(kmer) as u64,
+                )?;
+            }
+            log::info!("End write coverage");
+
+            Ok(())
+        }
+    };
+}
+
+#[cfg(feature = "parallel")]
+impl_coverage!(coverage_u8, std::sync::atomic::AtomicU8);
+#[cfg(feature = "parallel")]
+impl_coverage!(coverage_u16, std::sync::atomic::AtomicU16);
+#[cfg(feature = "parallel")]
+impl_coverage!(coverage_u32, std::sync::atomic::AtomicU32);
+#[cfg(feature = "parallel")]
+impl_coverage!(coverage_u64, std::sync::atomic::AtomicU64);
+
+#[cfg(not(feature = "parallel"))]
+impl_coverage!(coverage_u8, u8);
+#[cfg(not(feature = "parallel"))]
+impl_coverage!(coverage_u16, u16);
+#[cfg(not(feature = "parallel"))]
+impl_coverage!(coverage_u32, u32);
+#[cfg(not(feature = "parallel"))]
+impl_coverage!(coverage_u64, u64);
+
+/// Write one bedgraph row by window of `window` kmer start position of `sequence`, value is mean count of kmer,
+/// kmer with a base other than A, C, G or T have a count of 0. Sequence shorter than k produce no row
+fn write_contig<W, F>(
+    output: &mut W,
+    name: &str,
+    sequence: &[u8],
+    window: usize,
+    k: u8,
+    count: F,
+) -> error::Result<()>
+where
+    W: std::io::Write,
+    F: Fn(u64) -> u64,
+{
+    let counts = sequence
+        .windows(k as usize)
+        .map(|kmer| {
+            if kmer
+                .iter()
+                .all(|nuc| matches!(nuc, b'A' | b'C' | b'G' | b'T' | b'a' | b'c' | b'g' | b't'))
+            {
+                count(cocktail::kmer::seq2bit(kmer))
+            } else {
+                0
+            }
+        })
+        .collect::<Vec<u64>>();
+
+    for (index, chunk) in counts.chunks(window).enumerate() {
+        let start = index * window;
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}",
+            name,
+            start,
+            start + chunk.len(),
+            chunk.iter().sum::<u64>() as f64 / chunk.len() as f64
+        )?;
+    }
+
+    Ok(())
+}
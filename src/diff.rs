@@ -0,0 +1,116 @@
+//! Run diff command
+
+/* std use */
+
+/* crate use */
+use anyhow::Context as _;
+
+/* project use */
+use crate::cli;
+use crate::counter;
+use crate::error;
+use crate::utils;
+
+/// Run diff, count type is read in header of first input
+pub fn diff(params: cli::Diff) -> error::Result<()> {
+    let path = &params.inputs()[0];
+    let (width, input) = counter::read_width(std::io::BufReader::new(std::fs::File::open(path)?))
+        .with_context(|| format!("Can't load count of {}", path.display()))?;
+
+    match width {
+        cli::CountWidth::U8 => diff_u8(&params, input),
+        cli::CountWidth::U16 => diff_u16(&params, input),
+        cli::CountWidth::U32 => diff_u32(&params, input),
+        cli::CountWidth::U64 => diff_u64(&params, input),
+    }
+}
+
+macro_rules! impl_diff {
+    ($name:ident, $type:ty) => {
+        /// Load `first` and second input in counters of `$type` and compare them
+        fn $name<R>(params: &cli::Diff, first: R) -> error::Result<()>
+        where
+            R: std::io::Read,
+        {
+            let paths = params.inputs();
+
+            log::info!("Start load count of {}", paths[0].display());
+            let first = counter::Counter::<$type>::from_stream(first)
+                .with_context(|| format!("Can't load count of {}", paths[0].display()))?;
+            log::info!("End load count of {}", paths[0].display());
+
+            log::info!("Start load count of {}", paths[1].display());
+            let second = counter::Counter::<$type>::from_stream(std::io::BufReader::new(
+                std::fs::File::open(&paths[1])?,
+            ))
+            .with_context(|| format!("Can't load count of {}", paths[1].display()))?;
+            log::info!("End load count of {}", paths[1].display());
+
+            log::info!("Start compare count");
+            if params.report() {
+                let report = first
+                    .diff_report(&second)
+                    .with_context(|| format!("Can't compare {}", paths[1].display()))?;
+                report.write(params.output()?)?;
+            } else {
+                if first.k() != second.k() {
+                    return Err(error::Error::KmerSizeNotMatch {
+                        expected: first.k(),
+                        found: second.k(),
+                    })
+                    .with_context(|| format!("Can't compare {}", paths[1].display()));
+                }
+                write(
+                    params.output()?,
+                    first.k(),
+                    first.raw_noatomic(),
+                    second.raw_noatomic(),
+                )?;
+            }
+            log::info!("End compare count");
+
+            Ok(())
+        }
+    };
+}
+
+#[cfg(feature = "parallel")]
+impl_diff!(diff_u8, std::sync::atomic::AtomicU8);
+#[cfg(feature = "parallel")]
+impl_diff!(diff_u16, std::sync::atomic::AtomicU16);
+#[cfg(feature = "parallel")]
+impl_diff!(diff_u32, std::sync::atomic::AtomicU32);
+#[cfg(feature = "parallel")]
+impl_diff!(diff_u64, std::sync::atomic::AtomicU64);
+
+#[cfg(not(feature = "parallel"))]
+impl_diff!(diff_u8, u8);
+#[cfg(not(feature = "parallel"))]
+impl_diff!(diff_u16, u16);
+#[cfg(not(feature = "parallel"))]
+impl_diff!(diff_u32, u32);
+#[cfg(not(feature = "parallel"))]
+impl_diff!(diff_u64, u64);
+
+/// Write a csv with one line by kmer with a different count in `first` and `second`
+fn write<W, T>(mut output: W, k: u8, first: &[T], second: &[T]) -> error::Result<()>
+where
+    W: std::io::Write,
+    T: std::cmp::PartialEq + std::fmt::Display,
+{
+    writeln!(output, "kmer,first,second")?;
+
+    for (hash, (a, b)) in first.iter().zip(second.iter()).enumerate() {
+        if a != b {
+            writeln!(
+                output,
+                "{},{},{}",
+                cocktail::kmer::kmer2seq(utils::hash2kmer(hash as u64), k),
+                a,
+                b
+            )?;
+        }
+    }
+
+    Ok(())
+}
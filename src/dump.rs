@@ -3,39 +3,129 @@
 /* std use */
 
 /* crate use */
+use anyhow::Context as _;
 
 /* project use */
 use crate::cli;
 use crate::counter;
 use crate::error;
 
-/// Run dump
+/// Run dump, count type is read in header of first input
 pub fn dump(params: cli::Dump) -> error::Result<()> {
     log::info!("Start load count");
-    let counter = counter::Counter::<crate::CountType>::from_stream(params.input()?)?;
-    log::info!("End load count");
+    let mut inputs = params.inputs().into_iter();
+    let (name, input) = match inputs.next() {
+        Some((name, input)) => (name, input?),
+        None => return Ok(()),
+    };
 
-    let serialize = counter.serialize();
+    let (width, input) =
+        counter::read_width(input).with_context(|| format!("Can't load count of {}", name))?;
 
-    for (out_type, output) in params.outputs().into_iter() {
-        match out_type {
-            cli::DumpType::Pcon => {
-                log::info!("Start write count in pcon format");
-                serialize.pcon(output?)?;
-                log::info!("End write count in pcon format");
+    match width {
+        cli::CountWidth::U8 => dump_u8(&params, &name, input, inputs),
+        cli::CountWidth::U16 => dump_u16(&params, &name, input, inputs),
+        cli::CountWidth::U32 => dump_u32(&params, &name, input, inputs),
+        cli::CountWidth::U64 => dump_u64(&params, &name, input, inputs),
+    }
+}
+
+macro_rules! impl_dump {
+    ($name:ident, $type:ty, $out_type:ty) => {
+        /// Merge count of `first` and `inputs` in a counter of `$type` and write outputs
+        fn $name<R, I>(params: &cli::Dump, name: &str, first: R, inputs: I) -> error::Result<()>
+        where
+            R: std::io::Read,
+            I: Iterator<Item = (String, error::Result<Box<dyn std::io::BufRead>>)>,
+        {
+            let mut counter = counter::Counter::<$type>::from_stream(first)
+                .with_context(|| format!("Can't load count of {}", name))?;
+
+            for (name, input) in inputs {
+                let other = counter::Counter::<$type>::from_stream(input?)
+                    .with_context(|| format!("Can't load count of {}", name))?;
+                counter
+                    .merge(&other)
+                    .with_context(|| format!("Can't merge count of {}", name))?;
             }
-            cli::DumpType::Csv => {
-                log::info!("Start write count in csv format");
-                serialize.csv(params.abundance(), output?)?;
-                log::info!("End write count in csv format");
+            log::info!("End load count");
+
+            let abundance = <$out_type>::try_from(params.abundance()).unwrap_or(<$out_type>::MAX);
+
+            if params.stats_only() {
+                counter.write_report(std::io::stdout().lock(), abundance)?;
+
+                return Ok(());
             }
-            cli::DumpType::Solid => {
-                log::info!("Start write count in solid format");
-                serialize.solid(params.abundance(), output?)?;
-                log::info!("End write count in solid format");
+
+            let serialize = counter
+                .serialize()
+                .min_complexity(params.min_complexity())
+                .sort(params.sort());
+
+            if let Some(n) = params.top() {
+                log::info!("Start write {} most abundant kmer", n);
+                serialize.top(n, abundance, &params.columns(), std::io::stdout().lock())?;
+                log::info!("End write {} most abundant kmer", n);
+
+                return Ok(());
+            }
+
+            for (out_type, output) in params.outputs().into_iter() {
+                match out_type {
+                    cli::DumpType::Pcon => {
+                        log::info!("Start write count in pcon format");
+                        serialize.pcon(output?)?;
+                        log::info!("End write count in pcon format");
+                    }
+                    cli::DumpType::Csv => {
+                        log::info!("Start write count in csv format");
+                        serialize.csv_columns(abundance, &params.columns(), output?)?;
+                        log::info!("End write count in csv format");
+                    }
+                    cli::DumpType::Solid => {
+                        log::info!("Start write count in solid format");
+                        serialize.solid(abundance, output?)?;
+                        log::info!("End write count in solid format");
+                    }
+                    #[cfg(feature = "kff")]
+                    cli::DumpType::Kff => {
+                        log::info!("Start write count in kff format");
+                        serialize.kff(abundance, output?)?;
+                        log::info!("End write count in kff format");
+                    }
+                    cli::DumpType::Raw => {
+                        log::info!("Start write count in raw format");
+                        serialize.raw(output?)?;
+                        log::info!("End write count in raw format");
+                    }
+                    cli::DumpType::FastaCount => {
+                        log::info!("Start write count in fasta format");
+                        serialize.fasta_count(abundance, output?)?;
+                        log::info!("End write count in fasta format");
+                    }
+                }
             }
-        }
-    }
 
-    Ok(())
+            Ok(())
+        }
+    };
 }
+
+#[cfg(feature = "parallel")]
+impl_dump!(dump_u8, std::sync::atomic::AtomicU8, u8);
+#[cfg(feature = "parallel")]
+impl_dump!(dump_u16, std::sync::atomic::AtomicU16, u16);
+#[cfg(feature = "parallel")]
+impl_dump!(dump_u32, std::sync::atomic::AtomicU32, u32);
+#[cfg(feature = "parallel")]
+impl_dump!(dump_u64, std::sync::atomic::AtomicU64, u64);
+
+#[cfg(not(feature = "parallel"))]
+impl_dump!(dump_u8, u8, u8);
+#[cfg(not(feature = "parallel"))]
+impl_dump!(dump_u16, u16, u16);
+#[cfg(not(feature = "parallel"))]
+impl_dump!(dump_u32, u32, u32);
+#[cfg(not(feature = "parallel"))]
+impl_dump!(dump_u64, u64, u64);
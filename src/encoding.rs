@@ -0,0 +1,61 @@
+//! 2 bits encoding of DNA kmer, A -> 00, C -> 01, T -> 10, G -> 11, encoding is the same as [cocktail::kmer]
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+
+/// Convert a sequence in 2 bits representation, sequence length must be lower or equal than 32
+pub fn seq2bit(subseq: &[u8]) -> u64 {
+    cocktail::kmer::seq2bit(subseq)
+}
+
+/// Convert a kmer in 2 bits representation to a sequence
+pub fn bit2seq(kmer: u64, k: u8) -> String {
+    cocktail::kmer::kmer2seq(kmer, k)
+}
+
+/// Compute reverse complement of a kmer in 2 bits representation
+pub fn revcomp(kmer: u64, k: u8) -> u64 {
+    cocktail::kmer::revcomp(kmer, k)
+}
+
+/// Get the canonical form of a kmer, the minimum between kmer and its reverse complement
+pub fn canonical(kmer: u64, k: u8) -> u64 {
+    cocktail::kmer::canonical(kmer, k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seq2bit_() {
+        assert_eq!(seq2bit(b"ACTGA"), 0b0001101100);
+        assert_eq!(seq2bit(b"actga"), 0b0001101100);
+        assert_eq!(seq2bit(b"ACTGA"), cocktail::kmer::seq2bit(b"ACTGA"));
+    }
+
+    #[test]
+    fn bit2seq_() {
+        assert_eq!(bit2seq(0b0001101100, 5), "ACTGA");
+        assert_eq!(bit2seq(seq2bit(b"TTGCA"), 5), "TTGCA");
+    }
+
+    #[test]
+    fn revcomp_() {
+        assert_eq!(revcomp(seq2bit(b"ACTGA"), 5), seq2bit(b"TCAGT"));
+        assert_eq!(revcomp(revcomp(seq2bit(b"ACTGA"), 5), 5), seq2bit(b"ACTGA"));
+    }
+
+    #[test]
+    fn canonical_() {
+        assert_eq!(canonical(seq2bit(b"ACTGA"), 5), seq2bit(b"ACTGA"));
+        assert_eq!(canonical(seq2bit(b"TCAGT"), 5), seq2bit(b"ACTGA"));
+        assert_eq!(
+            canonical(seq2bit(b"TCAGT"), 5),
+            cocktail::kmer::canonical(seq2bit(b"TCAGT"), 5)
+        );
+    }
+}
@@ -24,14 +24,360 @@ pub enum Error {
     #[error(transparent)]
     IO(#[from] std::io::Error),
 
+    /// Error durring detection of compression or creation of a compressed stream
+    #[error(transparent)]
+    Niffler(#[from] niffler::Error),
+
+    /// Error durring writing of kff output
+    #[cfg(feature = "kff")]
+    #[error(transparent)]
+    Kff(#[from] kff::error::Error),
+
+    /// Error durring writing of npz output
+    #[cfg(feature = "npz")]
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
+
     /// Error if we can't convert a DumpTypeFromStr
     #[error("Can't convert {0} in DumpType")]
     DumpTypeFromStr(String),
 
     /// Error durring loading count type not match
-    #[error("Type use in counter not match file count")]
-    TypeNotMatch,
+    #[error("Type use in counter not match file count, expected {expected_bytes} bytes found {found_bytes} bytes")]
+    TypeNotMatch {
+        /// Size of count type of counter
+        expected_bytes: usize,
+        /// Size of count type in file
+        found_bytes: usize,
+    },
+
+    /// Error durring loading count, no count type match with file count size
+    #[error("Count on {0} bytes isn't supported")]
+    CountWidthNotSupported(u8),
+
+    /// Error durring merge of counter with different kmer size
+    #[error("Kmer size not match, expected {expected} found {found}")]
+    KmerSizeNotMatch {
+        /// Kmer size of counter
+        expected: u8,
+        /// Kmer size of other counter
+        found: u8,
+    },
+
+    /// Error durring loading or writing count, kmer size is zero or larger than [crate::counter::MAX_K]
+    #[error("Kmer size {k} isn't supported, kmer size must be between 1 and {max}", max = crate::counter::MAX_K)]
+    KmerSizeOutOfRange {
+        /// Kmer size read or write
+        k: u8,
+    },
+
+    /// Error durring loading count in a user buffer, buffer length doesn't match kmer size of file
+    #[error("Buffer length not match kmer size of file, expected {expected} found {found}")]
+    BufferSizeNotMatch {
+        /// Hash space size of file kmer size
+        expected: usize,
+        /// Length of buffer
+        found: usize,
+    },
+
+    /// Error durring loading count, file use a format version unknown by this version of pcon
+    #[error("pcon format version {got} isn't supported")]
+    UnsupportedFormatVersion {
+        /// Version read in file
+        got: u8,
+    },
+
+    /// Error durring opening of an indexed count, file doesn't contains a block index
+    #[error("pcon file doesn't contain a block index")]
+    NotIndexed,
+
+    /// Error if no kmer are count in input
+    #[error("No kmer count in input, input is empty or all records are shorter than k")]
+    EmptyInput,
+
+    /// Error durring parsing of a bed file
+    #[error("Bed line {0} is malformed, expected contig, start and end separate by tabulation")]
+    MalformedBed(usize),
+
+    /// Error durring parsing of a histogram csv
+    #[error("Histogram line {0} is malformed, expected count and freq separate by comma")]
+    MalformedHistogram(usize),
+
+    /// Error durring parsing of a previous csv count
+    #[error("Csv line {0} is malformed, expected kmer and count separate by comma")]
+    MalformedCsv(usize),
+
+    /// Error durring parsing of a sam alignment
+    #[error("Sam alignment {0} is malformed")]
+    MalformedSam(usize),
+
+    /// Error if parent directory of an output doesn't exist
+    #[error("Parent directory of output {} doesn't exist", .0.display())]
+    OutputDirectoryNotExist(std::path::PathBuf),
+
+    /// Error if a subcommand can't read an input format
+    #[error("Input format {0} isn't supported by {1}")]
+    FormatNotSupported(String, &'static str),
+
+    /// Error durring loading solid store in roaring format without roaring feature
+    #[error("Solid file use sparse representation, pcon must be build with roaring feature")]
+    SparseSolidNotSupported,
+}
+
+/// Stable category of an [Error], downstream tools should match on kind instead of error message
+#[derive(std::fmt::Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Logging system configuration failed
+    Log,
+
+    /// Thread pool build failed
+    ThreadPool,
+
+    /// Input or output failed
+    IoError,
+
+    /// Input end before all expected data are read
+    Truncated,
+
+    /// Detection of compression or creation of a compressed stream failed
+    Compression,
+
+    /// Writing of kff output failed
+    Kff,
+
+    /// Writing of npz output failed
+    Npz,
+
+    /// A parameter value is invalid
+    InvalidArgument,
+
+    /// Count type of counter and file doesn't match
+    TypeMismatch,
+
+    /// Kmer size of counter and file or of two counters doesn't match
+    KmerSizeMismatch,
+
+    /// Kmer size, count width, format version or input format isn't supported
+    Unsupported,
+
+    /// A line of a text input is malformed
+    MalformedInput,
+
+    /// No kmer are count
+    EmptyInput,
+
+    /// Parent directory of an output doesn't exist
+    MissingDirectory,
+}
+
+impl Error {
+    /// Get stable category of error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Log(_) => ErrorKind::Log,
+            #[cfg(feature = "parallel")]
+            Error::RayonThreadPool(_) => ErrorKind::ThreadPool,
+            Error::IO(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => {
+                ErrorKind::Truncated
+            }
+            Error::IO(_) => ErrorKind::IoError,
+            Error::Niffler(_) => ErrorKind::Compression,
+            #[cfg(feature = "kff")]
+            Error::Kff(_) => ErrorKind::Kff,
+            #[cfg(feature = "npz")]
+            Error::Zip(_) => ErrorKind::Npz,
+            Error::DumpTypeFromStr(_) => ErrorKind::InvalidArgument,
+            Error::TypeNotMatch { .. } => ErrorKind::TypeMismatch,
+            Error::CountWidthNotSupported(_) => ErrorKind::Unsupported,
+            Error::KmerSizeNotMatch { .. } => ErrorKind::KmerSizeMismatch,
+            Error::KmerSizeOutOfRange { .. } => ErrorKind::Unsupported,
+            Error::BufferSizeNotMatch { .. } => ErrorKind::InvalidArgument,
+            Error::UnsupportedFormatVersion { .. } => ErrorKind::Unsupported,
+            Error::NotIndexed => ErrorKind::Unsupported,
+            Error::EmptyInput => ErrorKind::EmptyInput,
+            Error::MalformedBed(_) => ErrorKind::MalformedInput,
+            Error::MalformedHistogram(_) => ErrorKind::MalformedInput,
+            Error::MalformedCsv(_) => ErrorKind::MalformedInput,
+            Error::MalformedSam(_) => ErrorKind::MalformedInput,
+            Error::OutputDirectoryNotExist(_) => ErrorKind::MissingDirectory,
+            Error::FormatNotSupported(_, _) => ErrorKind::Unsupported,
+            Error::SparseSolidNotSupported => ErrorKind::Unsupported,
+        }
+    }
+}
+
+/// Get kind of a pcon [Error] or of an IO error wrapped in `error`, None if `error` is neither
+pub fn kind_of(error: &anyhow::Error) -> Option<ErrorKind> {
+    if let Some(error) = error.downcast_ref::<Error>() {
+        return Some(error.kind());
+    }
+
+    error
+        .downcast_ref::<std::io::Error>()
+        .map(|error| match error.kind() {
+            std::io::ErrorKind::UnexpectedEof => ErrorKind::Truncated,
+            _ => ErrorKind::IoError,
+        })
 }
 
 /// Alias of result
 pub type Result<T> = anyhow::Result<T>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        for (error, message) in [
+            (
+                Error::DumpTypeFromStr("bam".to_string()),
+                "Can't convert bam in DumpType",
+            ),
+            (
+                Error::TypeNotMatch {
+                    expected_bytes: 1,
+                    found_bytes: 2,
+                },
+                "Type use in counter not match file count, expected 1 bytes found 2 bytes",
+            ),
+            (
+                Error::CountWidthNotSupported(3),
+                "Count on 3 bytes isn't supported",
+            ),
+            (
+                Error::KmerSizeNotMatch {
+                    expected: 5,
+                    found: 7,
+                },
+                "Kmer size not match, expected 5 found 7",
+            ),
+            (
+                Error::KmerSizeOutOfRange { k: 33 },
+                "Kmer size 33 isn't supported, kmer size must be between 1 and 32",
+            ),
+            (
+                Error::BufferSizeNotMatch {
+                    expected: 512,
+                    found: 10,
+                },
+                "Buffer length not match kmer size of file, expected 512 found 10",
+            ),
+            (
+                Error::UnsupportedFormatVersion { got: 9 },
+                "pcon format version 9 isn't supported",
+            ),
+            (Error::NotIndexed, "pcon file doesn't contain a block index"),
+            (
+                Error::EmptyInput,
+                "No kmer count in input, input is empty or all records are shorter than k",
+            ),
+            (
+                Error::MalformedBed(2),
+                "Bed line 2 is malformed, expected contig, start and end separate by tabulation",
+            ),
+            (
+                Error::MalformedHistogram(3),
+                "Histogram line 3 is malformed, expected count and freq separate by comma",
+            ),
+            (
+                Error::MalformedCsv(4),
+                "Csv line 4 is malformed, expected kmer and count separate by comma",
+            ),
+            (Error::MalformedSam(5), "Sam alignment 5 is malformed"),
+            (
+                Error::OutputDirectoryNotExist(std::path::PathBuf::from("missing/out.pcon")),
+                "Parent directory of output missing/out.pcon doesn't exist",
+            ),
+            (
+                Error::FormatNotSupported("gfa".to_string(), "mini-count"),
+                "Input format gfa isn't supported by mini-count",
+            ),
+            (
+                Error::SparseSolidNotSupported,
+                "Solid file use sparse representation, pcon must be build with roaring feature",
+            ),
+        ] {
+            assert_eq!(error.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn kind() {
+        for (error, kind) in [
+            (
+                Error::IO(std::io::Error::from(std::io::ErrorKind::NotFound)),
+                ErrorKind::IoError,
+            ),
+            (
+                Error::IO(std::io::Error::from(std::io::ErrorKind::UnexpectedEof)),
+                ErrorKind::Truncated,
+            ),
+            (
+                Error::DumpTypeFromStr("bam".to_string()),
+                ErrorKind::InvalidArgument,
+            ),
+            (
+                Error::TypeNotMatch {
+                    expected_bytes: 1,
+                    found_bytes: 2,
+                },
+                ErrorKind::TypeMismatch,
+            ),
+            (Error::CountWidthNotSupported(3), ErrorKind::Unsupported),
+            (
+                Error::KmerSizeNotMatch {
+                    expected: 5,
+                    found: 7,
+                },
+                ErrorKind::KmerSizeMismatch,
+            ),
+            (Error::KmerSizeOutOfRange { k: 33 }, ErrorKind::Unsupported),
+            (
+                Error::BufferSizeNotMatch {
+                    expected: 512,
+                    found: 10,
+                },
+                ErrorKind::InvalidArgument,
+            ),
+            (
+                Error::UnsupportedFormatVersion { got: 9 },
+                ErrorKind::Unsupported,
+            ),
+            (Error::NotIndexed, ErrorKind::Unsupported),
+            (Error::EmptyInput, ErrorKind::EmptyInput),
+            (Error::MalformedBed(2), ErrorKind::MalformedInput),
+            (Error::MalformedHistogram(3), ErrorKind::MalformedInput),
+            (Error::MalformedCsv(4), ErrorKind::MalformedInput),
+            (Error::MalformedSam(5), ErrorKind::MalformedInput),
+            (
+                Error::OutputDirectoryNotExist(std::path::PathBuf::from("missing/out.pcon")),
+                ErrorKind::MissingDirectory,
+            ),
+            (
+                Error::FormatNotSupported("gfa".to_string(), "mini-count"),
+                ErrorKind::Unsupported,
+            ),
+            (Error::SparseSolidNotSupported, ErrorKind::Unsupported),
+        ] {
+            assert_eq!(error.kind(), kind);
+        }
+    }
+
+    #[test]
+    fn kind_of_anyhow() {
+        let error: anyhow::Error = Error::EmptyInput.into();
+        assert_eq!(kind_of(&error), Some(ErrorKind::EmptyInput));
+
+        let error = error.context("Can't count input");
+        assert_eq!(kind_of(&error), Some(ErrorKind::EmptyInput));
+
+        let error: anyhow::Error = std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into();
+        assert_eq!(kind_of(&error), Some(ErrorKind::Truncated));
+
+        let error: anyhow::Error = std::io::Error::from(std::io::ErrorKind::NotFound).into();
+        assert_eq!(kind_of(&error), Some(ErrorKind::IoError));
+
+        assert_eq!(kind_of(&anyhow::anyhow!("other error")), None);
+    }
+}
@@ -0,0 +1,31 @@
+//! Run spectrum command
+
+/* std use */
+use std::io::Write as _;
+
+/* crate use */
+
+/* project use */
+use crate::cli;
+use crate::error;
+use crate::spectrum;
+
+/// Run spectrum
+pub fn histogram(params: cli::Spectrum) -> error::Result<()> {
+    let spectrum = spectrum::Spectrum::from_pcon_path(params.input(), params.max_count())?;
+    let mut output = params.output()?;
+
+    if params.peaks() {
+        writeln!(output, "type,count,freq")?;
+        for (count, freq) in spectrum.peaks() {
+            writeln!(output, "peak,{},{}", count, freq)?;
+        }
+        for (count, freq) in spectrum.valleys() {
+            writeln!(output, "valley,{},{}", count, freq)?;
+        }
+    } else {
+        spectrum.write_csv(output)?;
+    }
+
+    Ok(())
+}
@@ -12,18 +12,23 @@
 pub mod cli;
 pub mod count;
 pub mod counter;
+pub mod coverage;
+pub mod diff;
 pub mod dump;
+pub mod encoding;
 pub mod error;
+pub mod histogram;
+pub mod logger;
+pub mod matrix;
 pub mod minicount;
 pub mod minicounter;
 pub mod serialize;
+pub mod shared;
 pub mod solid;
 pub mod spectrum;
+pub mod sweep;
 mod utils;
 
-/// Define a const
-type ByteOrder = byteorder::LittleEndian; // WARNING IF YOU CHANGE THIS CHECK AND CHANGE SERIALIZE.RS
-
 cfg_if::cfg_if! {
     if #[cfg(all(feature = "count_u16", feature = "parallel"))] {
     /// Define count type
@@ -0,0 +1,100 @@
+//! Logger that write each log event as a JSON object
+
+/* std use */
+use std::io::Write as _;
+
+/* crate use */
+
+/* project use */
+
+/// Logger write in stderr one JSON object (timestamp, level, message) by log event
+pub struct JsonLogger {
+    module: String,
+    level: log::LevelFilter,
+}
+
+impl JsonLogger {
+    /// Create a new JsonLogger, only event of `module` with a level lower or equal than `level` are write
+    pub fn new(module: &str, level: log::LevelFilter) -> Self {
+        Self {
+            module: module.to_string(),
+            level,
+        }
+    }
+
+    /// Set JsonLogger as global logger
+    pub fn init(self) -> Result<(), log::SetLoggerError> {
+        log::set_max_level(self.level);
+        log::set_boxed_logger(Box::new(self))
+    }
+
+    /// Build JSON object associate to a record
+    fn format(record: &log::Record) -> serde_json::Value {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_secs_f64())
+            .unwrap_or(0.0);
+
+        serde_json::json!({
+            "timestamp": timestamp,
+            "level": record.level().as_str(),
+            "message": record.args().to_string(),
+        })
+    }
+}
+
+impl log::Log for JsonLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.level
+            && (metadata.target() == self.module
+                || metadata
+                    .target()
+                    .strip_prefix(&self.module)
+                    .map_or(false, |suffix| suffix.starts_with("::")))
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            let _ = writeln!(std::io::stderr().lock(), "{}", Self::format(record));
+        }
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stderr().flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /* project use */
+    use super::*;
+    use log::Log as _;
+
+    #[test]
+    fn enabled() {
+        let logger = JsonLogger::new("pcon", log::LevelFilter::Info);
+
+        let metadata = |target, level| log::Metadata::builder().target(target).level(level).build();
+
+        assert!(logger.enabled(&metadata("pcon", log::Level::Info)));
+        assert!(logger.enabled(&metadata("pcon::count", log::Level::Error)));
+        assert!(!logger.enabled(&metadata("pcon::count", log::Level::Debug)));
+        assert!(!logger.enabled(&metadata("pconx", log::Level::Info)));
+        assert!(!logger.enabled(&metadata("noodles", log::Level::Info)));
+    }
+
+    #[test]
+    fn format() {
+        let value = JsonLogger::format(
+            &log::Record::builder()
+                .args(format_args!("count \"{}\" kmer", 42))
+                .level(log::Level::Warn)
+                .target("pcon")
+                .build(),
+        );
+
+        assert_eq!(value["level"], "WARN");
+        assert_eq!(value["message"], "count \"42\" kmer");
+        assert!(value["timestamp"].is_f64());
+    }
+}
@@ -12,31 +12,60 @@ use clap::Parser as _;
 /* project use */
 use pcon::cli;
 use pcon::count;
+use pcon::coverage;
+use pcon::diff;
 use pcon::dump;
 use pcon::error;
+use pcon::histogram;
+use pcon::logger;
+use pcon::matrix;
 use pcon::minicount;
+use pcon::shared;
+use pcon::sweep;
 
 fn main() -> error::Result<()> {
     // parse cli
     let params = cli::Command::parse();
 
     // Setup logger
-    stderrlog::new()
-        .module(module_path!())
-        .quiet(params.quiet())
-        .verbosity(params.verbosity())
-        .timestamp(params.timestamp())
-        .init()
-        .context("stderrlog already create a logger")?;
+    if params.log_json() {
+        logger::JsonLogger::new(module_path!(), params.log_level())
+            .init()
+            .context("json logger already create a logger")?;
+    } else {
+        stderrlog::new()
+            .module(module_path!())
+            .quiet(params.quiet())
+            .verbosity(params.verbosity())
+            .timestamp(params.timestamp())
+            .init()
+            .context("stderrlog already create a logger")?;
+    }
 
     #[cfg(feature = "parallel")]
-    rayon::ThreadPoolBuilder::new()
-        .num_threads(params.threads())
-        .build_global()?;
+    if params.threads() == 1 {
+        // current thread is the only worker of pool, no thread are spawn and count run without rayon dispatch
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .use_current_thread()
+            .build_global()?;
+    } else {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(params.threads())
+            .build_global()?;
+    }
+
+    let seed = params.seed();
 
     match params.subcommand {
         cli::SubCommand::Count(params) => count::count(params),
-        cli::SubCommand::MiniCount(params) => minicount::minicount(params),
+        cli::SubCommand::MiniCount(params) => minicount::minicount(params, seed),
         cli::SubCommand::Dump(params) => dump::dump(params),
+        cli::SubCommand::Sweep(params) => sweep::sweep(params),
+        cli::SubCommand::Spectrum(params) => histogram::histogram(params),
+        cli::SubCommand::Matrix(params) => matrix::matrix(params),
+        cli::SubCommand::Diff(params) => diff::diff(params),
+        cli::SubCommand::Coverage(params) => coverage::coverage(params),
+        cli::SubCommand::Shared(params) => shared::shared(params),
     }
 }
@@ -0,0 +1,82 @@
+//! Run matrix command
+
+/* std use */
+
+/* crate use */
+use anyhow::Context as _;
+
+/* project use */
+use crate::cli;
+use crate::error;
+use crate::solid;
+use crate::utils;
+
+/// Run matrix
+pub fn matrix(params: cli::Matrix) -> error::Result<()> {
+    let mut names = Vec::new();
+    let mut solids: Vec<solid::Solid> = Vec::new();
+
+    for path in params.inputs() {
+        log::info!("Start load count of {}", path.display());
+        let solid = solid::Solid::from_pcon_path(path, params.abundance())
+            .with_context(|| format!("Can't load count of {}", path.display()))?;
+        log::info!("End load count of {}", path.display());
+
+        if let Some(first) = solids.first() {
+            if first.k() != solid.k() {
+                return Err(error::Error::KmerSizeNotMatch {
+                    expected: first.k(),
+                    found: solid.k(),
+                })
+                .with_context(|| format!("Can't add {} in matrix", path.display()));
+            }
+        }
+
+        names.push(
+            path.file_stem()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .to_string(),
+        );
+        solids.push(solid);
+    }
+
+    let mut union = match solids.first() {
+        Some(first) => first.clone(),
+        None => return Ok(()),
+    };
+    for other in &solids[1..] {
+        union.extend(other.clone());
+    }
+
+    log::info!("Start write matrix");
+    write(params.output()?, &names, &solids, &union)?;
+    log::info!("End write matrix");
+
+    Ok(())
+}
+
+/// Write a tsv with a header of sample name and one line by kmer solid in `union`
+fn write<W>(
+    mut output: W,
+    names: &[String],
+    solids: &[solid::Solid],
+    union: &solid::Solid,
+) -> error::Result<()>
+where
+    W: std::io::Write,
+{
+    writeln!(output, "kmer\t{}", names.join("\t"))?;
+
+    for hash in union.iter_solid() {
+        let canonical = utils::hash2kmer(hash);
+
+        write!(output, "{}", cocktail::kmer::kmer2seq(canonical, union.k()))?;
+        for solid in solids {
+            write!(output, "\t{}", solid.get_canonic(canonical) as u8)?;
+        }
+        writeln!(output)?;
+    }
+
+    Ok(())
+}
@@ -9,21 +9,32 @@ use crate::cli;
 use crate::error;
 use crate::minicounter;
 
-/// Run count
-pub fn minicount(params: cli::MiniCount) -> error::Result<()> {
+/// Run count, minimizer hash are salted with `seed`
+pub fn minicount(params: cli::MiniCount, seed: u64) -> error::Result<()> {
     log::info!("Start init counter");
     let mut counter = minicounter::MiniCounter::<crate::CountType, crate::CountTypeNoAtomic>::new(
         params.kmer_size(),
         params.minimizer_size(),
         params.abundance(),
+        seed,
     );
     log::info!("End init counter");
 
     log::info!("Start count kmer");
     match params.format() {
-        cli::Format::Fasta => counter.count_fasta(params.inputs()?, params.record_buffer()),
+        cli::Format::Fasta | cli::Format::Auto => {
+            counter.count_fasta(params.inputs()?, params.record_buffer())?
+        }
         #[cfg(feature = "fastq")]
-        cli::Format::Fastq => counter.count_fastq(params.inputs()?, params.record_buffer()),
+        cli::Format::Fastq => counter.count_fastq(params.inputs()?, params.record_buffer())?,
+        #[cfg(feature = "gfa")]
+        cli::Format::Gfa => {
+            return Err(error::Error::FormatNotSupported("gfa".to_string(), "mini-count").into())
+        }
+        #[cfg(feature = "sam")]
+        cli::Format::Sam => {
+            return Err(error::Error::FormatNotSupported("sam".to_string(), "mini-count").into())
+        }
     }
     log::info!("End count kmer");
 
@@ -34,7 +45,7 @@ pub fn minicount(params: cli::MiniCount) -> error::Result<()> {
                 counter.serialize(params.abundance(), output?)?;
                 log::info!("End write count in csv format");
             }
-            _ => log::error!("Only csv dump is available for minicount"),
+            _ => log::warn!("Only csv dump is available for minicount"),
         }
     }
 
@@ -10,12 +10,15 @@ use rayon::prelude::*;
 /* project use */
 use crate::counter;
 use crate::error;
+use crate::serialize;
 use crate::utils;
 
 /// A counter of kmer, count only if minimizer is present more than a threshold.
 #[derive(Clone, Eq, PartialEq, Debug, Default)]
 pub struct MiniCounter<T, U> {
     k: u64,
+    seed: u64,
+    canonical: bool,
     threshold: U,
     mini_count: counter::Counter<T>,
     kmer_count: rustc_hash::FxHashMap<Vec<u8>, U>,
@@ -38,6 +41,24 @@ where
         self.mini_count.k()
     }
 
+    /// Get seed use to salt minimizer hash
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// If `value` is true (default) a kmer and its reverse complement are count together, otherwise kmer are only
+    /// uppercase, must be set before count
+    pub fn canonical(mut self, value: bool) -> Self {
+        self.canonical = value;
+
+        self
+    }
+
+    /// Get if kmer are count in canonical form
+    pub fn is_canonical(&self) -> bool {
+        self.canonical
+    }
+
     /// Get mini_count at one index
     pub fn get_mini(&self, index: usize) -> &T {
         self.mini_count.get_raw(index)
@@ -53,6 +74,11 @@ where
         &self.kmer_count
     }
 
+    /// Convert minimizer counter in a [serialize::Serialize], minimizer profile can be write in pcon format
+    pub fn serialize_mini(self) -> serialize::Serialize<T> {
+        self.mini_count.serialize()
+    }
+
     /// Write minicounter result in csv
     pub fn serialize<W>(&self, abundance: U, mut output: W) -> error::Result<()>
     where
@@ -72,34 +98,73 @@ where
     }
 }
 
+/// Normalize kmer use as key of kmer count, uppercase and canonical if `canonical` is true
+fn normalize(kmer: &[u8], canonical: bool) -> Vec<u8> {
+    if canonical {
+        utils::canonical(kmer)
+    } else {
+        kmer.to_ascii_uppercase()
+    }
+}
+
+/// Salt `minimizer` hash with `seed`, multiplication by an odd constant keep salt bijective
+fn salt(minimizer: u64, seed: u64) -> u64 {
+    (minimizer ^ seed).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// Iterate over each kmer of `sequence` and its canonical minimizer
+///
+/// With a `seed` equal to 0 minimizer are choose by cocktail, otherwise minimizer is the mmer with
+/// the lowest salted hash
+fn minimizers<'a>(
+    sequence: &'a [u8],
+    k: u64,
+    m: u8,
+    seed: u64,
+) -> Box<dyn Iterator<Item = (&'a [u8], u64)> + 'a> {
+    if seed == 0 {
+        return Box::new(cocktail::tokenizer::MiniBstr::new(sequence, k, m));
+    }
+
+    let minis = cocktail::tokenizer::Canonical::new(sequence, m).collect::<Vec<u64>>();
+    let window = (k - m as u64 + 1) as usize;
+
+    Box::new((0..=(sequence.len() - k as usize)).map(move |start| {
+        let minimizer = minis[start..start + window]
+            .iter()
+            .min_by_key(|mini| salt(**mini, seed))
+            .copied()
+            .unwrap_or(0);
+
+        (&sequence[start..start + k as usize], minimizer)
+    }))
+}
+
 /*****************************/
 /* sequential implementation */
 /*****************************/
 macro_rules! impl_sequential (
     ($type:ty) => {
 	impl MiniCounter<$type, $type> {
-	    /// Create a new MiniCounter with kmer size equal to k and minimizer size equal to m
-	    pub fn new(k: u64, m: u8, threshold: $type) -> Self {
+	    /// Create a new MiniCounter with kmer size equal to k and minimizer size equal to m, minimizer hash are salted with seed
+	    pub fn new(k: u64, m: u8, threshold: $type, seed: u64) -> Self {
 		Self {
 		    k,
+		    seed,
+		    canonical: true,
 		    threshold,
 		    mini_count: counter::Counter::<$type>::new(m),
 		    kmer_count: rustc_hash::FxHashMap::default(),
 		}
 	    }
 
-	    /// Perform count on fasta input
-	    pub fn count_fasta(&mut self, fasta: Box<dyn std::io::BufRead>, _record_buffer: u64) {
+	    /// Perform count on fasta input, stop at first malformed record
+	    pub fn count_fasta(&mut self, fasta: Box<dyn std::io::BufRead>, _record_buffer: u64) -> error::Result<()> {
 		let mut reader = noodles::fasta::Reader::new(fasta);
-		let mut records = reader.records();
-
-		while let Some(Ok(record)) = records.next() {
+		for record in reader.records() {
+		    let record = record?;
 		    if record.sequence().len() >= self.k() as usize {
-			let minimizer = cocktail::tokenizer::MiniBstr::new(
-			    record.sequence().as_ref(),
-			    self.k(),
-			    self.m(),
-			);
+			let minimizer = minimizers(record.sequence().as_ref(), self.k(), self.m(), self.seed);
 
 			let mut prev_mini = None;
 			for (kmer, minimizer) in minimizer {
@@ -110,7 +175,7 @@ macro_rules! impl_sequential (
 				&mut self.mini_count,
 				&mut self.kmer_count,
 				minimizer as usize,
-				utils::canonical(kmer),
+				normalize(kmer, self.canonical),
 				self.threshold,
 			    );
 
@@ -118,21 +183,18 @@ macro_rules! impl_sequential (
 			}
 		    }
 		}
+
+		Ok(())
 	    }
 
 	    #[cfg(feature = "fastq")]
-	    /// Perform count on fastq input
-	    pub fn count_fastq(&mut self, fastq: Box<dyn std::io::BufRead>, _record_buffer: u64) {
+	    /// Perform count on fastq input, stop at first malformed record
+	    pub fn count_fastq(&mut self, fastq: Box<dyn std::io::BufRead>, _record_buffer: u64) -> error::Result<()> {
 		let mut reader = noodles::fastq::Reader::new(fastq);
-		let mut records = reader.records();
-
-		while let Some(Ok(record)) = records.next() {
+		for record in reader.records() {
+		    let record = record?;
 		    if record.sequence().len() >= self.k() as usize {
-			let minimizer = cocktail::tokenizer::MiniBstr::new(
-			    record.sequence().as_ref(),
-			    self.k(),
-			    self.m(),
-			);
+			let minimizer = minimizers(record.sequence().as_ref(), self.k(), self.m(), self.seed);
 
 			let mut prev_mini = None;
 			for (kmer, minimizer) in minimizer {
@@ -144,7 +206,7 @@ macro_rules! impl_sequential (
 				&mut self.mini_count,
 				&mut self.kmer_count,
 				minimizer as usize,
-				utils::canonical(kmer),
+				normalize(kmer, self.canonical),
 				self.threshold,
 			    );
 
@@ -152,6 +214,8 @@ macro_rules! impl_sequential (
 			}
 		    }
 		}
+
+		Ok(())
 	    }
 
 
@@ -173,7 +237,7 @@ macro_rules! impl_sequential (
 
 	    /// Get count of a kmer
 	    pub fn get(&self, kmer: &[u8]) -> &$type {
-		self.kmer_count.get(&utils::canonical(kmer)).unwrap_or(&0)
+		self.kmer_count.get(&normalize(kmer, self.canonical)).unwrap_or(&0)
 	    }
 	}
     }
@@ -189,18 +253,20 @@ impl_sequential!(u128);
 macro_rules! impl_atomic(
     ($type:ty, $out_type:ty, $max:expr) => {
 	impl MiniCounter<$type, $out_type> {
-	    /// Create a new kmer MiniCounter with kmer size equal to k and minimizer equal to m
-	    pub fn new(k: u64, m: u8, threshold: $out_type) -> Self {
+	    /// Create a new kmer MiniCounter with kmer size equal to k and minimizer equal to m, minimizer hash are salted with seed
+	    pub fn new(k: u64, m: u8, threshold: $out_type, seed: u64) -> Self {
 		Self {
 		    k,
+		    seed,
+		    canonical: true,
 		    threshold,
 		    mini_count: counter::Counter::<$type>::new(m),
 		    kmer_count: rustc_hash::FxHashMap::default(),
 		}
 	    }
 
-	    /// Perform count on fasta input
-	    pub fn count_fasta(&mut self, fasta: Box<dyn std::io::BufRead>, record_buffer: u64) {
+	    /// Perform count on fasta input, stop at first malformed record
+	    pub fn count_fasta(&mut self, fasta: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
 		let mut reader = noodles::fasta::Reader::new(fasta);
 		let mut iter = reader.records();
 		let mut records = Vec::with_capacity(record_buffer as usize);
@@ -208,52 +274,19 @@ macro_rules! impl_atomic(
 		let mut end = true;
 		while end {
 		    log::info!("Start populate buffer");
-		    end = utils::populate_buffer(&mut iter, &mut records, record_buffer);
+		    end = utils::populate_buffer(&mut iter, &mut records, record_buffer)?;
 		    log::info!("End populate buffer {}", records.len());
 
-		    let local = records.par_iter().map(|record| {
-			let mut values = std::collections::HashMap::new();
-
-			if record.sequence().len() >= self.k as usize {
-			    let minimizer = cocktail::tokenizer::MiniBstr::new(
-				record.sequence().as_ref(),
-				self.k(),
-				self.m(),
-			    );
-
-			    let mut prev_mini = None;
-			    for (kmer, mini) in minimizer {
-				if prev_mini != Some(mini) {
-				    Self::mini_inc(&self.mini_count.count, (mini >> 1) as usize);
-				}
-
-				let normalize = utils::canonical(kmer);
-				if self.mini_count.get(mini as u64) > self.threshold {
-
-				    values.entry(normalize).and_modify(|c: &mut $out_type| *c = c.saturating_add(1)).or_insert(1);
-				}
-				prev_mini = Some(mini);
-			    }
-			}
-
-			values
-		    }).reduce(|| std::collections::HashMap::new(), |mut a, b| {
-			for (k, v) in b.into_iter() {
-			    a.entry(k.to_vec()).and_modify(|c: &mut $out_type| *c = c.saturating_add(v)).or_insert(v);
-			}
-
-			a
-		    });
-
-		    for (k, v) in local.into_iter() {
-			self.kmer_count.entry(k.to_vec()).and_modify(|c: &mut $out_type| *c = c.saturating_add(v)).or_insert(v);
-		    }
+		    let local = self.count_buffer(&records, |record: &noodles::fasta::Record| record.sequence().as_ref());
+		    self.kmer_count = Self::merge(std::mem::take(&mut self.kmer_count), local);
 		}
+
+		Ok(())
 	    }
 
 	    #[cfg(feature = "fastq")]
-	    /// Perform count on fastq input
-	    pub fn count_fastq(&mut self, fastq: Box<dyn std::io::BufRead>, record_buffer: u64) {
+	    /// Perform count on fastq input, stop at first malformed record
+	    pub fn count_fastq(&mut self, fastq: Box<dyn std::io::BufRead>, record_buffer: u64) -> error::Result<()> {
 		let mut reader = noodles::fastq::Reader::new(fastq);
 		let mut iter = reader.records();
 		let mut records = Vec::with_capacity(record_buffer as usize);
@@ -261,46 +294,62 @@ macro_rules! impl_atomic(
 		let mut end = true;
 		while end {
 		    log::info!("Start populate buffer");
-		    end = utils::populate_bufferq(&mut iter, &mut records, record_buffer);
-			    log::info!("End populate buffer {}", records.len());
+		    end = utils::populate_bufferq(&mut iter, &mut records, record_buffer)?;
+		    log::info!("End populate buffer {}", records.len());
 
-		    let local = records.par_iter().map(|record| {
-			let mut values = std::collections::HashMap::new();
+		    let local = self.count_buffer(&records, |record: &noodles::fastq::Record| record.sequence().as_ref());
+		    self.kmer_count = Self::merge(std::mem::take(&mut self.kmer_count), local);
+		}
 
-			if record.sequence().len() >= self.k as usize {
-			    let minimizer = cocktail::tokenizer::MiniBstr::new(
-				record.sequence().as_ref(),
-				self.k(),
-				self.m(),
-			    );
+		Ok(())
+	    }
+
+	    /// Count kmer of a buffer of records, each worker fill its own map, maps are merged at end
+	    fn count_buffer<R, F>(&self, records: &[R], sequence: F) -> rustc_hash::FxHashMap<Vec<u8>, $out_type>
+	    where
+		R: Sync,
+		F: Fn(&R) -> &[u8] + Sync,
+	    {
+		records.par_iter().fold(rustc_hash::FxHashMap::default, |mut values, record| {
+		    let sequence = sequence(record);
+
+		    if sequence.len() >= self.k as usize {
+			let minimizer = minimizers(sequence, self.k(), self.m(), self.seed);
 
-			    let mut prev_mini = None;
-			    for (kmer, mini) in minimizer {
-				if prev_mini != Some(mini) {
-				    Self::mini_inc(&self.mini_count.count, (mini >> 1) as usize);
-				}
-
-				let normalize = utils::canonical(kmer);
-				if self.mini_count.get(mini as u64) > self.threshold {
-				    values.entry(normalize).and_modify(|c: &mut $out_type| *c = c.saturating_add(1)).or_insert(1);
-				}
-				prev_mini = Some(mini);
+			let mut prev_mini = None;
+			for (kmer, mini) in minimizer {
+			    if prev_mini != Some(mini) {
+				Self::mini_inc(&self.mini_count.count, (mini >> 1) as usize);
 			    }
-			}
 
-			values
-		    }).reduce(|| std::collections::HashMap::new(), |mut a, b| {
-			for (k, v) in b.into_iter() {
-			    a.entry(k).and_modify(|c: &mut $out_type| *c = c.saturating_add(v)).or_insert(v);
+			    if self.mini_count.get(mini as u64) > self.threshold {
+				values
+				    .entry(normalize(kmer, self.canonical))
+				    .and_modify(|c: &mut $out_type| *c = c.saturating_add(1))
+				    .or_insert(1);
+			    }
+			    prev_mini = Some(mini);
 			}
+		    }
 
-			a
-		    });
+		    values
+		}).reduce(rustc_hash::FxHashMap::default, Self::merge)
+	    }
 
-		    for (k, v) in local.into_iter() {
-			self.kmer_count.entry(k).and_modify(|c: &mut $out_type| *c = c.saturating_add(v)).or_insert(v);
-		    }
+	    /// Merge two kmer count, smallest map is move in largest one
+	    fn merge(
+		mut a: rustc_hash::FxHashMap<Vec<u8>, $out_type>,
+		mut b: rustc_hash::FxHashMap<Vec<u8>, $out_type>,
+	    ) -> rustc_hash::FxHashMap<Vec<u8>, $out_type> {
+		if a.len() < b.len() {
+		    std::mem::swap(&mut a, &mut b);
+		}
+
+		for (kmer, count) in b {
+		    a.entry(kmer).and_modify(|c| *c = c.saturating_add(count)).or_insert(count);
 		}
+
+		a
 	    }
 
 	    /// Increment value at index
@@ -312,7 +361,7 @@ macro_rules! impl_atomic(
 
 	    /// Get count of a kmer
 	    pub fn get(&self, kmer: &[u8]) -> $out_type {
-		*self.kmer_count.get(&utils::canonical(kmer)).unwrap_or(&0)
+		*self.kmer_count.get(&normalize(kmer, self.canonical)).unwrap_or(&0)
 	    }
 	}
     }
@@ -392,9 +441,9 @@ AAGATAATTC
         ($type:ty, $name:ident, $truth:ident) => {
             #[test]
             fn $name() {
-                let mut mini_count = MiniCounter::<$type, $type>::new(10, 5, 1);
+                let mut mini_count = MiniCounter::<$type, $type>::new(10, 5, 1, 0);
 
-                mini_count.count_fasta(Box::new(FASTA_FILE), 1);
+                mini_count.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
 
                 assert_eq!(mini_count.mini_raw(), $truth);
 
@@ -430,14 +479,76 @@ AAGATAATTC
     sequential_fasta!(u64, sequential_fasta_u64, TRUTH_COUNT_U64);
     sequential_fasta!(u128, sequential_fasta_u128, TRUTH_COUNT_U128);
 
+    #[test]
+    fn serialize_mini() -> error::Result<()> {
+        let mut mini_count = MiniCounter::<u8, u8>::new(10, 5, 1, 0);
+        mini_count.count_fasta(Box::new(FASTA_FILE), 1)?;
+        let mini_raw = mini_count.mini_raw().to_vec();
+
+        let mut output = Vec::new();
+        mini_count.serialize_mini().pcon(&mut output)?;
+
+        let reload = counter::Counter::<u8>::from_stream(&output[..])?;
+        assert_eq!(reload.k(), 5);
+        assert_eq!(reload.raw(), mini_raw.as_slice());
+        assert_eq!(reload.raw(), TRUTH_COUNT_U8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn seed() {
+        let mut first = MiniCounter::<u8, u8>::new(10, 5, 0, 1);
+        first.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+        let mut second = MiniCounter::<u8, u8>::new(10, 5, 0, 0x5555_5555_5555_5555);
+        second.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
+
+        assert_eq!(first.seed(), 1);
+        assert_ne!(first.mini_raw(), second.mini_raw());
+
+        let total = |counter: &MiniCounter<u8, u8>| {
+            counter
+                .kmer_raw()
+                .values()
+                .map(|count| *count as u64)
+                .sum::<u64>()
+        };
+        assert_eq!(total(&first), 183);
+        assert_eq!(total(&first), total(&second));
+    }
+
+    #[test]
+    fn canonical() {
+        let fasta = b">forward\nGTTCTGCAAATTAGAACAGACAATAC\n>reverse\nGTATTGTCTGTTCTAATTTGCAGAAC\n";
+
+        let mut canonical = MiniCounter::<u8, u8>::new(10, 5, 0, 0);
+        canonical.count_fasta(Box::new(&fasta[..]), 1).unwrap();
+
+        assert!(canonical.is_canonical());
+        assert_eq!(canonical.kmer_raw().len(), 17);
+        assert!(canonical.kmer_raw().values().all(|count| *count == 2));
+        assert_eq!(*canonical.get(b"GTTCTGCAAA"), 2);
+        assert_eq!(*canonical.get(b"TTTGCAGAAC"), 2);
+
+        let mut forward = MiniCounter::<u8, u8>::new(10, 5, 0, 0).canonical(false);
+        forward.count_fasta(Box::new(&fasta[..]), 1).unwrap();
+
+        assert!(!forward.is_canonical());
+        assert_eq!(forward.kmer_raw().len(), 34);
+        assert_eq!(*forward.get(b"GTTCTGCAAA"), 1);
+        assert_eq!(*forward.get(b"gttctgcaaa"), 1);
+        assert_eq!(*forward.get(b"TTTGCAGAAC"), 1);
+    }
+
     #[cfg(feature = "fastq")]
     macro_rules! sequential_fastq {
         ($type:ty, $name:ident, $truth:ident) => {
             #[test]
             fn $name() {
-                let mut mini_count = MiniCounter::<$type, $type>::new(10, 5, 1);
+                let mut mini_count = MiniCounter::<$type, $type>::new(10, 5, 1, 0);
 
-                mini_count.count_fastq(Box::new(FASTQ_FILE), 1);
+                mini_count.count_fastq(Box::new(FASTQ_FILE), 1).unwrap();
 
                 assert_eq!(mini_count.mini_raw(), $truth);
 
@@ -483,9 +594,9 @@ AAGATAATTC
         ($type:ty, $out_type:ty, $name:ident, $truth:ident) => {
             #[test]
             fn $name() {
-                let mut mini_count = MiniCounter::<$type, $out_type>::new(10, 5, 1);
+                let mut mini_count = MiniCounter::<$type, $out_type>::new(10, 5, 1, 0);
 
-                mini_count.count_fasta(Box::new(FASTA_FILE), 1);
+                mini_count.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
 
                 assert_eq!(
                     utils::transmute::<$type, $out_type>(mini_count.mini_raw()),
@@ -547,14 +658,41 @@ AAGATAATTC
         TRUTH_COUNT_U64
     );
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_reduction() {
+        let mut fasta = Vec::new();
+        for _ in 0..20 {
+            fasta.extend_from_slice(FASTA_FILE);
+            fasta.push(b'\n');
+        }
+
+        // with threshold 0 every kmer is keep, result doesn't depend on record order
+        let mut sequential = MiniCounter::<u32, u32>::new(10, 5, 0, 0);
+        sequential.count_fasta(Box::new(&fasta[..]), 1).unwrap();
+
+        for record_buffer in [1, 7, 64] {
+            let mut parallel = MiniCounter::<std::sync::atomic::AtomicU32, u32>::new(10, 5, 0, 0);
+            parallel
+                .count_fasta(Box::new(std::io::Cursor::new(fasta.clone())), record_buffer)
+                .unwrap();
+
+            assert_eq!(
+                utils::transmute::<std::sync::atomic::AtomicU32, u32>(parallel.mini_raw()),
+                sequential.mini_raw()
+            );
+            assert_eq!(parallel.kmer_raw(), sequential.kmer_raw());
+        }
+    }
+
     #[cfg(all(feature = "parallel", feature = "fastq"))]
     macro_rules! parallel_fastq {
         ($type:ty, $out_type:ty, $name:ident, $truth:ident) => {
             #[test]
             fn $name() {
-                let mut mini_count = MiniCounter::<$type, $out_type>::new(10, 5, 1);
+                let mut mini_count = MiniCounter::<$type, $out_type>::new(10, 5, 1, 0);
 
-                mini_count.count_fastq(Box::new(FASTQ_FILE), 1);
+                mini_count.count_fastq(Box::new(FASTQ_FILE), 1).unwrap();
 
                 assert_eq!(
                     utils::transmute::<$type, $out_type>(mini_count.mini_raw()),
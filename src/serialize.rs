@@ -1,12 +1,13 @@
 //! Tools to serialize a Counter
+//!
+//! Integer of binary outputs (count, index, header length) are always little endian whatever host endianness, they are
+//! write with `to_le_bytes` and read with `from_le_bytes` or [byteorder::LittleEndian]
 
 /* std use */
 
 use std::io::Write as _;
 
 /* crate use */
-use byteorder::WriteBytesExt as _;
-
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
@@ -17,18 +18,224 @@ use kff;
 use crate::counter;
 use crate::error;
 use crate::solid;
-#[cfg(feature = "parallel")]
 use crate::utils;
 
+/// Column available in csv output
+#[derive(Copy, Clone, Eq, Debug, PartialEq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Column {
+    /// Canonical kmer
+    Kmer,
+
+    /// Count of kmer
+    Count,
+
+    /// Reverse complement of canonical kmer
+    Revcomp,
+
+    /// Index of kmer in count array
+    HashIndex,
+
+    /// Canonical kmer in 2 bits representation, a stable 64 bits hash of kmer
+    Hash,
+}
+
+/// Default columns of csv output
+pub const DEFAULT_COLUMNS: &[Column] = &[Column::Kmer, Column::Count];
+
+/// Order of csv output rows
+#[derive(Copy, Clone, Eq, Debug, PartialEq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Sort {
+    /// Higher count first, tie are order by kmer hash
+    CountDesc,
+
+    /// Lower count first, tie are order by kmer hash
+    CountAsc,
+
+    /// Alphabetical order of canonical kmer
+    Kmer,
+}
+
+/// Write one csv row of kmer at index `hash` with value of each `columns`
+fn write_row<W, C>(
+    output: &mut W,
+    columns: &[Column],
+    k: u8,
+    hash: u64,
+    count: C,
+) -> error::Result<()>
+where
+    W: std::io::Write,
+    C: std::fmt::Display,
+{
+    let kmer = cocktail::kmer::kmer2seq(utils::hash2kmer(hash), k);
+
+    let fields = columns
+        .iter()
+        .map(|column| match column {
+            Column::Kmer => kmer.clone(),
+            Column::Count => count.to_string(),
+            Column::Revcomp => {
+                String::from_utf8_lossy(&utils::revcomp(kmer.as_bytes())).into_owned()
+            }
+            Column::HashIndex => hash.to_string(),
+            Column::Hash => utils::hash2kmer(hash).to_string(),
+        })
+        .collect::<Vec<String>>();
+
+    writeln!(output, "{}", fields.join(","))?;
+
+    Ok(())
+}
+
+/// Write kmer at index `hash` as a fasta record, `count` is write in header `>count_N`
+fn write_fasta_record<W, C>(output: &mut W, k: u8, hash: u64, count: C) -> error::Result<()>
+where
+    W: std::io::Write,
+    C: std::fmt::Display,
+{
+    writeln!(
+        output,
+        ">count_{}\n{}",
+        count,
+        cocktail::kmer::kmer2seq(utils::hash2kmer(hash), k)
+    )?;
+
+    Ok(())
+}
+
+/// Sort `rows` of `(hash, count)` by `sort` and write them in csv
+fn write_sorted<W, C>(
+    output: &mut W,
+    columns: &[Column],
+    k: u8,
+    mut rows: Vec<(u64, C)>,
+    sort: Sort,
+) -> error::Result<()>
+where
+    W: std::io::Write,
+    C: std::fmt::Display + std::cmp::Ord + std::marker::Copy,
+{
+    match sort {
+        Sort::CountDesc => rows.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0))),
+        Sort::CountAsc => rows.sort_unstable_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0))),
+        Sort::Kmer => rows
+            .sort_by_cached_key(|(hash, _)| cocktail::kmer::kmer2seq(utils::hash2kmer(*hash), k)),
+    }
+
+    for (hash, count) in rows {
+        write_row(output, columns, k, hash, count)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "parallel")]
+/// Number of count format in csv by a parallel task
+const CSV_CHUNK_SIZE: usize = 1 << 16;
+
+/// Below this ratio of non zero count on hash space size, pcon output store only `(index, count)` of non zero count
+pub const SPARSE_DENSITY: f64 = 0.05;
+
+/// Length of raw output header, k and count width in bytes followed by zero padding, count array start aligned on
+/// 8 bytes
+pub const RAW_HEADER_LEN: usize = 8;
+
+/// Choose compression of output from extension of `path`, `.gz`, `.zst`, `.bz2` and `.xz` are compressed, other
+/// path aren't
+pub fn codec_from_path<P>(path: P) -> solid::Codec
+where
+    P: std::convert::AsRef<std::path::Path>,
+{
+    match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+        Some("gz") => solid::Codec::Gzip,
+        Some("zst") => solid::Codec::Zstd,
+        Some("bz2") => solid::Codec::Bzip,
+        Some("xz") => solid::Codec::Lzma,
+        _ => solid::Codec::No,
+    }
+}
+
 /// Struct to serialize counter
 pub struct Serialize<T> {
     counter: counter::Counter<T>,
+    min_complexity: f64,
+    sparse_density: f64,
+    sort: Option<Sort>,
 }
 
 impl<T> Serialize<T> {
     /// Create a new Serialize from a Counter
     pub fn new(counter: counter::Counter<T>) -> Self {
-        Self { counter }
+        Self {
+            counter,
+            min_complexity: 0.0,
+            sparse_density: SPARSE_DENSITY,
+            sort: None,
+        }
+    }
+
+    /// Counter with a ratio of non zero count on hash space size lower than `value` are write in sparse
+    /// pcon format, default value [SPARSE_DENSITY], 0.0 always write dense format
+    pub fn sparse_density(mut self, value: f64) -> Self {
+        self.sparse_density = value;
+
+        self
+    }
+
+    /// Return true if counter should be write in sparse pcon format
+    fn is_sparse(&self, distinct: u64) -> bool {
+        (distinct as f64)
+            < self.sparse_density * cocktail::kmer::get_hash_space_size(self.counter.k()) as f64
+    }
+
+    /// Kmer with a complexity lower than `value` are ignored in csv, top and solid output, see [utils::complexity]
+    pub fn min_complexity(mut self, value: f64) -> Self {
+        self.min_complexity = value;
+
+        self
+    }
+
+    /// Order of csv rows, with None (default) rows follow kmer hash, otherwise all rows are store in memory before write
+    pub fn sort(mut self, value: Option<Sort>) -> Self {
+        self.sort = value;
+
+        self
+    }
+
+    /// Return true if kmer at index `hash` have a complexity upper or equal to min_complexity
+    fn is_complex(&self, hash: u64) -> bool {
+        self.min_complexity <= 0.0
+            || utils::complexity(
+                cocktail::kmer::kmer2seq(utils::hash2kmer(hash), self.counter.k()).as_bytes(),
+            ) >= self.min_complexity
+    }
+
+    /// Unset kmer of `solid` with a complexity lower than min_complexity
+    fn remove_low_complexity(&self, solid: &mut solid::Solid) {
+        if self.min_complexity <= 0.0 {
+            return;
+        }
+
+        for hash in 0..cocktail::kmer::get_hash_space_size(self.counter.k()) {
+            let canonical = utils::hash2kmer(hash);
+            if solid.get_canonic(canonical) && !self.is_complex(hash) {
+                solid.set_canonic(canonical, false);
+            }
+        }
+    }
+
+    #[cfg(feature = "bincode")]
+    /// Write the whole counter, k included, in bincode format
+    ///
+    /// Unlike pcon format data isn't compressed, it's reload by [counter::Counter::from_bincode]
+    pub fn bincode<W>(&self, output: W) -> error::Result<()>
+    where
+        W: std::io::Write,
+        T: serde::Serialize,
+    {
+        bincode::serialize_into(output, &self.counter)?;
+
+        Ok(())
     }
 }
 
@@ -40,7 +247,91 @@ macro_rules! impl_sequential {
             where
                 W: std::io::Write,
             {
-                output.write_all(&[self.counter.k(), std::mem::size_of::<$type>() as u8])?;
+                counter::check_kmer_size(self.counter.k())?;
+
+                if self.is_sparse(self.counter.distinct_kmers()) {
+                    output.write_all(&[
+                        self.counter.k() | counter::SPARSE_FLAG,
+                        std::mem::size_of::<$type>() as u8,
+                        counter::FORMAT_VERSION,
+                    ])?;
+
+                    let mut encoder =
+                        flate2::write::GzEncoder::new(output, flate2::Compression::fast());
+                    for (index, count) in self
+                        .counter
+                        .raw_noatomic()
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, count)| **count != 0)
+                    {
+                        encoder.write_all(&(index as u64).to_le_bytes())?;
+                        encoder.write_all(&count.to_le_bytes())?;
+                    }
+                    encoder.finish()?;
+
+                    return Ok(());
+                }
+
+                self.pcon_dense(output, false)
+            }
+
+            /// Write kmer with count upper than `abundance` in fasta format, one record by kmer with count in header
+            /// `>count_N`
+            pub fn fasta_count<W>(&self, abundance: $type, mut output: W) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                for (hash, value) in self.counter.raw().iter().enumerate() {
+                    if value > &abundance && self.is_complex(hash as u64) {
+                        write_fasta_record(&mut output, self.counter.k(), hash as u64, value)?;
+                    }
+                }
+
+                Ok(())
+            }
+
+            /// Write counter as a flat little endian count array without compression, a [RAW_HEADER_LEN] bytes header
+            /// store k and count width, count of hash `h` is at offset `RAW_HEADER_LEN + h * width`
+            pub fn raw<W>(&self, mut output: W) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                counter::check_kmer_size(self.counter.k())?;
+
+                let mut header = [0u8; RAW_HEADER_LEN];
+                header[0] = self.counter.k();
+                header[1] = std::mem::size_of::<$type>() as u8;
+                output.write_all(&header)?;
+
+                for count in self.counter.raw_noatomic() {
+                    output.write_all(&count.to_le_bytes())?;
+                }
+
+                Ok(())
+            }
+
+            /// Write counter in dense pcon format followed by an index of compressed block, see
+            /// [counter::Counter::open_indexed]
+            pub fn pcon_indexed<W>(&self, output: W) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                counter::check_kmer_size(self.counter.k())?;
+
+                self.pcon_dense(output, true)
+            }
+
+            /// Write counter in dense pcon format, with an index of compressed block if `indexed` is true
+            fn pcon_dense<W>(&self, mut output: W, indexed: bool) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                output.write_all(&[
+                    self.counter.k(),
+                    std::mem::size_of::<$type>() as u8,
+                    counter::FORMAT_VERSION,
+                ])?;
 
                 // Magic number choose empirically
                 let chunk_size = (1 << 21) / std::mem::size_of::<$type>();
@@ -71,57 +362,122 @@ macro_rules! impl_sequential {
                     })
                     .collect();
 
+                let mut offsets = Vec::with_capacity(compress_block.len() + 1);
+                let mut offset = counter::HEADER_LEN;
                 for result in compress_block {
-                    output.write_all(&result?)?;
+                    let block = result?;
+
+                    offsets.push(offset);
+                    offset += block.len() as u64;
+
+                    output.write_all(&block)?;
+                }
+                offsets.push(offset);
+
+                if indexed {
+                    counter::write_index(&mut output, &offsets, chunk_size as u64)?;
                 }
 
                 Ok(())
             }
 
             /// Write kmer count in csv format
-            pub fn csv<W>(&self, abundance: $type, mut output: W) -> error::Result<()>
+            pub fn csv<W>(&self, abundance: $type, output: W) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                self.csv_columns(abundance, DEFAULT_COLUMNS, output)
+            }
+
+            /// Write kmer count in csv format, with only `columns` in this order
+            pub fn csv_columns<W>(
+                &self,
+                abundance: $type,
+                columns: &[Column],
+                mut output: W,
+            ) -> error::Result<()>
             where
                 W: std::io::Write,
             {
                 let counts = self.counter.raw();
 
-                for (hash, value) in counts.iter().enumerate() {
-                    let kmer = if cocktail::kmer::parity_even(hash as u64) {
-                        cocktail::kmer::kmer2seq((hash as u64) << 1, self.counter.k())
-                    } else {
-                        cocktail::kmer::kmer2seq(((hash as u64) << 1) ^ 0b1, self.counter.k())
-                    };
+                if let Some(sort) = self.sort {
+                    log::warn!("Sort csv output require to store all rows in memory");
+                    let rows = counts
+                        .iter()
+                        .enumerate()
+                        .map(|(hash, value)| (hash as u64, *value))
+                        .filter(|(hash, value)| value > &abundance && self.is_complex(*hash))
+                        .collect();
 
-                    if value > &abundance {
-                        writeln!(output, "{},{}", kmer, value)?;
+                    return write_sorted(&mut output, columns, self.counter.k(), rows, sort);
+                }
+
+                for (hash, value) in counts.iter().enumerate() {
+                    if value > &abundance && self.is_complex(hash as u64) {
+                        write_row(&mut output, columns, self.counter.k(), hash as u64, value)?;
                     }
                 }
 
                 Ok(())
             }
 
+            /// Write the `n` kmer with highest count upper than `abundance` in csv format, with only `columns` in this
+            /// order, rows are sort by decreasing count, kmer with a complexity lower than min_complexity are skip
+            pub fn top<W>(
+                &self,
+                n: usize,
+                abundance: $type,
+                columns: &[Column],
+                mut output: W,
+            ) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                let k = self.counter.k();
+
+                for (hash, count) in self
+                    .counter
+                    .top_with(n, abundance, |hash| self.is_complex(hash))
+                {
+                    write_row(&mut output, columns, k, hash, count)?;
+                }
+
+                Ok(())
+            }
+
+            /// Write count of each kmer of `kmers` in csv format, kmer are write in canonical form
+            pub fn csv_whitelist<W>(&self, kmers: &[u64], mut output: W) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                for kmer in kmers {
+                    let canonical = cocktail::kmer::canonical(*kmer, self.counter.k());
+
+                    writeln!(
+                        output,
+                        "{},{}",
+                        cocktail::kmer::kmer2seq(canonical, self.counter.k()),
+                        self.counter.get(*kmer)
+                    )?;
+                }
+
+                Ok(())
+            }
+
             /// Convert counter in solid and write it
             ///
             /// The first bytes contains the size of k the rest of the file are a
-            /// bitfield of absence for each kmer
+            /// bitfield of absence for each kmer, see [solid::Solid::from_stream]
             pub fn solid<W>(&self, abundance: $type, output: W) -> error::Result<()>
             where
                 W: std::io::Write,
             {
-                let solid =
+                let mut solid =
                     solid::Solid::from_count(self.counter.k(), self.counter.raw(), abundance);
+                self.remove_low_complexity(&mut solid);
 
-                let mut writer = niffler::get_writer(
-                    Box::new(output),
-                    niffler::compression::Format::Gzip,
-                    niffler::compression::Level::One,
-                )?;
-
-                writer.write_u8(solid.k())?;
-
-                writer.write_all(solid.get_raw_solid().as_raw_slice())?;
-
-                Ok(())
+                solid.serialize(output, solid::Codec::Gzip)
             }
 
             #[cfg(feature = "kff")]
@@ -137,15 +493,18 @@ macro_rules! impl_sequential {
                     true,
                     true,
                     b"producer: pcon".to_vec(),
-                )?;
-                let mut writer = kff::Kff::write(output, header)?;
+                )
+                .map_err(error::Error::from)?;
+                let mut writer = kff::Kff::write(output, header).map_err(error::Error::from)?;
                 let mut values = kff::section::Values::default();
                 values.insert("k".to_string(), self.counter.k() as u64);
                 values.insert("ordered".to_string(), true as u64);
                 values.insert("max".to_string(), <$type>::MAX as u64);
                 values.insert("data_size".to_string(), std::mem::size_of::<$type>() as u64);
 
-                writer.write_values(values.clone())?;
+                writer
+                    .write_values(values.clone())
+                    .map_err(error::Error::from)?;
 
                 let mut kmers = vec![];
 
@@ -159,7 +518,7 @@ macro_rules! impl_sequential {
 
                         kmers.push(kff::section::Block::new(
                             self.counter.k() as u64,
-                            std::mem::size_of::<crate::CountType>(),
+                            std::mem::size_of::<$type>(),
                             kff::Kmer::new(
                                 bitvec::boxed::BitBox::<u8, bitvec::order::Msb0>::from_boxed_slice(
                                     Box::new(kmer.to_be_bytes()),
@@ -171,9 +530,14 @@ macro_rules! impl_sequential {
                     }
                 }
 
-                writer.write_raw(kff::section::Raw::new(&values)?, kmers)?;
+                writer
+                    .write_raw(
+                        kff::section::Raw::new(&values).map_err(error::Error::from)?,
+                        kmers,
+                    )
+                    .map_err(error::Error::from)?;
 
-                writer.finalize()?;
+                writer.finalize().map_err(error::Error::from)?;
 
                 Ok(())
             }
@@ -196,7 +560,93 @@ macro_rules! impl_atomic {
             where
                 W: std::io::Write,
             {
-                output.write_all(&[self.counter.k(), std::mem::size_of::<$type>() as u8])?;
+                counter::check_kmer_size(self.counter.k())?;
+
+                if self.is_sparse(self.counter.distinct_kmers()) {
+                    output.write_all(&[
+                        self.counter.k() | counter::SPARSE_FLAG,
+                        std::mem::size_of::<$type>() as u8,
+                        counter::FORMAT_VERSION,
+                    ])?;
+
+                    let mut encoder =
+                        flate2::write::GzEncoder::new(output, flate2::Compression::fast());
+                    for (index, count) in self
+                        .counter
+                        .raw_noatomic()
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, count)| **count != 0)
+                    {
+                        encoder.write_all(&(index as u64).to_le_bytes())?;
+                        encoder.write_all(&count.to_le_bytes())?;
+                    }
+                    encoder.finish()?;
+
+                    return Ok(());
+                }
+
+                self.pcon_dense(output, false)
+            }
+
+            /// Write kmer with count upper than `abundance` in fasta format, one record by kmer with count in header
+            /// `>count_N`
+            pub fn fasta_count<W>(&self, abundance: $out_type, mut output: W) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                let counts = utils::transmute::<$type, $out_type>(self.counter.raw());
+
+                for (hash, value) in counts.iter().enumerate() {
+                    if value > &abundance && self.is_complex(hash as u64) {
+                        write_fasta_record(&mut output, self.counter.k(), hash as u64, value)?;
+                    }
+                }
+
+                Ok(())
+            }
+
+            /// Write counter as a flat little endian count array without compression, a [RAW_HEADER_LEN] bytes header
+            /// store k and count width, count of hash `h` is at offset `RAW_HEADER_LEN + h * width`
+            pub fn raw<W>(&self, mut output: W) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                counter::check_kmer_size(self.counter.k())?;
+
+                let mut header = [0u8; RAW_HEADER_LEN];
+                header[0] = self.counter.k();
+                header[1] = std::mem::size_of::<$type>() as u8;
+                output.write_all(&header)?;
+
+                for count in self.counter.raw_noatomic() {
+                    output.write_all(&count.to_le_bytes())?;
+                }
+
+                Ok(())
+            }
+
+            /// Write counter in dense pcon format followed by an index of compressed block, see
+            /// [counter::Counter::open_indexed]
+            pub fn pcon_indexed<W>(&self, output: W) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                counter::check_kmer_size(self.counter.k())?;
+
+                self.pcon_dense(output, true)
+            }
+
+            /// Write counter in dense pcon format, with an index of compressed block if `indexed` is true
+            fn pcon_dense<W>(&self, mut output: W, indexed: bool) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                output.write_all(&[
+                    self.counter.k(),
+                    std::mem::size_of::<$type>() as u8,
+                    counter::FORMAT_VERSION,
+                ])?;
 
                 // Magic number choose empirically
                 let chunk_size = (1 << 21) / std::mem::size_of::<$type>();
@@ -227,35 +677,130 @@ macro_rules! impl_atomic {
                     })
                     .collect();
 
+                let mut offsets = Vec::with_capacity(compress_block.len() + 1);
+                let mut offset = counter::HEADER_LEN;
                 for result in compress_block {
-                    output.write_all(&result?)?;
+                    let block = result?;
+
+                    offsets.push(offset);
+                    offset += block.len() as u64;
+
+                    output.write_all(&block)?;
+                }
+                offsets.push(offset);
+
+                if indexed {
+                    counter::write_index(&mut output, &offsets, chunk_size as u64)?;
                 }
 
                 Ok(())
             }
 
             /// Write kmer in csv format
-            pub fn csv<W>(&self, abundance: $out_type, mut output: W) -> error::Result<()>
+            pub fn csv<W>(&self, abundance: $out_type, output: W) -> error::Result<()>
             where
                 W: std::io::Write,
             {
-                let counts = utils::transmute::<$type, $out_type>(self.counter.raw());
+                self.csv_columns(abundance, DEFAULT_COLUMNS, output)
+            }
 
-                for (hash, value) in counts.iter().enumerate() {
-                    let kmer = if cocktail::kmer::parity_even(hash as u64) {
-                        cocktail::kmer::kmer2seq((hash as u64) << 1, self.counter.k())
-                    } else {
-                        cocktail::kmer::kmer2seq(((hash as u64) << 1) ^ 0b1, self.counter.k())
-                    };
+            /// Write kmer count in csv format, with only `columns` in this order
+            pub fn csv_columns<W>(
+                &self,
+                abundance: $out_type,
+                columns: &[Column],
+                mut output: W,
+            ) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                let counts = utils::transmute::<$type, $out_type>(self.counter.raw());
+                let k = self.counter.k();
+
+                if let Some(sort) = self.sort {
+                    log::warn!("Sort csv output require to store all rows in memory");
+                    let rows = counts
+                        .par_iter()
+                        .enumerate()
+                        .map(|(hash, value)| (hash as u64, *value))
+                        .filter(|(hash, value)| value > &abundance && self.is_complex(*hash))
+                        .collect();
+
+                    return write_sorted(&mut output, columns, k, rows, sort);
+                }
 
-                    if value > &abundance {
-                        writeln!(output, "{},{}", kmer, value)?;
+                // Rows of a group of chunk are format in parallel, group limit memory usage
+                let group_size = CSV_CHUNK_SIZE * rayon::current_num_threads();
+                for (group_index, group) in counts.chunks(group_size).enumerate() {
+                    let blocks: Vec<error::Result<Vec<u8>>> = group
+                        .par_chunks(CSV_CHUNK_SIZE)
+                        .enumerate()
+                        .map(|(chunk_index, chunk)| {
+                            let offset = group_index * group_size + chunk_index * CSV_CHUNK_SIZE;
+                            let mut block = Vec::new();
+
+                            for (index, value) in chunk.iter().enumerate() {
+                                let hash = (offset + index) as u64;
+                                if value > &abundance && self.is_complex(hash) {
+                                    write_row(&mut block, columns, k, hash, value)?;
+                                }
+                            }
+
+                            Ok(block)
+                        })
+                        .collect();
+
+                    for block in blocks {
+                        output.write_all(&block?)?;
                     }
                 }
 
                 Ok(())
             }
 
+            /// Write the `n` kmer with highest count upper than `abundance` in csv format, with only `columns` in this
+            /// order, rows are sort by decreasing count, kmer with a complexity lower than min_complexity are skip
+            pub fn top<W>(
+                &self,
+                n: usize,
+                abundance: $out_type,
+                columns: &[Column],
+                mut output: W,
+            ) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                let k = self.counter.k();
+
+                for (hash, count) in self
+                    .counter
+                    .top_with(n, abundance, |hash| self.is_complex(hash))
+                {
+                    write_row(&mut output, columns, k, hash, count)?;
+                }
+
+                Ok(())
+            }
+
+            /// Write count of each kmer of `kmers` in csv format, kmer are write in canonical form
+            pub fn csv_whitelist<W>(&self, kmers: &[u64], mut output: W) -> error::Result<()>
+            where
+                W: std::io::Write,
+            {
+                for kmer in kmers {
+                    let canonical = cocktail::kmer::canonical(*kmer, self.counter.k());
+
+                    writeln!(
+                        output,
+                        "{},{}",
+                        cocktail::kmer::kmer2seq(canonical, self.counter.k()),
+                        self.counter.get(*kmer)
+                    )?;
+                }
+
+                Ok(())
+            }
+
             /// Convert counter in solid and write it
             ///
             /// The first bytes contains the size of k the rest of the file and a
@@ -264,23 +809,14 @@ macro_rules! impl_atomic {
             where
                 W: std::io::Write,
             {
-                let solid = solid::Solid::from_count(
+                let mut solid = solid::Solid::from_count(
                     self.counter.k(),
                     utils::transmute::<$type, $out_type>(self.counter.raw()),
                     abundance,
                 );
+                self.remove_low_complexity(&mut solid);
 
-                let mut writer = niffler::get_writer(
-                    Box::new(output),
-                    niffler::compression::Format::Gzip,
-                    niffler::compression::Level::One,
-                )?;
-
-                writer.write_all(&[solid.k()])?;
-
-                writer.write_all(solid.get_raw_solid().as_raw_slice())?;
-
-                Ok(())
+                solid.serialize(output, solid::Codec::Gzip)
             }
 
             #[cfg(feature = "kff")]
@@ -296,8 +832,9 @@ macro_rules! impl_atomic {
                     true,
                     true,
                     b"producer: pcon".to_vec(),
-                )?;
-                let mut writer = kff::Kff::write(output, header)?;
+                )
+                .map_err(error::Error::from)?;
+                let mut writer = kff::Kff::write(output, header).map_err(error::Error::from)?;
                 let mut values = kff::section::Values::default();
                 values.insert("k".to_string(), self.counter.k() as u64);
                 values.insert("ordered".to_string(), true as u64);
@@ -307,7 +844,9 @@ macro_rules! impl_atomic {
                     std::mem::size_of::<$out_type>() as u64,
                 );
 
-                writer.write_values(values.clone())?;
+                writer
+                    .write_values(values.clone())
+                    .map_err(error::Error::from)?;
 
                 let mut kmers = vec![];
 
@@ -322,7 +861,7 @@ macro_rules! impl_atomic {
 
                         kmers.push(kff::section::Block::new(
                             self.counter.k() as u64,
-                            std::mem::size_of::<crate::CountType>(),
+                            std::mem::size_of::<$out_type>(),
                             kff::Kmer::new(
                                 bitvec::boxed::BitBox::<u8, bitvec::order::Msb0>::from_boxed_slice(
                                     Box::new(kmer.to_be_bytes()),
@@ -334,9 +873,14 @@ macro_rules! impl_atomic {
                     }
                 }
 
-                writer.write_raw(kff::section::Raw::new(&values)?, kmers)?;
+                writer
+                    .write_raw(
+                        kff::section::Raw::new(&values).map_err(error::Error::from)?,
+                        kmers,
+                    )
+                    .map_err(error::Error::from)?;
 
-                writer.finalize()?;
+                writer.finalize().map_err(error::Error::from)?;
 
                 Ok(())
             }
@@ -390,8 +934,8 @@ mod tests {
     }
 
     const PCON_ABUNDANCE: &[u8] = &[
-        5, 1, 31, 139, 8, 0, 0, 0, 0, 0, 4, 255, 237, 208, 1, 13, 0, 0, 0, 130, 176, 77, 251, 119,
-        38, 8, 60, 194, 191, 152, 7, 0, 94, 201, 71, 192, 0, 2, 0, 0,
+        5, 1, 1, 31, 139, 8, 0, 0, 0, 0, 0, 4, 255, 237, 208, 1, 13, 0, 0, 0, 130, 176, 77, 251,
+        119, 38, 8, 60, 194, 191, 152, 7, 0, 94, 201, 71, 192, 0, 2, 0, 0,
     ];
 
     #[test]
@@ -419,6 +963,62 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn raw() -> error::Result<()> {
+        let mut outfile = Vec::new();
+        let counter = generate_counter();
+        let serialize = counter.serialize();
+
+        serialize.raw(&mut outfile)?;
+
+        assert_eq!(outfile.len(), RAW_HEADER_LEN + 512);
+        assert_eq!(&outfile[..RAW_HEADER_LEN], &[5, 1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(outfile[RAW_HEADER_LEN], 3);
+        assert!(outfile[RAW_HEADER_LEN + 1..]
+            .iter()
+            .all(|count| *count == 2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fasta_count() -> error::Result<()> {
+        let mut outfile = Vec::new();
+        let counter = generate_counter();
+        let serialize = counter.serialize();
+
+        serialize.fasta_count(2, &mut outfile)?;
+        assert_eq!(&outfile[..], b">count_3\nAAAAA\n");
+
+        outfile.clear();
+        serialize.fasta_count(1, &mut outfile)?;
+        assert_eq!(outfile.iter().filter(|c| **c == b'>').count(), 512);
+        assert!(outfile.starts_with(b">count_3\nAAAAA\n>count_2\nAAAAG\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn top() -> error::Result<()> {
+        let counter = generate_counter();
+        let serialize = counter.serialize();
+
+        let mut outfile = Vec::new();
+        serialize.top(2, 0, DEFAULT_COLUMNS, &mut outfile)?;
+        assert_eq!(&outfile[..], b"AAAAA,3\nAAAAG,2\n");
+
+        outfile.clear();
+        serialize.top(3, 2, DEFAULT_COLUMNS, &mut outfile)?;
+        assert_eq!(&outfile[..], b"AAAAA,3\n");
+
+        outfile.clear();
+        let serialize = serialize.min_complexity(1.0);
+        serialize.top(2, 0, DEFAULT_COLUMNS, &mut outfile)?;
+        assert_eq!(&outfile[..], b"AAACT,2\nAAATC,2\n");
+
+        Ok(())
+    }
+
     const CSV_ABUNDANCE_MIN_1: &[u8] = b"AAAAA,3\nAAAAG,2\nAAACC,2\nAAACT,2\nAAATC,2\nAAATT,2\nAAAGA,2\nAAAGG,2\nAACAC,2\nAACAT,2\nAACCA,2\nAACCG,2\nAACTA,2\nAACTG,2\nAACGC,2\nAACGT,2\nAATAC,2\nAATAT,2\nAATCA,2\nAATCG,2\nAATTA,2\nAATTG,2\nAATGC,2\nAATGT,2\nAAGAA,2\nAAGAG,2\nAAGCC,2\nAAGCT,2\nAAGTC,2\nAAGTT,2\nAAGGA,2\nAAGGG,2\nACAAC,2\nACAAT,2\nACACA,2\nACACG,2\nACATA,2\nACATG,2\nACAGC,2\nACAGT,2\nACCAA,2\nACCAG,2\nACCCC,2\nACCCT,2\nACCTC,2\nACCTT,2\nACCGA,2\nACCGG,2\nACTAA,2\nACTAG,2\nACTCC,2\nACTCT,2\nACTTC,2\nACTTT,2\nACTGA,2\nACTGG,2\nACGAC,2\nACGAT,2\nACGCA,2\nACGCG,2\nACGTA,2\nACGTG,2\nACGGC,2\nACGGT,2\nATAAC,2\nATAAT,2\nATACA,2\nATACG,2\nATATA,2\nATATG,2\nATAGC,2\nATAGT,2\nATCAA,2\nATCAG,2\nATCCC,2\nATCCT,2\nATCTC,2\nATCTT,2\nATCGA,2\nATCGG,2\nATTAA,2\nATTAG,2\nATTCC,2\nATTCT,2\nATTTC,2\nATTTT,2\nATTGA,2\nATTGG,2\nATGAC,2\nATGAT,2\nATGCA,2\nATGCG,2\nATGTA,2\nATGTG,2\nATGGC,2\nATGGT,2\nAGAAA,2\nAGAAG,2\nAGACC,2\nAGACT,2\nAGATC,2\nAGATT,2\nAGAGA,2\nAGAGG,2\nAGCAC,2\nAGCAT,2\nAGCCA,2\nAGCCG,2\nAGCTA,2\nAGCTG,2\nAGCGC,2\nAGCGT,2\nAGTAC,2\nAGTAT,2\nAGTCA,2\nAGTCG,2\nAGTTA,2\nAGTTG,2\nAGTGC,2\nAGTGT,2\nAGGAA,2\nAGGAG,2\nAGGCC,2\nAGGCT,2\nAGGTC,2\nAGGTT,2\nAGGGA,2\nAGGGG,2\nCAAAC,2\nCAAAT,2\nCAACA,2\nCAACG,2\nCAATA,2\nCAATG,2\nCAAGC,2\nCAAGT,2\nCACAA,2\nCACAG,2\nCACCC,2\nCACCT,2\nCACTC,2\nCACTT,2\nCACGA,2\nCACGG,2\nCATAA,2\nCATAG,2\nCATCC,2\nCATCT,2\nCATTC,2\nCATTT,2\nCATGA,2\nCATGG,2\nCAGAC,2\nCAGAT,2\nCAGCA,2\nCAGCG,2\nCAGTA,2\nCAGTG,2\nCAGGC,2\nCAGGT,2\nCCAAA,2\nCCAAG,2\nCCACC,2\nCCACT,2\nCCATC,2\nCCATT,2\nCCAGA,2\nCCAGG,2\nCCCAC,2\nCCCAT,2\nCCCCA,2\nCCCCG,2\nCCCTA,2\nCCCTG,2\nCCCGC,2\nCCCGT,2\nCCTAC,2\nCCTAT,2\nCCTCA,2\nCCTCG,2\nCCTTA,2\nCCTTG,2\nCCTGC,2\nCCTGT,2\nCCGAA,2\nCCGAG,2\nCCGCC,2\nCCGCT,2\nCCGTC,2\nCCGTT,2\nCCGGA,2\nCCGGG,2\nCTAAA,2\nCTAAG,2\nCTACC,2\nCTACT,2\nCTATC,2\nCTATT,2\nCTAGA,2\nCTAGG,2\nCTCAC,2\nCTCAT,2\nCTCCA,2\nCTCCG,2\nCTCTA,2\nCTCTG,2\nCTCGC,2\nCTCGT,2\nCTTAC,2\nCTTAT,2\nCTTCA,2\nCTTCG,2\nCTTTA,2\nCTTTG,2\nCTTGC,2\nCTTGT,2\nCTGAA,2\nCTGAG,2\nCTGCC,2\nCTGCT,2\nCTGTC,2\nCTGTT,2\nCTGGA,2\nCTGGG,2\nCGAAC,2\nCGAAT,2\nCGACA,2\nCGACG,2\nCGATA,2\nCGATG,2\nCGAGC,2\nCGAGT,2\nCGCAA,2\nCGCAG,2\nCGCCC,2\nCGCCT,2\nCGCTC,2\nCGCTT,2\nCGCGA,2\nCGCGG,2\nCGTAA,2\nCGTAG,2\nCGTCC,2\nCGTCT,2\nCGTTC,2\nCGTTT,2\nCGTGA,2\nCGTGG,2\nCGGAC,2\nCGGAT,2\nCGGCA,2\nCGGCG,2\nCGGTA,2\nCGGTG,2\nCGGGC,2\nCGGGT,2\nTAAAC,2\nTAAAT,2\nTAACA,2\nTAACG,2\nTAATA,2\nTAATG,2\nTAAGC,2\nTAAGT,2\nTACAA,2\nTACAG,2\nTACCC,2\nTACCT,2\nTACTC,2\nTACTT,2\nTACGA,2\nTACGG,2\nTATAA,2\nTATAG,2\nTATCC,2\nTATCT,2\nTATTC,2\nTATTT,2\nTATGA,2\nTATGG,2\nTAGAC,2\nTAGAT,2\nTAGCA,2\nTAGCG,2\nTAGTA,2\nTAGTG,2\nTAGGC,2\nTAGGT,2\nTCAAA,2\nTCAAG,2\nTCACC,2\nTCACT,2\nTCATC,2\nTCATT,2\nTCAGA,2\nTCAGG,2\nTCCAC,2\nTCCAT,2\nTCCCA,2\nTCCCG,2\nTCCTA,2\nTCCTG,2\nTCCGC,2\nTCCGT,2\nTCTAC,2\nTCTAT,2\nTCTCA,2\nTCTCG,2\nTCTTA,2\nTCTTG,2\nTCTGC,2\nTCTGT,2\nTCGAA,2\nTCGAG,2\nTCGCC,2\nTCGCT,2\nTCGTC,2\nTCGTT,2\nTCGGA,2\nTCGGG,2\nTTAAA,2\nTTAAG,2\nTTACC,2\nTTACT,2\nTTATC,2\nTTATT,2\nTTAGA,2\nTTAGG,2\nTTCAC,2\nTTCAT,2\nTTCCA,2\nTTCCG,2\nTTCTA,2\nTTCTG,2\nTTCGC,2\nTTCGT,2\nTTTAC,2\nTTTAT,2\nTTTCA,2\nTTTCG,2\nTTTTA,2\nTTTTG,2\nTTTGC,2\nTTTGT,2\nTTGAA,2\nTTGAG,2\nTTGCC,2\nTTGCT,2\nTTGTC,2\nTTGTT,2\nTTGGA,2\nTTGGG,2\nTGAAC,2\nTGAAT,2\nTGACA,2\nTGACG,2\nTGATA,2\nTGATG,2\nTGAGC,2\nTGAGT,2\nTGCAA,2\nTGCAG,2\nTGCCC,2\nTGCCT,2\nTGCTC,2\nTGCTT,2\nTGCGA,2\nTGCGG,2\nTGTAA,2\nTGTAG,2\nTGTCC,2\nTGTCT,2\nTGTTC,2\nTGTTT,2\nTGTGA,2\nTGTGG,2\nTGGAC,2\nTGGAT,2\nTGGCA,2\nTGGCG,2\nTGGTA,2\nTGGTG,2\nTGGGC,2\nTGGGT,2\nGAAAA,2\nGAAAG,2\nGAACC,2\nGAACT,2\nGAATC,2\nGAATT,2\nGAAGA,2\nGAAGG,2\nGACAC,2\nGACAT,2\nGACCA,2\nGACCG,2\nGACTA,2\nGACTG,2\nGACGC,2\nGACGT,2\nGATAC,2\nGATAT,2\nGATCA,2\nGATCG,2\nGATTA,2\nGATTG,2\nGATGC,2\nGATGT,2\nGAGAA,2\nGAGAG,2\nGAGCC,2\nGAGCT,2\nGAGTC,2\nGAGTT,2\nGAGGA,2\nGAGGG,2\nGCAAC,2\nGCAAT,2\nGCACA,2\nGCACG,2\nGCATA,2\nGCATG,2\nGCAGC,2\nGCAGT,2\nGCCAA,2\nGCCAG,2\nGCCCC,2\nGCCCT,2\nGCCTC,2\nGCCTT,2\nGCCGA,2\nGCCGG,2\nGCTAA,2\nGCTAG,2\nGCTCC,2\nGCTCT,2\nGCTTC,2\nGCTTT,2\nGCTGA,2\nGCTGG,2\nGCGAC,2\nGCGAT,2\nGCGCA,2\nGCGCG,2\nGCGTA,2\nGCGTG,2\nGCGGC,2\nGCGGT,2\nGTAAC,2\nGTAAT,2\nGTACA,2\nGTACG,2\nGTATA,2\nGTATG,2\nGTAGC,2\nGTAGT,2\nGTCAA,2\nGTCAG,2\nGTCCC,2\nGTCCT,2\nGTCTC,2\nGTCTT,2\nGTCGA,2\nGTCGG,2\nGTTAA,2\nGTTAG,2\nGTTCC,2\nGTTCT,2\nGTTTC,2\nGTTTT,2\nGTTGA,2\nGTTGG,2\nGTGAC,2\nGTGAT,2\nGTGCA,2\nGTGCG,2\nGTGTA,2\nGTGTG,2\nGTGGC,2\nGTGGT,2\nGGAAA,2\nGGAAG,2\nGGACC,2\nGGACT,2\nGGATC,2\nGGATT,2\nGGAGA,2\nGGAGG,2\nGGCAC,2\nGGCAT,2\nGGCCA,2\nGGCCG,2\nGGCTA,2\nGGCTG,2\nGGCGC,2\nGGCGT,2\nGGTAC,2\nGGTAT,2\nGGTCA,2\nGGTCG,2\nGGTTA,2\nGGTTG,2\nGGTGC,2\nGGTGT,2\nGGGAA,2\nGGGAG,2\nGGGCC,2\nGGGCT,2\nGGGTC,2\nGGGTT,2\nGGGGA,2\nGGGGG,2\n";
 
     const CSV_ABUNDANCE_MIN_2: &[u8] = b"AAAAA,3\n";
@@ -458,6 +1058,36 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_csv() -> error::Result<()> {
+        let mut sequential = counter::Counter::<u8>::new(11);
+        let mut parallel = counter::Counter::<std::sync::atomic::AtomicU8>::new(11);
+        for hash in 0..cocktail::kmer::get_hash_space_size(11) as usize {
+            for _ in 0..(hash % 3) {
+                counter::Counter::<u8>::inc(sequential.raw_mut(), hash);
+                counter::Counter::<std::sync::atomic::AtomicU8>::inc(parallel.raw_mut(), hash);
+            }
+        }
+
+        let columns = [Column::Kmer, Column::Count, Column::HashIndex];
+
+        let mut expected = Vec::new();
+        sequential
+            .serialize()
+            .csv_columns(0, &columns, &mut expected)?;
+
+        let mut outfile = Vec::new();
+        parallel
+            .serialize()
+            .csv_columns(0, &columns, &mut outfile)?;
+
+        assert!(!expected.is_empty());
+        assert_eq!(outfile, expected);
+
+        Ok(())
+    }
+
     const SOLID_ABUNDANCE_MIN_1: &[u8] = &[
         31, 139, 8, 0, 0, 0, 0, 0, 4, 255, 165, 192, 49, 1, 0, 0, 0, 64, 176, 75, 255, 200, 132,
         48, 156, 2, 70, 0, 241, 137, 65, 0, 0, 0,
@@ -468,6 +1098,160 @@ mod tests {
         140, 175, 17, 95, 201, 40, 124, 65, 0, 0, 0,
     ];
 
+    #[test]
+    fn csv_columns() -> error::Result<()> {
+        let mut outfile = Vec::new();
+        let counter = generate_counter();
+        let serialize = counter.serialize();
+
+        serialize.csv_columns(2, &[Column::Count, Column::Kmer], &mut outfile)?;
+
+        let kmer = cocktail::kmer::kmer2seq(utils::hash2kmer(0), 5);
+        assert_eq!(String::from_utf8(outfile)?, format!("3,{}\n", kmer));
+
+        outfile = Vec::new();
+        serialize.csv_columns(
+            2,
+            &[Column::HashIndex, Column::Revcomp, Column::Count],
+            &mut outfile,
+        )?;
+
+        let revcomp = String::from_utf8(utils::revcomp(kmer.as_bytes()))?;
+        assert_eq!(String::from_utf8(outfile)?, format!("0,{},3\n", revcomp));
+
+        Ok(())
+    }
+
+    #[test]
+    fn csv_hash_column() -> error::Result<()> {
+        let mut outfile = Vec::new();
+        generate_counter().serialize().csv_columns(
+            0,
+            &[Column::Kmer, Column::Hash, Column::Count],
+            &mut outfile,
+        )?;
+
+        let output = String::from_utf8(outfile)?;
+        assert!(!output.is_empty());
+        for line in output.lines() {
+            let fields = line.split(',').collect::<Vec<&str>>();
+            let canonical =
+                cocktail::kmer::canonical(cocktail::kmer::seq2bit(fields[0].as_bytes()), 5);
+
+            assert_eq!(fields[1].parse::<u64>()?, canonical);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn csv_sort() -> error::Result<()> {
+        let lines = |sort| -> error::Result<Vec<String>> {
+            let mut outfile = Vec::new();
+            generate_counter()
+                .serialize()
+                .sort(Some(sort))
+                .csv(1, &mut outfile)?;
+
+            Ok(String::from_utf8(outfile)?
+                .lines()
+                .map(str::to_string)
+                .collect())
+        };
+
+        let desc = lines(Sort::CountDesc)?;
+        assert_eq!(desc.len(), 512);
+        assert_eq!(desc[0], "AAAAA,3");
+
+        let asc = lines(Sort::CountAsc)?;
+        assert_eq!(asc.len(), 512);
+        assert_eq!(asc[511], "AAAAA,3");
+
+        let kmer = lines(Sort::Kmer)?;
+        let mut sorted = kmer.clone();
+        sorted.sort();
+        assert_eq!(kmer, sorted);
+        assert_eq!(kmer[0], "AAAAA,3");
+        assert_eq!(kmer[1], "AAAAG,2");
+        assert_eq!(kmer[2], "AAACC,2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn min_complexity() -> error::Result<()> {
+        let mut outfile = Vec::new();
+        let counter = generate_counter();
+        let serialize = counter.serialize().min_complexity(1.0);
+
+        serialize.csv(0, &mut outfile)?;
+        let output = String::from_utf8(outfile)?;
+
+        let canonical = |kmer| cocktail::kmer::kmer2seq(cocktail::kmer::canonical(kmer, 5), 5);
+        let kmers = output
+            .lines()
+            .map(|line| line.split(',').next().unwrap_or("").to_string())
+            .collect::<Vec<String>>();
+
+        assert!(!kmers.contains(&canonical(cocktail::kmer::seq2bit(b"AAAAA"))));
+        assert!(kmers.contains(&canonical(cocktail::kmer::seq2bit(b"ACGTA"))));
+        assert!(kmers
+            .iter()
+            .all(|kmer| utils::complexity(kmer.as_bytes()) >= 1.0));
+
+        outfile = Vec::new();
+        serialize.solid(0, &mut outfile)?;
+        let solid = solid::Solid::deserialize(&outfile[..])?;
+
+        assert!(!solid.get(cocktail::kmer::seq2bit(b"AAAAA")));
+        assert!(solid.get(cocktail::kmer::seq2bit(b"ACGTA")));
+        assert_eq!(solid.nb_solid(), kmers.len() as u64);
+
+        Ok(())
+    }
+
+    #[test]
+    fn csv_whitelist() -> error::Result<()> {
+        let mut outfile = Vec::new();
+        let counter = generate_counter();
+        let serialize = counter.serialize();
+
+        let kmers = [
+            cocktail::kmer::seq2bit(b"AAAAA"),
+            cocktail::kmer::seq2bit(b"GTTTT"),
+        ];
+        serialize.csv_whitelist(&kmers, &mut outfile)?;
+
+        let canonical = |kmer| cocktail::kmer::kmer2seq(cocktail::kmer::canonical(kmer, 5), 5);
+        assert_eq!(
+            String::from_utf8(outfile)?,
+            format!("{},3\n{},2\n", canonical(kmers[0]), canonical(kmers[1]))
+        );
+
+        Ok(())
+    }
+
+    /// Without roaring `output` must be byte equal to dense fixture `truth`, with roaring a low density solid is
+    /// write in roaring format so kmer size and present kmer of `output` are compare to `truth`
+    fn assert_solid(output: &[u8], truth: &[u8]) -> error::Result<()> {
+        #[cfg(not(feature = "roaring"))]
+        assert_eq!(output, truth);
+
+        let load = |data: &[u8]| -> error::Result<solid::Solid> {
+            let (input, _) = niffler::get_reader(Box::new(data)).map_err(error::Error::from)?;
+            solid::Solid::from_stream(input)
+        };
+        let (output, truth) = (load(output)?, load(truth)?);
+
+        assert_eq!(output.k(), truth.k());
+        assert_eq!(
+            output.iter_solid().collect::<Vec<u64>>(),
+            truth.iter_solid().collect::<Vec<u64>>()
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn solid() -> error::Result<()> {
         let mut outfile = Vec::new();
@@ -475,12 +1259,12 @@ mod tests {
         let serialize = counter.serialize();
 
         serialize.solid(1, &mut outfile)?;
-        assert_eq!(&outfile[..], &SOLID_ABUNDANCE_MIN_1[..]);
+        assert_solid(&outfile, SOLID_ABUNDANCE_MIN_1)?;
 
         outfile.clear();
 
         serialize.solid(2, &mut outfile)?;
-        assert_eq!(&outfile[..], &SOLID_ABUNDANCE_MIN_2[..]);
+        assert_solid(&outfile, SOLID_ABUNDANCE_MIN_2)?;
 
         Ok(())
     }
@@ -493,12 +1277,12 @@ mod tests {
         let serialize = counter.serialize();
 
         serialize.solid(1, &mut outfile)?;
-        assert_eq!(&outfile[..], &SOLID_ABUNDANCE_MIN_1[..]);
+        assert_solid(&outfile, SOLID_ABUNDANCE_MIN_1)?;
 
         outfile.clear();
 
         serialize.solid(2, &mut outfile)?;
-        assert_eq!(&outfile[..], &SOLID_ABUNDANCE_MIN_2[..]);
+        assert_solid(&outfile, SOLID_ABUNDANCE_MIN_2)?;
 
         Ok(())
     }
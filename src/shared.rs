@@ -0,0 +1,65 @@
+//! Run shared command
+
+/* std use */
+
+/* crate use */
+use anyhow::Context as _;
+
+/* project use */
+use crate::cli;
+use crate::error;
+use crate::solid;
+
+/// Run shared
+pub fn shared(params: cli::Shared) -> error::Result<()> {
+    let mut solids: Vec<solid::Solid> = Vec::with_capacity(2);
+    for path in params.inputs() {
+        log::info!("Start load count of {}", path.display());
+        let solid = solid::Solid::from_pcon_path(path, params.abundance())
+            .with_context(|| format!("Can't load count of {}", path.display()))?;
+        log::info!("End load count of {}", path.display());
+
+        if let Some(first) = solids.first() {
+            if first.k() != solid.k() {
+                return Err(error::Error::KmerSizeNotMatch {
+                    expected: first.k(),
+                    found: solid.k(),
+                })
+                .with_context(|| format!("Can't compare {}", path.display()));
+            }
+        }
+
+        solids.push(solid);
+    }
+
+    log::info!("Start compute shared kmer");
+    let mut intersection = solids[0].clone();
+    intersection.intersect(solids[1].clone());
+    let mut union = solids[0].clone();
+    union.extend(solids[1].clone());
+    log::info!("End compute shared kmer");
+
+    write(params.output()?, &intersection, &union)
+}
+
+/// Write number of kmer solid in both inputs, in at least one input, Jaccard similarity and density of shared kmer
+fn write<W>(mut output: W, intersection: &solid::Solid, union: &solid::Solid) -> error::Result<()>
+where
+    W: std::io::Write,
+{
+    let jaccard = match union.nb_solid() {
+        0 => 0.0,
+        nb_union => intersection.nb_solid() as f64 / nb_union as f64,
+    };
+
+    writeln!(output, "intersection: {}", intersection.nb_solid())?;
+    writeln!(output, "union: {}", union.nb_solid())?;
+    writeln!(output, "jaccard: {:.6}", jaccard)?;
+    writeln!(
+        output,
+        "intersection density: {:.6}",
+        intersection.density()
+    )?;
+
+    Ok(())
+}
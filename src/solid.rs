@@ -1,18 +1,42 @@
 //! Define Solid struct
 
 /* std use */
+use std::io::Write as _;
 
 /* crate use */
 use bitvec::prelude::*;
 use byteorder::ReadBytesExt as _;
 
 /* local use */
+use crate::cli;
+use crate::counter;
 use crate::error;
 
+/// Compression format available to write solid
+pub type Codec = niffler::compression::Format;
+
+/// Bit set in first byte of a solid file if presence is store in roaring format
+pub const SPARSE_FLAG: u8 = 0b1000_0000;
+
+/// Under this density, solid is write in roaring format if feature roaring is activate
+pub const SPARSE_DENSITY: f64 = 0.01;
+
+/// Storage of kmer presence
+#[derive(Clone, Debug, PartialEq)]
+enum Presence {
+    /// One bit by kmer
+    Dense(BitBox<u8, Lsb0>),
+
+    #[cfg(feature = "roaring")]
+    /// Only index of present kmer are store
+    Sparse(roaring::RoaringTreemap),
+}
+
 /// A struct to store if a kmer is Solid or not. Only kmer with abundance upper than a threshold is solid
+#[derive(Clone, Debug, PartialEq)]
 pub struct Solid {
     k: u8,
-    solid: BitBox<u8, Lsb0>,
+    solid: Presence,
 }
 
 impl Solid {
@@ -20,7 +44,18 @@ impl Solid {
     pub fn new(k: u8) -> Self {
         Self {
             k,
-            solid: bitbox![u8, Lsb0; 0; cocktail::kmer::get_hash_space_size(k) as usize],
+            solid: Presence::Dense(
+                bitbox![u8, Lsb0; 0; cocktail::kmer::get_hash_space_size(k) as usize],
+            ),
+        }
+    }
+
+    #[cfg(feature = "roaring")]
+    /// Create a new Solid for kmer size equal to `k` where only present kmer are store
+    pub fn new_sparse(k: u8) -> Self {
+        Self {
+            k,
+            solid: Presence::Sparse(roaring::RoaringTreemap::new()),
         }
     }
 
@@ -37,21 +72,85 @@ impl Solid {
             }
         }
 
-        Self { k, solid }
+        Self {
+            k,
+            solid: Presence::Dense(solid),
+        }
+    }
+
+    /// Create a new Solid from a pcon file, only kmer upper than `abundance` are solid
+    ///
+    /// Count type is read in file header, `abundance` upper than max count of this type is saturate
+    pub fn from_pcon_path<P>(path: P, abundance: u64) -> error::Result<Self>
+    where
+        P: std::convert::AsRef<std::path::Path>,
+    {
+        let (width, input) =
+            counter::read_width(std::io::BufReader::new(std::fs::File::open(path)?))?;
+
+        match width {
+            cli::CountWidth::U8 => {
+                let counter = counter::Counter::<u8>::from_stream(input)?;
+                let abundance = u8::try_from(abundance).unwrap_or(u8::MAX);
+                Ok(Self::from_count(counter.k(), counter.raw(), abundance))
+            }
+            cli::CountWidth::U16 => {
+                let counter = counter::Counter::<u16>::from_stream(input)?;
+                let abundance = u16::try_from(abundance).unwrap_or(u16::MAX);
+                Ok(Self::from_count(counter.k(), counter.raw(), abundance))
+            }
+            cli::CountWidth::U32 => {
+                let counter = counter::Counter::<u32>::from_stream(input)?;
+                let abundance = u32::try_from(abundance).unwrap_or(u32::MAX);
+                Ok(Self::from_count(counter.k(), counter.raw(), abundance))
+            }
+            cli::CountWidth::U64 => {
+                let counter = counter::Counter::<u64>::from_stream(input)?;
+                Ok(Self::from_count(counter.k(), counter.raw(), abundance))
+            }
+        }
     }
 
     /// Create a new Solid by read
+    ///
+    /// If first byte have [SPARSE_FLAG] set, presence is read in roaring format
     pub fn from_stream<R>(mut input: R) -> error::Result<Self>
     where
         R: std::io::Read,
     {
-        let k = input.read_u8()?;
+        let header = input.read_u8()?;
+        let k = header & !SPARSE_FLAG;
+
+        if header & SPARSE_FLAG != 0 {
+            #[cfg(feature = "roaring")]
+            return Ok(Self {
+                k,
+                solid: Presence::Sparse(roaring::RoaringTreemap::deserialize_from(input)?),
+            });
+
+            #[cfg(not(feature = "roaring"))]
+            return Err(error::Error::SparseSolidNotSupported.into());
+        }
 
         let mut solid = bitbox![u8, Lsb0; 0; cocktail::kmer::get_hash_space_size(k) as usize];
 
         input.read_exact(solid.as_raw_mut_slice())?;
 
-        Ok(Self { k, solid })
+        Ok(Self {
+            k,
+            solid: Presence::Dense(solid),
+        })
+    }
+
+    /// Create a new Solid by read a compressed or not stream, compression is detect by niffler
+    pub fn deserialize<R>(input: R) -> error::Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let (readable, _compression) =
+            niffler::get_reader(Box::new(input)).map_err(error::Error::from)?;
+
+        Self::from_stream(readable)
     }
 
     /// Create a new Solid from path
@@ -63,11 +162,30 @@ impl Solid {
             std::fs::File::open(path)
                 .map(std::io::BufReader::new)
                 .map(Box::new)?,
-        )?;
+        )
+        .map_err(error::Error::from)?;
 
         Self::from_stream(readable)
     }
 
+    /// Create a new Solid from path, failed if kmer size of file isn't `expected_k`
+    pub fn from_path_expect<P>(path: P, expected_k: u8) -> error::Result<Self>
+    where
+        P: std::convert::AsRef<std::path::Path>,
+    {
+        let solid = Self::from_path(path)?;
+
+        if solid.k() != expected_k {
+            return Err(error::Error::KmerSizeNotMatch {
+                expected: expected_k,
+                found: solid.k(),
+            }
+            .into());
+        }
+
+        Ok(solid)
+    }
+
     /// Get value of k
     pub fn k(&self) -> u8 {
         self.k
@@ -80,10 +198,24 @@ impl Solid {
 
     /// Solidity status of a canonical`kmer` is set to `value`
     pub fn set_canonic(&mut self, canonical: u64, value: bool) {
-        let hash = (canonical >> 1) as usize;
+        let hash = canonical >> 1;
 
-        if let Some(mut v) = self.solid.get_mut(hash) {
-            *v = value;
+        match &mut self.solid {
+            Presence::Dense(solid) => {
+                if let Some(mut v) = solid.get_mut(hash as usize) {
+                    *v = value;
+                }
+            }
+            #[cfg(feature = "roaring")]
+            Presence::Sparse(solid) => {
+                if hash < cocktail::kmer::get_hash_space_size(self.k) {
+                    if value {
+                        solid.insert(hash);
+                    } else {
+                        solid.remove(hash);
+                    }
+                }
+            }
         }
     }
 
@@ -94,18 +226,207 @@ impl Solid {
 
     /// Get the solidity status of a canonical `kmer`
     pub fn get_canonic(&self, canonical: u64) -> bool {
-        let hash = (canonical >> 1) as usize;
+        let hash = canonical >> 1;
+
+        match &self.solid {
+            Presence::Dense(solid) => solid[hash as usize],
+            #[cfg(feature = "roaring")]
+            Presence::Sparse(solid) => solid.contains(hash),
+        }
+    }
 
-        self.solid[hash]
+    /// Get the solidity status of each kmer of `kmers` in `out`, kmer are canonicalize internally
+    ///
+    /// Only the `min(kmers.len(), out.len())` first status are set
+    pub fn get_batch(&self, kmers: &[u64], out: &mut [bool]) {
+        for (kmer, status) in kmers.iter().zip(out.iter_mut()) {
+            *status = self.get(*kmer);
+        }
     }
 
     /// Extend
     pub fn extend(&mut self, rhs: Solid) {
-        self.solid |= rhs.get_raw_solid()
+        match (&mut self.solid, rhs.solid) {
+            (Presence::Dense(solid), Presence::Dense(other)) => *solid |= &other,
+            #[cfg(feature = "roaring")]
+            (Presence::Dense(solid), Presence::Sparse(other)) => {
+                for hash in other.iter() {
+                    solid.set(hash as usize, true);
+                }
+            }
+            #[cfg(feature = "roaring")]
+            (Presence::Sparse(solid), Presence::Sparse(other)) => *solid |= other,
+            #[cfg(feature = "roaring")]
+            (Presence::Sparse(solid), Presence::Dense(other)) => {
+                solid.extend(other.iter_ones().map(|hash| hash as u64));
+            }
+        }
+    }
+
+    /// Keep only kmer solid in self and rhs
+    pub fn intersect(&mut self, rhs: Solid) {
+        match (&mut self.solid, rhs.solid) {
+            (Presence::Dense(solid), Presence::Dense(other)) => *solid &= &other,
+            #[cfg(feature = "roaring")]
+            (Presence::Dense(solid), Presence::Sparse(other)) => {
+                let removed = solid
+                    .iter_ones()
+                    .filter(|hash| !other.contains(*hash as u64))
+                    .collect::<Vec<usize>>();
+                for hash in removed {
+                    solid.set(hash, false);
+                }
+            }
+            #[cfg(feature = "roaring")]
+            (Presence::Sparse(solid), Presence::Sparse(other)) => *solid &= other,
+            #[cfg(feature = "roaring")]
+            (Presence::Sparse(solid), Presence::Dense(other)) => {
+                *solid = solid.iter().filter(|hash| other[*hash as usize]).collect();
+            }
+        }
+    }
+
+    /// Jaccard similarity between solid kmer of self and rhs, 0.0 if union is empty
+    pub(crate) fn jaccard(&self, rhs: &Solid) -> f64 {
+        let lhs = self.get_raw_solid();
+        let rhs = rhs.get_raw_solid();
+
+        let mut intersection = lhs.clone().into_owned();
+        intersection &= rhs.as_ref();
+        let mut union = lhs.into_owned();
+        union |= rhs.as_ref();
+
+        match union.count_ones() {
+            0 => 0.0,
+            union => intersection.count_ones() as f64 / union as f64,
+        }
+    }
+
+    /// Number of solid kmer
+    pub fn nb_solid(&self) -> u64 {
+        match &self.solid {
+            Presence::Dense(solid) => solid.count_ones() as u64,
+            #[cfg(feature = "roaring")]
+            Presence::Sparse(solid) => solid.len(),
+        }
+    }
+
+    /// Iterate over hash of solid kmer, in increasing order
+    pub fn iter_solid(&self) -> Box<dyn Iterator<Item = u64> + '_> {
+        match &self.solid {
+            Presence::Dense(solid) => Box::new(solid.iter_ones().map(|hash| hash as u64)),
+            #[cfg(feature = "roaring")]
+            Presence::Sparse(solid) => Box::new(solid.iter()),
+        }
+    }
+
+    /// Ratio between number of solid kmer and number of possible kmer
+    pub fn density(&self) -> f64 {
+        self.nb_solid() as f64 / cocktail::kmer::get_hash_space_size(self.k) as f64
+    }
+
+    /// Return true if presence is store in roaring format
+    pub fn is_sparse(&self) -> bool {
+        !matches!(self.solid, Presence::Dense(_))
+    }
+
+    #[cfg(feature = "roaring")]
+    /// Convert presence storage in roaring format
+    pub fn to_sparse(&self) -> Self {
+        let solid = match &self.solid {
+            Presence::Dense(solid) => solid.iter_ones().map(|hash| hash as u64).collect(),
+            Presence::Sparse(solid) => solid.clone(),
+        };
+
+        Self {
+            k: self.k,
+            solid: Presence::Sparse(solid),
+        }
+    }
+
+    /// Convert presence storage in dense format
+    pub fn to_dense(&self) -> Self {
+        Self {
+            k: self.k,
+            solid: Presence::Dense(self.get_raw_solid().into_owned()),
+        }
+    }
+
+    /// Write solid compressed with `codec`, see [Solid::from_stream] for format
+    pub fn serialize<W>(&self, output: W, codec: Codec) -> error::Result<()>
+    where
+        W: std::io::Write,
+    {
+        let writer = niffler::get_writer(Box::new(output), codec, niffler::compression::Level::One)
+            .map_err(error::Error::from)?;
+
+        self.write(writer)
+    }
+
+    /// Write solid compressed with `codec` like [Solid::serialize], return true if presence is write in roaring
+    /// format
+    ///
+    /// Representation is choose from [Solid::density] and record with [SPARSE_FLAG] in first byte, so
+    /// [Solid::from_stream] load the same representation
+    pub fn serialize_auto<W>(&self, output: W, codec: Codec) -> error::Result<bool>
+    where
+        W: std::io::Write,
+    {
+        self.serialize(output, codec)?;
+
+        Ok(self.write_sparse())
     }
 
-    pub(crate) fn get_raw_solid(&self) -> &BitBox<u8, Lsb0> {
-        &self.solid
+    /// Return true if solid is write in roaring format, only with feature roaring and density lower than
+    /// [SPARSE_DENSITY]
+    fn write_sparse(&self) -> bool {
+        cfg!(feature = "roaring") && self.density() < SPARSE_DENSITY
+    }
+
+    /// Write solid without compression
+    ///
+    /// With feature roaring, if density is lower than [SPARSE_DENSITY] presence is write in
+    /// roaring format and [SPARSE_FLAG] is set in first byte
+    pub(crate) fn write<W>(&self, mut output: W) -> error::Result<()>
+    where
+        W: std::io::Write,
+    {
+        #[cfg(feature = "roaring")]
+        if self.write_sparse() {
+            output.write_all(&[self.k | SPARSE_FLAG])?;
+
+            match &self.solid {
+                Presence::Sparse(solid) => solid.serialize_into(output)?,
+                Presence::Dense(_) => {
+                    if let Presence::Sparse(solid) = self.to_sparse().solid {
+                        solid.serialize_into(output)?
+                    }
+                }
+            }
+
+            return Ok(());
+        }
+
+        output.write_all(&[self.k])?;
+        output.write_all(self.get_raw_solid().as_raw_slice())?;
+
+        Ok(())
+    }
+
+    pub(crate) fn get_raw_solid(&self) -> std::borrow::Cow<'_, BitBox<u8, Lsb0>> {
+        match &self.solid {
+            Presence::Dense(solid) => std::borrow::Cow::Borrowed(solid),
+            #[cfg(feature = "roaring")]
+            Presence::Sparse(solid) => {
+                let mut dense =
+                    bitbox![u8, Lsb0; 0; cocktail::kmer::get_hash_space_size(self.k) as usize];
+                for hash in solid.iter() {
+                    dense.set(hash as usize, true);
+                }
+
+                std::borrow::Cow::Owned(dense)
+            }
+        }
     }
 }
 
@@ -122,7 +443,7 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTATTACAGTGCCGCCTGTTGAAAGCCCCAATCCC
     fn get_counter() -> crate::counter::Counter<u8> {
         let mut counter = crate::counter::Counter::<u8>::new(5);
 
-        counter.count_fasta(Box::new(FASTA_FILE), 1);
+        counter.count_fasta(Box::new(FASTA_FILE), 1).unwrap();
 
         counter
     }
@@ -139,6 +460,25 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTATTACAGTGCCGCCTGTTGAAAGCCCCAATCCC
         179, 64, 3, 25, 20, 226, 0, 32, 76, 1, 134, 48, 64, 7, 0, 200, 144, 98, 131, 2, 203,
     ];
 
+    /// Reader that always fail
+    struct Broken;
+
+    impl std::io::Read for Broken {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::from(std::io::ErrorKind::Other))
+        }
+    }
+
+    #[test]
+    fn niffler_error() {
+        let err = Solid::deserialize(Broken).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::Niffler(_))
+        ));
+    }
+
     #[test]
     fn new_solid() {
         let mut solid = Solid::new(5);
@@ -160,6 +500,65 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTATTACAGTGCCGCCTGTTGAAAGCCCCAATCCC
         assert_eq!(solid.get_raw_solid().as_raw_slice(), SOLID);
     }
 
+    #[test]
+    fn get_batch() {
+        let solid = get_solid();
+
+        let kmers = (0..cocktail::kmer::get_kmer_space_size(5)).collect::<Vec<u64>>();
+        let mut out = vec![false; kmers.len()];
+
+        solid.get_batch(&kmers, &mut out);
+
+        assert!(out.contains(&true));
+        for (kmer, status) in kmers.iter().zip(out.iter()) {
+            assert_eq!(*status, solid.get(*kmer));
+        }
+    }
+
+    #[test]
+    fn iter_solid() {
+        let solid = get_solid();
+
+        let hashes = solid.iter_solid().collect::<Vec<u64>>();
+        assert_eq!(hashes.len() as u64, solid.nb_solid());
+        assert!(hashes.windows(2).all(|w| w[0] < w[1]));
+        assert!(hashes
+            .iter()
+            .all(|hash| solid.get_canonic(crate::utils::hash2kmer(*hash))));
+
+        #[cfg(feature = "roaring")]
+        assert_eq!(solid.to_sparse().iter_solid().collect::<Vec<u64>>(), hashes);
+    }
+
+    #[test]
+    fn iter_solid_as_index() {
+        let solid = get_solid();
+
+        let raw = solid.get_raw_solid();
+        let manual = (0..raw.len())
+            .filter(|index| raw[*index])
+            .collect::<Vec<usize>>();
+
+        assert_eq!(
+            solid
+                .iter_solid()
+                .map(|i| i as usize)
+                .collect::<Vec<usize>>(),
+            manual
+        );
+        assert_eq!(manual.len() as u64, solid.nb_solid());
+
+        #[cfg(feature = "roaring")]
+        assert_eq!(
+            solid
+                .to_sparse()
+                .iter_solid()
+                .map(|i| i as usize)
+                .collect::<Vec<usize>>(),
+            manual
+        );
+    }
+
     const SOLID_SET: &[u8] = &[
         112, 64, 113, 143, 130, 8, 128, 4, 6, 52, 214, 0, 243, 8, 193, 1, 30, 4, 2, 97, 4, 70, 192,
         12, 16, 144, 133, 36, 192, 41, 1, 4, 218, 179, 140, 0, 0, 140, 242, 35, 90, 56, 205, 179,
@@ -216,4 +615,156 @@ AGGATAGAAGCTTAAGTACAAGATAATTCCCATAGAGGAAGGGTGGTATTACAGTGCCGCCTGTTGAAAGCCCCAATCCC
 
         Ok(())
     }
+
+    #[test]
+    fn from_path_expect() -> error::Result<()> {
+        let temp = tempfile::NamedTempFile::new()?;
+        get_counter().serialize().solid(0, &temp)?;
+
+        let path = temp.into_temp_path();
+
+        assert_eq!(Solid::from_path_expect(&path, 5)?.k(), 5);
+
+        let err = Solid::from_path_expect(&path, 7).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::KmerSizeNotMatch {
+                expected: 7,
+                found: 5
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn density() {
+        let solid = get_solid();
+
+        assert_eq!(solid.nb_solid(), 158);
+        assert_eq!(solid.density(), 158.0 / 512.0);
+        assert!(!solid.is_sparse());
+    }
+
+    #[cfg(feature = "roaring")]
+    #[test]
+    fn sparse() -> error::Result<()> {
+        let mut solid = Solid::new_sparse(5);
+
+        solid.set(cocktail::kmer::seq2bit(b"GTTCT"), true);
+        solid.set(cocktail::kmer::seq2bit(b"AAATG"), true);
+        solid.set(cocktail::kmer::seq2bit(b"AAATG"), false);
+
+        assert!(solid.is_sparse());
+        assert!(solid.get(cocktail::kmer::seq2bit(b"GTTCT")));
+        assert!(solid.get(cocktail::kmer::seq2bit(b"AGAAC")));
+        assert!(!solid.get(cocktail::kmer::seq2bit(b"AAATG")));
+        assert_eq!(solid.nb_solid(), 1);
+
+        let mut dense = solid.to_dense();
+        assert!(!dense.is_sparse());
+        assert_eq!(dense.nb_solid(), 1);
+
+        dense.extend(get_solid());
+        assert_eq!(dense.nb_solid(), 158);
+
+        let mut other = get_solid().to_sparse();
+        other.extend(solid.clone());
+        assert!(other.is_sparse());
+        assert_eq!(other.get_raw_solid().as_raw_slice(), SOLID);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "roaring")]
+    #[test]
+    fn sparse_round_trip() -> error::Result<()> {
+        let mut solid = Solid::new(5);
+        solid.set(cocktail::kmer::seq2bit(b"GTTCT"), true);
+
+        let mut file = vec![];
+        solid.write(&mut file)?;
+
+        assert_eq!(file[0], 5 | SPARSE_FLAG);
+        assert!(file.len() < 1 + 512 / 8);
+
+        let second = Solid::from_stream(&file[..])?;
+
+        assert!(second.is_sparse());
+        assert_eq!(second.k(), 5);
+        assert!(second.get(cocktail::kmer::seq2bit(b"GTTCT")));
+        assert_eq!(second.get_raw_solid(), solid.get_raw_solid());
+
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_auto() -> error::Result<()> {
+        // high density solid is always dense
+        let dense = get_solid();
+        let mut file = vec![];
+        assert!(!dense.serialize_auto(&mut file, Codec::Gzip)?);
+
+        let second = Solid::deserialize(&file[..])?;
+        assert!(!second.is_sparse());
+        assert_eq!(second, dense);
+
+        // low density solid is sparse only with roaring feature
+        let mut sparse = Solid::new(11);
+        sparse.set(cocktail::kmer::seq2bit(b"GTTCTGCAAAT"), true);
+        let mut file = vec![];
+        assert_eq!(
+            sparse.serialize_auto(&mut file, Codec::Gzip)?,
+            cfg!(feature = "roaring")
+        );
+
+        let second = Solid::deserialize(&file[..])?;
+        assert_eq!(second.is_sparse(), cfg!(feature = "roaring"));
+        assert_eq!(second.k(), 11);
+        assert_eq!(second.nb_solid(), 1);
+        assert_eq!(second.get_raw_solid(), sparse.get_raw_solid());
+
+        // legacy dense file without flag
+        let mut legacy = vec![5];
+        legacy.extend(SOLID);
+        assert_eq!(Solid::from_stream(&legacy[..])?, dense);
+
+        Ok(())
+    }
+
+    #[test]
+    fn intersect() {
+        let mut solid = get_solid();
+        let mut other = Solid::new(5);
+
+        other.set(42, true);
+        other.set(44, true);
+
+        solid.intersect(other);
+
+        assert_eq!(solid.nb_solid(), 1);
+        assert!(solid.get(42));
+        assert!(!solid.get(44));
+    }
+
+    #[test]
+    fn serialize() -> error::Result<()> {
+        let mut solid = get_solid();
+        let mut other = get_solid();
+        other.set(cocktail::kmer::seq2bit(b"GTTCT"), false);
+        solid.intersect(other);
+
+        for codec in [Codec::No, Codec::Gzip] {
+            let temp = tempfile::NamedTempFile::new()?;
+            solid.serialize(std::fs::File::create(temp.path())?, codec)?;
+
+            let second = Solid::deserialize(std::fs::File::open(temp.path())?)?;
+
+            assert_eq!(second.k(), 5);
+            assert_eq!(second.nb_solid(), 157);
+            assert_eq!(second.get_raw_solid(), solid.get_raw_solid());
+        }
+
+        Ok(())
+    }
 }
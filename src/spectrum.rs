@@ -1,15 +1,19 @@
 //! Define Spectrum struct
 
 /* std use */
+use std::io::BufRead as _;
+use std::io::Read as _;
 
 /* crate use */
 
 /* local use */
+use crate::counter;
+use crate::error;
 
 /// Based on Kmergenie we assume kmer spectrum is a mixture of Pareto law and some Gaussians law
 /// Erroneous kmer follow Pareto law, Gaussians law represente true and repetitive kmer
 /// We use this property to found the threshold to remove most Erroneous kmer and keep Many True kmer
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Copy, clap::ValueEnum)]
 pub enum ThresholdMethod {
     /// The first local minimum match with the intersection of Pareto and Gaussians
     FirstMinimum,
@@ -24,6 +28,20 @@ pub enum ThresholdMethod {
     PercentAtLeast,
 }
 
+/// Threshold found by a [ThresholdMethod] with kmer removed by this threshold, kmer with a count lower or equal to
+/// threshold are removed
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ThresholdResult {
+    /// Threshold value
+    pub threshold: u8,
+
+    /// Number of distinct kmer with a non zero count lower or equal to threshold
+    pub removed_distinct: u64,
+
+    /// Sum of count of kmer lower or equal to threshold
+    pub removed_total: u64,
+}
+
 /// A struct to represent kmer spectrum and usefull corresponding function
 
 pub struct Spectrum {
@@ -31,6 +49,13 @@ pub struct Spectrum {
 }
 
 impl Spectrum {
+    /// Create a new empty Spectrum, with `max_count` + 1 bucket
+    pub fn new(max_count: usize) -> Self {
+        Self {
+            data: vec![0u64; max_count + 1].into_boxed_slice(),
+        }
+    }
+
     /// Create a new Spectrum with count in `counter`
     pub fn from_count<T>(counts: &[T]) -> Self
     where
@@ -47,6 +72,188 @@ impl Spectrum {
         Self { data }
     }
 
+    /// Create a new Spectrum with count in `counts`, count upper than `max_count` are store in last bucket
+    pub fn from_count_bounded<T>(counts: &[T], max_count: usize) -> Self
+    where
+        T: std::convert::TryInto<usize> + std::marker::Copy,
+    {
+        let mut data = vec![0u64; max_count + 1].into_boxed_slice();
+
+        for count in counts {
+            let index = (*count)
+                .try_into()
+                .map_or(max_count, |value| value.min(max_count));
+            data[index] = data[index].saturating_add(1);
+        }
+
+        Self { data }
+    }
+
+    /// Create a new Spectrum from a pcon file, count upper than `max_count` are store in last bucket
+    ///
+    /// Count type is read in file header
+    pub fn from_pcon_path<P>(path: P, max_count: usize) -> error::Result<Self>
+    where
+        P: std::convert::AsRef<std::path::Path>,
+    {
+        let mut input = std::io::BufReader::new(std::fs::File::open(path)?);
+
+        let mut header = [0u8; 2];
+        input.read_exact(&mut header)?;
+        let input = std::io::Cursor::new(header).chain(input);
+
+        match header[1] {
+            1 => Ok(Self::from_count_bounded(
+                counter::Counter::<u8>::from_stream(input)?.raw(),
+                max_count,
+            )),
+            2 => Ok(Self::from_count_bounded(
+                counter::Counter::<u16>::from_stream(input)?.raw(),
+                max_count,
+            )),
+            4 => Ok(Self::from_count_bounded(
+                counter::Counter::<u32>::from_stream(input)?.raw(),
+                max_count,
+            )),
+            8 => Ok(Self::from_count_bounded(
+                counter::Counter::<u64>::from_stream(input)?.raw(),
+                max_count,
+            )),
+            16 => Ok(Self::from_count_bounded(
+                counter::Counter::<u128>::from_stream(input)?.raw(),
+                max_count,
+            )),
+            width => Err(error::Error::CountWidthNotSupported(width).into()),
+        }
+    }
+
+    /// Create a new Spectrum from `count,freq` rows write by [Spectrum::write_csv], count upper than `max_count`
+    /// are store in last bucket, header line is optional
+    pub fn from_histogram_csv<R>(input: R, max_count: usize) -> error::Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let mut data = vec![0u64; max_count + 1].into_boxed_slice();
+
+        for (index, line) in std::io::BufReader::new(input).lines().enumerate() {
+            let line = line?;
+            if line.is_empty() || (index == 0 && line == "count,freq") {
+                continue;
+            }
+
+            let (count, freq) = line
+                .split_once(',')
+                .and_then(|(count, freq)| {
+                    Some((count.parse::<usize>().ok()?, freq.parse::<u64>().ok()?))
+                })
+                .ok_or(error::Error::MalformedHistogram(index + 1))?;
+
+            let bucket = count.min(max_count);
+            data[bucket] = data[bucket].saturating_add(freq);
+        }
+
+        Ok(Self { data })
+    }
+
+    /// Add histogram of counter store in pcon file `path` to this Spectrum, count upper than `max_count` are store in
+    /// last bucket. Counter is drop before return, so only one counter is store in memory.
+    ///
+    /// Each file must count a disjoint set of kmer (e.g. shards partitioned by hash), a kmer observed in many files is
+    /// count in many buckets and result doesn't match spectrum of merged counter. Bucket zero is set to number of
+    /// kmer of hash space not observed in any file.
+    pub fn accumulate_disjoint_from_pcon_path<P>(
+        &mut self,
+        path: P,
+        max_count: usize,
+    ) -> error::Result<()>
+    where
+        P: std::convert::AsRef<std::path::Path>,
+    {
+        let other = Self::from_pcon_path(path, max_count)?;
+
+        if other.data.len() > self.data.len() {
+            let mut data = self.data.to_vec();
+            data.resize(other.data.len(), 0);
+            self.data = data.into_boxed_slice();
+        }
+
+        for (bucket, value) in self.data.iter_mut().zip(other.data.iter()).skip(1) {
+            *bucket = bucket.saturating_add(*value);
+        }
+
+        let observed = self.total_observations() - self.data[0];
+        self.data[0] = other.total_observations().saturating_sub(observed);
+
+        Ok(())
+    }
+
+    /// Add `number` kmer with a count of zero, useful when counts come from a structure that store only observed kmer
+    pub fn add_unobserved(&mut self, number: u64) {
+        self.data[0] = self.data[0].saturating_add(number);
+    }
+
+    /// Drop buckets after the last non zero bucket, count of each bucket is unchanged, bucket zero is always keep
+    pub fn trim(&mut self) {
+        let len = self
+            .data
+            .iter()
+            .rposition(|freq| *freq != 0)
+            .map_or(1, |last| last + 1);
+
+        if len < self.data.len() {
+            self.data = self.data[..len].to_vec().into_boxed_slice();
+        }
+    }
+
+    /// Number of kmer in spectrum, sum of frequency of each count, bucket zero included
+    pub fn total_observations(&self) -> u64 {
+        self.data
+            .iter()
+            .fold(0u64, |acc, freq| acc.saturating_add(*freq))
+    }
+
+    /// Sum of count of all kmer, last bucket count for its index
+    pub fn total_kmers(&self) -> u64 {
+        self.data
+            .iter()
+            .enumerate()
+            .fold(0u64, |acc, (count, freq)| {
+                acc.saturating_add((count as u64).saturating_mul(*freq))
+            })
+    }
+
+    /// Mean count of kmer, [Spectrum::total_kmers] divide by [Spectrum::total_observations], 0.0 if spectrum is empty
+    pub fn mean_count(&self) -> f64 {
+        match self.total_observations() {
+            0 => 0.0,
+            observations => self.total_kmers() as f64 / observations as f64,
+        }
+    }
+
+    /// Count value at the `p`-th percentile of observed kmer, smallest count where fraction of observed kmer with a
+    /// lower or equal count reach `p`, bucket zero is ignored. None if `p` isn't in [0, 1] or no kmer is observed
+    pub fn percentile(&self, p: f64) -> Option<u64> {
+        if !(0.0..=1.0).contains(&p) {
+            return None;
+        }
+
+        let total: u64 = self.data.iter().skip(1).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut cumulative_sum = 0;
+        for (index, value) in self.data.iter().enumerate().skip(1) {
+            cumulative_sum += value;
+
+            if *value != 0 && (cumulative_sum as f64 / total as f64) >= p {
+                return Some(index as u64);
+            }
+        }
+
+        None
+    }
+
     /// Found threshold matching with method
     pub fn get_threshold(&self, method: ThresholdMethod, params: f64) -> Option<u8> {
         match method {
@@ -57,6 +264,63 @@ impl Spectrum {
         }
     }
 
+    /// Found threshold matching with method, with number of distinct kmer and sum of count removed by it
+    pub fn get_threshold_result(
+        &self,
+        method: ThresholdMethod,
+        params: f64,
+    ) -> Option<ThresholdResult> {
+        self.get_threshold(method, params).map(|threshold| {
+            let removed = self
+                .data
+                .iter()
+                .enumerate()
+                .take(threshold as usize + 1)
+                .skip(1);
+
+            ThresholdResult {
+                threshold,
+                removed_distinct: removed.clone().map(|(_, freq)| *freq).sum(),
+                removed_total: removed
+                    .map(|(count, freq)| (count as u64).saturating_mul(*freq))
+                    .sum(),
+            }
+        })
+    }
+
+    /// Get local maximum of spectrum, as (count, frequency) pairs in increasing count order
+    pub fn peaks(&self) -> Vec<(u64, u64)> {
+        self.data
+            .windows(3)
+            .enumerate()
+            .filter(|(_, d)| d[0] < d[1] && d[1] >= d[2])
+            .map(|(i, d)| (i as u64 + 1, d[1]))
+            .collect()
+    }
+
+    /// Get local minimum of spectrum, as (count, frequency) pairs in increasing count order
+    pub fn valleys(&self) -> Vec<(u64, u64)> {
+        self.data
+            .windows(3)
+            .enumerate()
+            .filter(|(_, d)| d[0] > d[1] && d[1] <= d[2])
+            .map(|(i, d)| (i as u64 + 1, d[1]))
+            .collect()
+    }
+
+    /// Write spectrum in csv format, one line by count with its frequency
+    pub fn write_csv<W>(&self, mut output: W) -> error::Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(output, "count,freq")?;
+        for (count, freq) in self.data.iter().enumerate() {
+            writeln!(output, "{},{}", count, freq)?;
+        }
+
+        Ok(())
+    }
+
     fn first_minimum(&self) -> Option<u8> {
         for (i, d) in self.data.windows(2).enumerate() {
             if d[1] > d[0] {
@@ -115,7 +379,6 @@ impl Spectrum {
 mod tests {
 
     use super::*;
-    use crate::counter;
 
     fn generate_counter() -> counter::Counter<u8> {
         let mut counter: counter::Counter<u8> = counter::Counter::<u8>::new(5);
@@ -154,6 +417,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_counter_bounded() {
+        let counter = generate_counter();
+        let mut spectrum = Spectrum::from_count_bounded(counter.raw(), 2);
+
+        assert_eq!(spectrum.get_raw_histogram(), &[0, 0, 512]);
+
+        spectrum.add_unobserved(10);
+        assert_eq!(spectrum.get_raw_histogram(), &[10, 0, 512]);
+    }
+
+    #[test]
+    fn from_pcon_path() -> error::Result<()> {
+        let counter = generate_counter();
+
+        let temp = tempfile::NamedTempFile::new()?;
+        counter
+            .serialize()
+            .pcon(std::fs::File::create(temp.path())?)?;
+
+        let spectrum = Spectrum::from_pcon_path(temp.path(), 4)?;
+        assert_eq!(spectrum.get_raw_histogram(), &[0, 0, 511, 1, 0]);
+
+        let mut counter = counter::Counter::<u32>::new(5);
+        counter::Counter::<u32>::inc(counter.raw_mut(), 0);
+        counter
+            .serialize()
+            .pcon(std::fs::File::create(temp.path())?)?;
+
+        let spectrum = Spectrum::from_pcon_path(temp.path(), 2)?;
+        assert_eq!(spectrum.get_raw_histogram(), &[511, 1, 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn accumulate_disjoint_from_pcon_path() -> error::Result<()> {
+        let counter = generate_counter();
+
+        let mut even = counter.clone();
+        let mut odd = counter.clone();
+        for (hash, (even, odd)) in even
+            .raw_mut()
+            .iter_mut()
+            .zip(odd.raw_mut().iter_mut())
+            .enumerate()
+        {
+            if hash % 2 == 0 {
+                *odd = 0;
+            } else {
+                *even = 0;
+            }
+        }
+
+        let even_temp = tempfile::NamedTempFile::new()?;
+        even.serialize()
+            .pcon(std::fs::File::create(even_temp.path())?)?;
+        let odd_temp = tempfile::NamedTempFile::new()?;
+        odd.serialize()
+            .pcon(std::fs::File::create(odd_temp.path())?)?;
+
+        let mut spectrum = Spectrum::new(4);
+        spectrum.accumulate_disjoint_from_pcon_path(even_temp.path(), 4)?;
+        spectrum.accumulate_disjoint_from_pcon_path(odd_temp.path(), 4)?;
+
+        let mut merged = even.clone();
+        merged.merge(&odd)?;
+        let merged = Spectrum::from_count_bounded(merged.raw(), 4);
+
+        assert_eq!(spectrum.get_raw_histogram(), &[0, 0, 511, 1, 0]);
+        assert_eq!(spectrum.get_raw_histogram(), merged.get_raw_histogram());
+
+        Ok(())
+    }
+
+    #[test]
+    fn accumulate_overlapping_pcon_path() -> error::Result<()> {
+        let counter = generate_counter();
+
+        let mut even = counter.clone();
+        for (hash, count) in even.raw_mut().iter_mut().enumerate() {
+            if hash % 2 != 0 {
+                *count = 0;
+            }
+        }
+
+        let full_temp = tempfile::NamedTempFile::new()?;
+        counter
+            .serialize()
+            .pcon(std::fs::File::create(full_temp.path())?)?;
+        let even_temp = tempfile::NamedTempFile::new()?;
+        even.serialize()
+            .pcon(std::fs::File::create(even_temp.path())?)?;
+
+        let mut spectrum = Spectrum::new(4);
+        spectrum.accumulate_disjoint_from_pcon_path(full_temp.path(), 4)?;
+        spectrum.accumulate_disjoint_from_pcon_path(even_temp.path(), 4)?;
+
+        let mut merged = counter.clone();
+        merged.merge(&even)?;
+        let merged = Spectrum::from_count_bounded(merged.raw(), 4);
+
+        assert_eq!(merged.get_raw_histogram(), &[0, 0, 256, 0, 256]);
+        assert_eq!(spectrum.get_raw_histogram(), &[0, 0, 766, 2, 0]);
+
+        Ok(())
+    }
+
     static SPECTRUM: [u64; 256] = [
         992273316, 64106898, 6792586, 1065818, 220444, 62400, 36748, 54062, 100806, 178868, 287058,
         424184, 568742, 705680, 805332, 871544, 874546, 827252, 744428, 636722, 523488, 418036,
@@ -182,6 +553,163 @@ mod tests {
         );
     }
 
+    #[test]
+    fn threshold_result() {
+        let spectrum = Spectrum {
+            data: Box::new(SPECTRUM),
+        };
+
+        assert_eq!(
+            spectrum.get_threshold_result(ThresholdMethod::FirstMinimum, 0.1),
+            Some(ThresholdResult {
+                threshold: 6,
+                removed_distinct: 72284894,
+                removed_total: 82303788,
+            })
+        );
+
+        let flat = Spectrum {
+            data: (0..256).map(|_| 1).collect::<Box<[u64]>>(),
+        };
+        assert_eq!(
+            flat.get_threshold_result(ThresholdMethod::FirstMinimum, 0.1),
+            None
+        );
+    }
+
+    #[test]
+    fn statistics() {
+        let spectrum = Spectrum {
+            data: Box::new(SPECTRUM),
+        };
+
+        assert_eq!(spectrum.total_observations(), 1 << 30);
+        assert_eq!(spectrum.total_kmers(), 235498466);
+        assert!((spectrum.mean_count() - 0.219325037673).abs() < 1e-9);
+
+        let empty = Spectrum::new(4);
+        assert_eq!(empty.total_observations(), 0);
+        assert_eq!(empty.total_kmers(), 0);
+        assert_eq!(empty.mean_count(), 0.0);
+    }
+
+    #[test]
+    fn percentile() {
+        let spectrum = Spectrum {
+            data: Box::new(SPECTRUM),
+        };
+
+        assert_eq!(spectrum.percentile(0.0), Some(1));
+        assert_eq!(spectrum.percentile(0.5), Some(1));
+        assert_eq!(spectrum.percentile(0.9), Some(11));
+        assert_eq!(spectrum.percentile(0.99), Some(23));
+        assert_eq!(spectrum.percentile(1.0), Some(255));
+
+        assert_eq!(spectrum.percentile(-0.1), None);
+        assert_eq!(spectrum.percentile(1.2), None);
+        assert_eq!(Spectrum::new(4).percentile(0.5), None);
+    }
+
+    #[test]
+    fn trim() {
+        let spectrum = Spectrum {
+            data: Box::new(SPECTRUM),
+        };
+
+        let mut trimmed = Spectrum {
+            data: SPECTRUM
+                .iter()
+                .copied()
+                .chain(std::iter::repeat(0).take(1000))
+                .collect::<Box<[u64]>>(),
+        };
+        trimmed.trim();
+
+        assert_eq!(trimmed.get_raw_histogram().len(), SPECTRUM.len());
+        assert_eq!(trimmed.get_raw_histogram(), spectrum.get_raw_histogram());
+        for (method, params) in [
+            (ThresholdMethod::FirstMinimum, 0.1),
+            (ThresholdMethod::Rarefaction, 0.1),
+            (ThresholdMethod::PercentAtMost, 0.1),
+            (ThresholdMethod::PercentAtLeast, 0.1),
+        ] {
+            assert_eq!(
+                trimmed.get_threshold(method, params),
+                spectrum.get_threshold(method, params)
+            );
+        }
+
+        let mut empty = Spectrum::new(255);
+        empty.trim();
+        assert_eq!(empty.get_raw_histogram(), &[0]);
+    }
+
+    #[test]
+    fn peaks_valleys() {
+        let spectrum = Spectrum {
+            data: Box::new(SPECTRUM),
+        };
+
+        assert_eq!(spectrum.valleys()[0], (6, 36748));
+        assert_eq!(spectrum.peaks()[0], (16, 874546));
+
+        let flat = Spectrum {
+            data: (0..256).map(|_| 1).collect::<Box<[u64]>>(),
+        };
+        assert!(flat.peaks().is_empty());
+        assert!(flat.valleys().is_empty());
+    }
+
+    #[test]
+    fn write_csv() -> error::Result<()> {
+        let spectrum = Spectrum {
+            data: vec![4, 0, 2].into_boxed_slice(),
+        };
+
+        let mut output = Vec::new();
+        spectrum.write_csv(&mut output)?;
+
+        assert_eq!(output, b"count,freq\n0,4\n1,0\n2,2\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_histogram_csv() -> error::Result<()> {
+        let spectrum = Spectrum {
+            data: Box::new(SPECTRUM),
+        };
+
+        let mut output = Vec::new();
+        spectrum.write_csv(&mut output)?;
+
+        let reload = Spectrum::from_histogram_csv(&output[..], SPECTRUM.len() - 1)?;
+        assert_eq!(reload.get_raw_histogram(), spectrum.get_raw_histogram());
+        for method in [
+            ThresholdMethod::FirstMinimum,
+            ThresholdMethod::Rarefaction,
+            ThresholdMethod::PercentAtMost,
+            ThresholdMethod::PercentAtLeast,
+        ] {
+            assert_eq!(
+                reload.get_threshold(method, 0.1),
+                spectrum.get_threshold(method, 0.1)
+            );
+        }
+
+        // count upper than max_count are store in last bucket
+        let bounded = Spectrum::from_histogram_csv(&b"count,freq\n0,4\n1,2\n5,3\n"[..], 2)?;
+        assert_eq!(bounded.get_raw_histogram(), &[4, 2, 3]);
+
+        let err = Spectrum::from_histogram_csv(&b"count,freq\n0,4\n1;2\n"[..], 2).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<error::Error>(),
+            Some(error::Error::MalformedHistogram(3))
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn failled_first_local_min() {
         let tmp = (0..256).map(|_| 1).collect::<Box<[u64]>>();
@@ -0,0 +1,135 @@
+//! Run sweep command
+
+/* std use */
+use std::io::Write as _;
+
+/* crate use */
+
+/* project use */
+use crate::cli;
+use crate::counter;
+use crate::error;
+use crate::spectrum;
+#[cfg(any(feature = "gfa", feature = "sam"))]
+use crate::utils;
+
+/// Above this kmer size, kmer are count in a hash map instead of a dense array
+pub const DENSE_MAX_K: u8 = 15;
+
+/// Spectrum is bounded to this count, threshold method work on u8
+const MAX_COUNT: usize = u8::MAX as usize;
+
+/// Run sweep
+pub fn sweep(params: cli::Sweep) -> error::Result<()> {
+    let mut output = params.output()?;
+
+    writeln!(output, "k,threshold,distinct,solid")?;
+
+    for k in params.kmer_sizes() {
+        log::info!("Start count kmer for k {}", k);
+        let (spectrum, counts) = if k <= DENSE_MAX_K {
+            let mut counter = counter::Counter::<crate::CountType>::new(k);
+            match params.format() {
+                cli::Format::Fasta | cli::Format::Auto => {
+                    counter.count_fasta(params.inputs()?, params.record_buffer())?
+                }
+                #[cfg(feature = "fastq")]
+                cli::Format::Fastq => {
+                    counter.count_fastq(params.inputs()?, params.record_buffer())?
+                }
+                #[cfg(feature = "gfa")]
+                cli::Format::Gfa => counter.count_gfa(params.inputs()?, params.record_buffer())?,
+                #[cfg(feature = "sam")]
+                cli::Format::Sam => counter.count_sam(params.inputs()?, params.record_buffer())?,
+            }
+
+            let counts = counter
+                .raw_noatomic()
+                .iter()
+                .filter(|x| **x != 0)
+                .cloned()
+                .collect::<Vec<crate::CountTypeNoAtomic>>();
+
+            (
+                spectrum::Spectrum::from_count_bounded(counter.raw_noatomic(), MAX_COUNT),
+                counts,
+            )
+        } else {
+            let counts = sparse_count(k, params.inputs()?, params.format())?
+                .into_values()
+                .collect::<Vec<crate::CountTypeNoAtomic>>();
+
+            let mut spectrum = spectrum::Spectrum::from_count_bounded(&counts, MAX_COUNT);
+            spectrum.add_unobserved(
+                cocktail::kmer::get_hash_space_size(k).saturating_sub(counts.len() as u64),
+            );
+
+            (spectrum, counts)
+        };
+        log::info!("End count kmer for k {}", k);
+
+        let threshold = spectrum.get_threshold(spectrum::ThresholdMethod::FirstMinimum, 0.0);
+        let solid = counts
+            .iter()
+            .filter(|x| **x as u64 > threshold.unwrap_or(0) as u64)
+            .count();
+
+        match threshold {
+            Some(value) => writeln!(output, "{},{},{},{}", k, value, counts.len(), solid)?,
+            None => writeln!(output, "{},NA,{},{}", k, counts.len(), solid)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Count kmer of input in a hash map, only observed kmer are store
+fn sparse_count(
+    k: u8,
+    input: Box<dyn std::io::BufRead>,
+    format: cli::Format,
+) -> error::Result<rustc_hash::FxHashMap<u64, crate::CountTypeNoAtomic>> {
+    counter::check_kmer_size(k)?;
+
+    let mut counts = rustc_hash::FxHashMap::default();
+
+    let options = counter::CountOptions::default();
+    let mut add = |sequence: &[u8]| {
+        options.kmerize(sequence, k, |canonical| {
+            counts
+                .entry(canonical >> 1)
+                .and_modify(|c: &mut crate::CountTypeNoAtomic| *c = c.saturating_add(1))
+                .or_insert(1);
+        });
+    };
+
+    match format {
+        cli::Format::Fasta | cli::Format::Auto => {
+            let mut reader = noodles::fasta::Reader::new(input);
+            for record in reader.records() {
+                add(record?.sequence().as_ref());
+            }
+        }
+        #[cfg(feature = "fastq")]
+        cli::Format::Fastq => {
+            let mut reader = noodles::fastq::Reader::new(input);
+            for record in reader.records() {
+                add(record?.sequence().as_ref());
+            }
+        }
+        #[cfg(feature = "gfa")]
+        cli::Format::Gfa => {
+            for segment in utils::gfa_segments(input) {
+                add(&segment?);
+            }
+        }
+        #[cfg(feature = "sam")]
+        cli::Format::Sam => {
+            for sequence in utils::sam_sequences(input) {
+                add(&sequence?);
+            }
+        }
+    }
+
+    Ok(counts)
+}
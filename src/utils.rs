@@ -5,6 +5,8 @@
 /* crate use */
 
 /* project use */
+#[cfg(any(feature = "parallel", feature = "gfa", feature = "sam"))]
+use crate::error;
 
 /// Initialize counter
 pub fn init_data<T>(k: u8, value: T) -> Box<[T]>
@@ -35,45 +37,238 @@ where
 }
 
 #[cfg(feature = "parallel")]
-/// Populate record buffer with content of iterator
+/// Populate record buffer with content of iterator, return false when iterator is empty
 pub fn populate_buffer(
     iter: &mut noodles::fasta::reader::Records<'_, Box<dyn std::io::BufRead>>,
     records: &mut Vec<noodles::fasta::Record>,
     record_buffer: u64,
-) -> bool {
+) -> error::Result<bool> {
     records.clear();
 
     for i in 0..record_buffer {
-        if let Some(Ok(record)) = iter.next() {
-            records.push(record);
+        if let Some(record) = iter.next() {
+            records.push(record?);
         } else {
             records.truncate(i as usize);
-            return false;
+            return Ok(false);
         }
     }
 
-    true
+    Ok(true)
 }
 
 #[cfg(all(feature = "parallel", feature = "fastq"))]
-/// Populate record buffer with content of iterator
+/// Populate record buffer with content of iterator, return false when iterator is empty
 pub fn populate_bufferq(
     iter: &mut noodles::fastq::reader::Records<'_, Box<dyn std::io::BufRead>>,
     records: &mut Vec<noodles::fastq::Record>,
     record_buffer: u64,
-) -> bool {
+) -> error::Result<bool> {
     records.clear();
 
     for i in 0..record_buffer {
-        if let Some(Ok(record)) = iter.next() {
-            records.push(record);
+        if let Some(record) = iter.next() {
+            records.push(record?);
         } else {
             records.truncate(i as usize);
-            return false;
+            return Ok(false);
         }
     }
 
-    true
+    Ok(true)
+}
+
+#[cfg(feature = "parallel")]
+/// Reused sequence with a capacity upper than this value is shrink before its reuse, so a very long record doesn't
+/// keep its memory until end of count
+pub const MAX_RETAINED_CAPACITY: usize = 1 << 20;
+
+#[cfg(feature = "parallel")]
+/// Fill sequence buffer with sequence of next records of `reader`, allocation of sequences already in buffer are
+/// reuse, return false when reader is empty
+///
+/// Capacity of each reused sequence is bound by [MAX_RETAINED_CAPACITY] and capacity of buffer by twice
+/// `record_buffer`
+pub fn refill_buffer<R>(
+    reader: &mut noodles::fasta::Reader<R>,
+    sequences: &mut Vec<Vec<u8>>,
+    record_buffer: u64,
+) -> error::Result<bool>
+where
+    R: std::io::BufRead,
+{
+    let mut definition = String::new();
+
+    for i in 0..record_buffer as usize {
+        definition.clear();
+        if reader.read_definition(&mut definition)? == 0 {
+            sequences.truncate(i);
+            return Ok(false);
+        }
+        definition.parse::<noodles::fasta::record::Definition>()?;
+
+        if i == sequences.len() {
+            sequences.push(Vec::new());
+        }
+        sequences[i].clear();
+        if sequences[i].capacity() > MAX_RETAINED_CAPACITY {
+            sequences[i].shrink_to(MAX_RETAINED_CAPACITY);
+        }
+        reader.read_sequence(&mut sequences[i])?;
+    }
+    sequences.truncate(record_buffer as usize);
+    if sequences.capacity() > 2 * record_buffer as usize {
+        sequences.shrink_to(record_buffer as usize);
+    }
+
+    Ok(true)
+}
+
+#[cfg(all(feature = "parallel", feature = "fastq"))]
+/// Fill record buffer with next records of `reader`, records already in buffer are reuse, return false when
+/// reader is empty
+pub fn refill_bufferq<R>(
+    reader: &mut noodles::fastq::Reader<R>,
+    records: &mut Vec<noodles::fastq::Record>,
+    record_buffer: u64,
+) -> error::Result<bool>
+where
+    R: std::io::BufRead,
+{
+    for i in 0..record_buffer as usize {
+        if i == records.len() {
+            records.push(noodles::fastq::Record::default());
+        }
+
+        if reader.read_record(&mut records[i])? == 0 {
+            records.truncate(i);
+            return Ok(false);
+        }
+    }
+    records.truncate(record_buffer as usize);
+
+    Ok(true)
+}
+
+#[cfg(feature = "gfa")]
+/// Iterate over sequence of gfa segments (`S` line), segment without sequence (`*`) are skip
+pub fn gfa_segments(
+    input: Box<dyn std::io::BufRead>,
+) -> impl Iterator<Item = error::Result<Vec<u8>>> {
+    std::io::BufRead::split(input, b'\n').filter_map(|line| {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e.into())),
+        };
+        let line = line.strip_suffix(b"\r").unwrap_or(&line);
+
+        let mut fields = line.split(|c| *c == b'\t');
+        if fields.next() != Some(&b"S"[..]) {
+            return None;
+        }
+
+        match fields.nth(1) {
+            None | Some(b"*") => None,
+            Some(sequence) => Some(Ok(sequence.to_vec())),
+        }
+    })
+}
+
+#[cfg(feature = "sam")]
+/// Iterate over SEQ field of sam alignments read by noodles, unmapped alignments are keep, secondary and
+/// supplementary alignments and alignment without sequence (`*`) are skip
+pub fn sam_sequences(
+    input: Box<dyn std::io::BufRead>,
+) -> impl Iterator<Item = error::Result<Vec<u8>>> {
+    let mut reader = noodles::sam::io::Reader::new(input);
+    let mut header = Some(reader.read_header());
+    let mut record = noodles::sam::Record::default();
+    let mut index = 0;
+
+    // invalid data error of noodles are report with number of alignment
+    let malformed = |e: std::io::Error, index: usize| -> anyhow::Error {
+        match e.kind() {
+            std::io::ErrorKind::InvalidData => error::Error::MalformedSam(index).into(),
+            _ => e.into(),
+        }
+    };
+
+    std::iter::from_fn(move || loop {
+        if let Some(Err(e)) = header.take() {
+            return Some(Err(e.into()));
+        }
+
+        index += 1;
+        match reader.read_record(&mut record) {
+            Ok(0) => return None,
+            Ok(_) => (),
+            Err(e) => return Some(Err(malformed(e, index))),
+        }
+
+        let flags = match record.flags() {
+            Ok(flags) => flags,
+            Err(e) => return Some(Err(malformed(e, index))),
+        };
+        if flags.is_secondary() || flags.is_supplementary() {
+            continue;
+        }
+
+        let sequence: &[u8] = record.sequence().as_ref();
+        if !sequence.is_empty() {
+            return Some(Ok(sequence.to_vec()));
+        }
+    })
+}
+
+#[cfg(all(feature = "parallel", any(feature = "gfa", feature = "sam")))]
+/// Populate segment buffer with content of iterator, return false when iterator is empty
+pub fn populate_segments<I>(
+    iter: &mut I,
+    segments: &mut Vec<Vec<u8>>,
+    record_buffer: u64,
+) -> error::Result<bool>
+where
+    I: Iterator<Item = error::Result<Vec<u8>>>,
+{
+    segments.clear();
+
+    for i in 0..record_buffer {
+        if let Some(segment) = iter.next() {
+            segments.push(segment?);
+        } else {
+            segments.truncate(i as usize);
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Get canonical kmer associate to a hash
+pub fn hash2kmer(hash: u64) -> u64 {
+    if cocktail::kmer::parity_even(hash) {
+        hash << 1
+    } else {
+        (hash << 1) ^ 0b1
+    }
+}
+
+/// Shannon entropy of nucleotides of kmer, 0 for an homopolymer and 2 when each nucleotide is equally present
+pub fn complexity(kmer: &[u8]) -> f64 {
+    let mut counts = [0usize; 4];
+    for nuc in kmer {
+        counts[((nuc >> 1) & 0b11) as usize] += 1;
+    }
+
+    let len = kmer.len() as f64;
+    counts
+        .iter()
+        .filter(|count| **count != 0)
+        .map(|count| {
+            let freq = *count as f64 / len;
+            -freq * freq.log2()
+        })
+        .sum()
 }
 
 /// Reverse complement a kmer
@@ -85,14 +280,94 @@ pub fn revcomp(kmer: &[u8]) -> Vec<u8> {
         .collect()
 }
 
-/// Get canonical form of kmer
+/// Get canonical form of kmer, kmer is uppercase before comparison with its reverse complement
 pub fn canonical(kmer: &[u8]) -> Vec<u8> {
-    let reverse = revcomp(kmer);
+    let forward = kmer.to_ascii_uppercase();
+    let reverse = revcomp(&forward);
 
-    if *reverse < *kmer {
+    if reverse < forward {
         reverse
     } else {
-        kmer.to_ascii_uppercase()
+        forward
+    }
+}
+
+#[cfg(feature = "checksum")]
+/// Writer that compute sha256 of bytes write in inner writer, digest is write in `<path>.sha256` in sha256sum
+/// format by [ChecksumWriter::finish], nothing is write if writer is drop before
+pub struct ChecksumWriter<W>
+where
+    W: std::io::Write,
+{
+    inner: W,
+    hasher: sha2::Sha256,
+    path: std::path::PathBuf,
+}
+
+#[cfg(feature = "checksum")]
+impl<W> ChecksumWriter<W>
+where
+    W: std::io::Write,
+{
+    /// Create a new ChecksumWriter, `path` is the path of file write by `inner`
+    pub fn new<P>(inner: W, path: P) -> Self
+    where
+        P: std::convert::AsRef<std::path::Path>,
+    {
+        use sha2::Digest as _;
+
+        Self {
+            inner,
+            hasher: sha2::Sha256::new(),
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Path of sha256 sidecar file
+    pub fn sidecar_path(&self) -> std::path::PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".sha256");
+
+        path.into()
+    }
+
+    /// Flush inner writer and write digest of all bytes write in sidecar file
+    pub fn finish(mut self) -> error::Result<()> {
+        use sha2::Digest as _;
+
+        self.inner.flush()?;
+
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        std::fs::write(
+            self.sidecar_path(),
+            format!("{:x}  {}\n", self.hasher.finalize(), name),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "checksum")]
+impl<W> std::io::Write for ChecksumWriter<W>
+where
+    W: std::io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        use sha2::Digest as _;
+
+        let len = self.inner.write(buf)?;
+        self.hasher.update(&buf[..len]);
+
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
     }
 }
 
@@ -119,5 +394,125 @@ mod tests {
         assert_eq!(canonical(b"AGAGGA"), b"AGAGGA".to_vec());
 
         assert_eq!(canonical(b"AttACAGTGC"), b"ATTACAGTGC".to_vec());
+
+        assert_eq!(canonical(b"tCCGTAACGA"), canonical(b"TCCGTAACGA"));
+    }
+
+    #[test]
+    fn hash2kmer_() {
+        for kmer in 0..cocktail::kmer::get_kmer_space_size(5) {
+            let canonical = cocktail::kmer::canonical(kmer, 5);
+
+            assert_eq!(hash2kmer(canonical >> 1), canonical);
+        }
+    }
+
+    #[test]
+    fn complexity_() {
+        assert_eq!(complexity(b"AAAAA"), 0.0);
+        assert_eq!(complexity(b"ACGT"), 2.0);
+        assert_eq!(complexity(b"acgt"), 2.0);
+        assert_eq!(complexity(b"ATAT"), 1.0);
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn checksum_writer() -> error::Result<()> {
+        use std::io::Write as _;
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("out.csv");
+
+        let mut writer = ChecksumWriter::new(Vec::new(), &path);
+        writer.write_all(b"AAAAA,3\n")?;
+        let sidecar = writer.sidecar_path();
+        drop(writer);
+        assert!(!sidecar.exists());
+
+        let mut writer = ChecksumWriter::new(Vec::new(), &path);
+        writer.write_all(b"AAAAA,3\n")?;
+        writer.finish()?;
+        assert_eq!(
+            std::fs::read_to_string(sidecar)?,
+            "fb9c29296f22a8e6d0b3d2aa702bde7f290f0a5a9f3876b67543275bb3c0ad32  out.csv\n"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "gfa")]
+    #[test]
+    fn gfa_segments_() -> error::Result<()> {
+        let gfa = b"H\tVN:Z:1.0\nS\t1\tACGT\r\nS\t2\t*\nL\t1\t+\t2\t+\t0M\nS\t3\tTTGCA\tLN:i:5\n";
+
+        let segments = gfa_segments(Box::new(&gfa[..])).collect::<error::Result<Vec<Vec<u8>>>>()?;
+
+        assert_eq!(segments, vec![b"ACGT".to_vec(), b"TTGCA".to_vec()]);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sam")]
+    #[test]
+    fn sam_sequences_() -> error::Result<()> {
+        let sam = b"@HD\tVN:1.6\n@SQ\tSN:ref\tLN:10\nr1\t0\tref\t1\t60\t4M\t*\t0\t0\tACGT\tIIII\r\nr2\t4\t*\t0\t0\t*\t*\t0\t0\tTTGCA\t*\nr3\t256\tref\t1\t60\t*\t*\t0\t0\t*\t*\nr4\t256\tref\t1\t60\t4M\t*\t0\t0\tCCCC\t*\nr5\t2048\tref\t1\t60\t4M\t*\t0\t0\tGGGG\t*\n";
+
+        let sequences =
+            sam_sequences(Box::new(&sam[..])).collect::<error::Result<Vec<Vec<u8>>>>()?;
+
+        assert_eq!(sequences, vec![b"ACGT".to_vec(), b"TTGCA".to_vec()]);
+
+        let malformed = b"@HD\tVN:1.6\nr1\t0\tref\n";
+        assert!(sam_sequences(Box::new(&malformed[..]))
+            .collect::<error::Result<Vec<Vec<u8>>>>()
+            .is_err());
+
+        let malformed = b"r1\tzero\tref\t1\t60\t4M\t*\t0\t0\tACGT\tIIII\n";
+        assert!(sam_sequences(Box::new(&malformed[..]))
+            .collect::<error::Result<Vec<Vec<u8>>>>()
+            .is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn refill_buffer_() -> error::Result<()> {
+        let fasta: &[u8] = b">0\nACGTACGTACGT\n>1\nAC\n>2\nGGGG\n";
+        let mut reader = noodles::fasta::Reader::new(fasta);
+        let mut sequences = Vec::new();
+
+        assert!(refill_buffer(&mut reader, &mut sequences, 2)?);
+        assert_eq!(sequences, vec![b"ACGTACGTACGT".to_vec(), b"AC".to_vec()]);
+        let first = sequences[0].as_ptr();
+
+        assert!(!refill_buffer(&mut reader, &mut sequences, 2)?);
+        assert_eq!(sequences, vec![b"GGGG".to_vec()]);
+        assert_eq!(sequences[0].as_ptr(), first);
+
+        assert!(!refill_buffer(&mut reader, &mut sequences, 2)?);
+        assert!(sequences.is_empty());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn refill_buffer_capacity() -> error::Result<()> {
+        let long = "A".repeat(4 * MAX_RETAINED_CAPACITY);
+        let fasta = format!(">0\n{}\n>1\nAC\n>2\nGGGG\n>3\nTT\n", long);
+        let mut reader = noodles::fasta::Reader::new(fasta.as_bytes());
+        let mut sequences = Vec::with_capacity(100);
+
+        assert!(refill_buffer(&mut reader, &mut sequences, 2)?);
+        assert_eq!(sequences[0].len(), long.len());
+        assert_eq!(sequences[1], b"AC");
+        assert!(sequences.capacity() <= 4);
+
+        assert!(refill_buffer(&mut reader, &mut sequences, 2)?);
+        assert_eq!(sequences, vec![b"GGGG".to_vec(), b"TT".to_vec()]);
+        assert!(sequences[0].capacity() <= MAX_RETAINED_CAPACITY);
+
+        Ok(())
     }
 }
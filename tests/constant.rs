@@ -2,22 +2,22 @@
 pub const SEED: [u8; 32] = [42; 32]; // WARNING if you change seed you change truth result
 
 pub const TRUTH_PCON: &[u8] = &[
-    5, 1, 31, 139, 8, 0, 0, 0, 0, 0, 4, 255, 13, 143, 73, 174, 219, 48, 20, 192, 142, 80, 36, 182,
-    244, 230, 73, 146, 21, 55, 191, 232, 253, 143, 247, 189, 228, 130, 0, 105, 85, 138, 206, 149,
-    139, 59, 160, 105, 15, 169, 132, 242, 237, 15, 12, 40, 19, 16, 205, 225, 68, 76, 233, 129, 78,
-    93, 164, 20, 196, 41, 175, 2, 96, 7, 5, 0, 127, 253, 189, 151, 88, 221, 174, 84, 218, 129, 78,
-    32, 59, 94, 62, 232, 18, 22, 235, 131, 135, 4, 156, 104, 50, 221, 82, 197, 129, 99, 200, 96,
-    148, 138, 228, 96, 105, 101, 173, 221, 161, 44, 181, 250, 120, 177, 219, 200, 192, 9, 224, 239,
-    171, 98, 118, 104, 172, 6, 107, 109, 58, 156, 129, 212, 185, 23, 214, 113, 248, 248, 103, 164,
-    166, 195, 125, 247, 5, 142, 142, 37, 53, 240, 212, 55, 105, 235, 163, 153, 75, 6, 157, 87, 177,
-    139, 189, 127, 116, 178, 98, 6, 31, 29, 180, 48, 173, 140, 243, 79, 241, 158, 126, 44, 0, 34,
-    96, 193, 31, 164, 103, 246, 138, 245, 205, 210, 230, 35, 133, 83, 178, 125, 138, 22, 131, 106,
-    172, 88, 77, 37, 92, 66, 198, 218, 163, 169, 217, 186, 185, 153, 0, 106, 226, 243, 39, 95, 107,
-    221, 238, 0, 238, 194, 222, 161, 231, 248, 191, 152, 68, 116, 37, 62, 169, 82, 205, 88, 125,
-    191, 159, 6, 66, 102, 200, 162, 79, 171, 142, 209, 122, 204, 61, 115, 246, 58, 211, 205, 163,
-    237, 125, 14, 4, 129, 10, 9, 178, 137, 252, 185, 32, 56, 195, 98, 86, 177, 77, 54, 176, 175,
-    230, 94, 214, 217, 31, 97, 187, 48, 46, 20, 101, 197, 131, 227, 100, 193, 99, 169, 49, 235, 71,
-    245, 19, 3, 200, 204, 184, 65, 30, 154, 230, 204, 191, 9, 69, 77, 106, 0, 2, 0, 0,
+    5, 1, 1, 31, 139, 8, 0, 0, 0, 0, 0, 4, 255, 13, 143, 73, 174, 219, 48, 20, 192, 142, 80, 36,
+    182, 244, 230, 73, 146, 21, 55, 191, 232, 253, 143, 247, 189, 228, 130, 0, 105, 85, 138, 206,
+    149, 139, 59, 160, 105, 15, 169, 132, 242, 237, 15, 12, 40, 19, 16, 205, 225, 68, 76, 233, 129,
+    78, 93, 164, 20, 196, 41, 175, 2, 96, 7, 5, 0, 127, 253, 189, 151, 88, 221, 174, 84, 218, 129,
+    78, 32, 59, 94, 62, 232, 18, 22, 235, 131, 135, 4, 156, 104, 50, 221, 82, 197, 129, 99, 200,
+    96, 148, 138, 228, 96, 105, 101, 173, 221, 161, 44, 181, 250, 120, 177, 219, 200, 192, 9, 224,
+    239, 171, 98, 118, 104, 172, 6, 107, 109, 58, 156, 129, 212, 185, 23, 214, 113, 248, 248, 103,
+    164, 166, 195, 125, 247, 5, 142, 142, 37, 53, 240, 212, 55, 105, 235, 163, 153, 75, 6, 157, 87,
+    177, 139, 189, 127, 116, 178, 98, 6, 31, 29, 180, 48, 173, 140, 243, 79, 241, 158, 126, 44, 0,
+    34, 96, 193, 31, 164, 103, 246, 138, 245, 205, 210, 230, 35, 133, 83, 178, 125, 138, 22, 131,
+    106, 172, 88, 77, 37, 92, 66, 198, 218, 163, 169, 217, 186, 185, 153, 0, 106, 226, 243, 39, 95,
+    107, 221, 238, 0, 238, 194, 222, 161, 231, 248, 191, 152, 68, 116, 37, 62, 169, 82, 205, 88,
+    125, 191, 159, 6, 66, 102, 200, 162, 79, 171, 142, 209, 122, 204, 61, 115, 246, 58, 211, 205,
+    163, 237, 125, 14, 4, 129, 10, 9, 178, 137, 252, 185, 32, 56, 195, 98, 86, 177, 77, 54, 176,
+    175, 230, 94, 214, 217, 31, 97, 187, 48, 46, 20, 101, 197, 131, 227, 100, 193, 99, 169, 49,
+    235, 71, 245, 19, 3, 200, 204, 184, 65, 30, 154, 230, 204, 191, 9, 69, 77, 106, 0, 2, 0, 0,
 ];
 
 pub const TRUTH_CSV: &[u8] = b"AACAT,36
@@ -32,6 +32,57 @@ mod count {
         Ok(())
     }
 
+    #[cfg(feature = "progress")]
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn progress_bar_not_tty() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args(["count", "-k", "5", "--progress-bar"])
+            .write_stdin(buffer);
+
+        let assert = cmd.assert();
+
+        assert
+            .success()
+            .stderr(b"" as &[u8])
+            .stdout(constant::TRUTH_PCON);
+        Ok(())
+    }
+
+    #[cfg(feature = "parallel")]
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn single_thread() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        // TRUTH_PCON is the output of sequential build
+        for threads in ["1", "4"] {
+            for extra in [None, Some("--local-count")] {
+                let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+                cmd.args(["-t", threads, "count", "-k", "5"])
+                    .args(extra)
+                    .write_stdin(buffer.clone());
+
+                cmd.assert()
+                    .success()
+                    .stderr(b"" as &[u8])
+                    .stdout(constant::TRUTH_PCON);
+            }
+        }
+
+        Ok(())
+    }
+
     #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
     #[test]
     fn from_file_to_stdout() -> anyhow::Result<()> {
@@ -167,4 +218,877 @@ mod count {
 
         Ok(())
     }
+
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn count_to_many_solid() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let mut low_temp = tempfile::NamedTempFile::new()?;
+        let mut high_temp = tempfile::NamedTempFile::new()?;
+        let mut single_temp = tempfile::NamedTempFile::new()?;
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "-s",
+            &format!("{}", low_temp.path().display()),
+            "-s",
+            &format!("{}:2", high_temp.path().display()),
+        ])
+        .write_stdin(buffer.clone());
+
+        cmd.assert()
+            .success()
+            .stderr(b"" as &[u8])
+            .stdout(b"" as &[u8]);
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "-a",
+            "2",
+            "-s",
+            &format!("{}", single_temp.path().display()),
+        ])
+        .write_stdin(buffer);
+
+        cmd.assert()
+            .success()
+            .stderr(b"" as &[u8])
+            .stdout(b"" as &[u8]);
+
+        let mut low = vec![];
+        low_temp.read_to_end(&mut low)?;
+        assert_eq!(low, constant::TRUTH_SOLID);
+
+        let mut high = vec![];
+        high_temp.read_to_end(&mut high)?;
+        let mut single = vec![];
+        single_temp.read_to_end(&mut single)?;
+        assert_eq!(high, single);
+        assert_ne!(high, low);
+
+        Ok(())
+    }
+
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn resume() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let middle = buffer
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == b'>')
+            .nth(50)
+            .map(|(i, _)| i)
+            .unwrap();
+        let (first, second) = buffer.split_at(middle);
+
+        let first_temp = tempfile::NamedTempFile::new()?;
+        let first_path = first_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "-p",
+            &format!("{}", first_path.display()),
+        ])
+        .write_stdin(first.to_vec());
+        cmd.assert().success();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--resume",
+            &format!("{}", first_path.display()),
+        ])
+        .write_stdin(second.to_vec());
+
+        cmd.assert()
+            .success()
+            .stderr(b"" as &[u8])
+            .stdout(constant::TRUTH_PCON);
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "7",
+            "--resume",
+            &format!("{}", first_path.display()),
+        ])
+        .write_stdin(second.to_vec());
+
+        cmd.assert().failure();
+
+        Ok(())
+    }
+
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn auto_abundance() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let pcon_temp = tempfile::NamedTempFile::new()?;
+        let pcon_path = pcon_temp.path();
+        let mut csv_temp = tempfile::NamedTempFile::new()?;
+        let csv_path = csv_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--auto-abundance",
+            "first-minimum",
+            "-p",
+            &format!("{}", pcon_path.display()),
+            "-c",
+            &format!("{}", csv_path.display()),
+        ])
+        .write_stdin(buffer);
+
+        let assert = cmd.assert();
+
+        assert.success().stderr(b"" as &[u8]).stdout(b"" as &[u8]);
+
+        let counter = pcon::counter::Counter::<u8>::from_stream(std::fs::File::open(pcon_path)?)?;
+        let spectrum =
+            pcon::spectrum::Spectrum::from_count_bounded(counter.raw(), u8::MAX as usize);
+        let threshold = spectrum
+            .get_threshold(pcon::spectrum::ThresholdMethod::FirstMinimum, 0.0)
+            .unwrap_or(0);
+
+        let mut output = String::new();
+        csv_temp.read_to_string(&mut output)?;
+
+        assert_eq!(
+            output.lines().count() as u64,
+            counter.distinct_kmers_above(threshold)
+        );
+        for line in output.lines() {
+            let count: u8 = line.split(',').nth(1).unwrap().parse()?;
+            assert!(count > threshold);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn auto_threshold_solid() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let temp_dir = tempfile::tempdir()?;
+        let pcon_path = temp_dir.path().join("out.pcon");
+        let solid_path = temp_dir.path().join("out.solid");
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--auto-threshold",
+            "first-minimum",
+            "-p",
+            &format!("{}", pcon_path.display()),
+            "-s",
+            &format!("{}", solid_path.display()),
+        ])
+        .write_stdin(buffer);
+
+        cmd.assert().success();
+
+        let counter = pcon::counter::Counter::<u8>::from_stream(std::fs::File::open(&pcon_path)?)?;
+        let threshold =
+            pcon::spectrum::Spectrum::from_count_bounded(counter.raw(), u8::MAX as usize)
+                .get_threshold(pcon::spectrum::ThresholdMethod::FirstMinimum, 0.0)
+                .unwrap_or(0);
+        let expected = pcon::solid::Solid::from_count(counter.k(), counter.raw(), threshold);
+
+        let solid = pcon::solid::Solid::from_path(&solid_path)?;
+        assert_eq!(solid.k(), 5);
+        assert_eq!(
+            solid.iter_solid().collect::<Vec<u64>>(),
+            expected.iter_solid().collect::<Vec<u64>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_input() -> anyhow::Result<()> {
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args(["count", "-k", "5"])
+            .write_stdin(b"ACGTACGT\n>seq\nACGTACGT\n".to_vec());
+
+        let assert = cmd.assert();
+
+        assert.failure().code(1).stdout(b"" as &[u8]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn empty_input() -> anyhow::Result<()> {
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args(["-v", "count", "-k", "5"])
+            .write_stdin(b">header_only\n".to_vec());
+
+        let assert = cmd.assert().success();
+
+        let stderr = String::from_utf8(assert.get_output().stderr.to_vec())?;
+        assert!(stderr.contains("WARN"));
+        assert!(stderr.contains("No kmer count in input"));
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args(["count", "-k", "5", "--fail-on-empty"])
+            .write_stdin(b">header_only\n".to_vec());
+
+        let assert = cmd.assert().failure().code(1).stdout(b"" as &[u8]);
+
+        let stderr = String::from_utf8(assert.get_output().stderr.to_vec())?;
+        assert!(stderr.contains("No kmer count in input"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn region_bed() -> anyhow::Result<()> {
+        let bed_temp = tempfile::NamedTempFile::new()?;
+        let bed_path = bed_temp.path();
+        std::fs::write(
+            bed_path,
+            b"# two regions\nchr1\t0\t5\nchr1\t20\t25\nchr2\t0\t10\n",
+        )?;
+
+        let mut output_temp = tempfile::NamedTempFile::new()?;
+        let output_path = output_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--region-bed",
+            &format!("{}", bed_path.display()),
+            "-c",
+            &format!("{}", output_path.display()),
+        ])
+        .write_stdin(b">chr1\nAAAAAAAAAACCCCCCCCCCGGGGGGGGGG\n".to_vec());
+
+        let assert = cmd.assert();
+
+        assert.success().stderr(b"" as &[u8]).stdout(b"" as &[u8]);
+
+        let mut output = String::new();
+        output_temp.read_to_string(&mut output)?;
+        let lines = output.lines().collect::<Vec<&str>>();
+
+        // only AAAAA and GGGGG start in regions, each is present 5 times
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            assert_eq!(line.split(',').nth(1), Some("5"));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn region_bed_min_record_len() -> anyhow::Result<()> {
+        let bed_temp = tempfile::NamedTempFile::new()?;
+        let bed_path = bed_temp.path();
+        std::fs::write(bed_path, b"chr1\t0\t5\nchr2\t0\t5\n")?;
+
+        let mut output_temp = tempfile::NamedTempFile::new()?;
+        let output_path = output_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--region-bed",
+            &format!("{}", bed_path.display()),
+            "--min-record-len",
+            "20",
+            "-c",
+            &format!("{}", output_path.display()),
+        ])
+        .write_stdin(b">chr1\nAAAAAAAAAACCCCCCCCCCGGGGGGGGGG\n>chr2\nCCCCCCCCCC\n".to_vec());
+
+        let assert = cmd.assert();
+
+        assert.success().stderr(b"" as &[u8]).stdout(b"" as &[u8]);
+
+        let mut output = String::new();
+        output_temp.read_to_string(&mut output)?;
+
+        // region of chr1 is shorter than 20 but chr1 isn't, chr2 is skip
+        assert_eq!(output, "AAAAA,5\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn group_by_header() -> anyhow::Result<()> {
+        let mut group_temp = tempfile::NamedTempFile::new()?;
+        let group_path = group_temp.path();
+
+        let mut output_temp = tempfile::NamedTempFile::new()?;
+        let output_path = output_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--group-by-header",
+            &format!("{}", group_path.display()),
+            "-c",
+            &format!("{}", output_path.display()),
+        ])
+        .write_stdin(
+            b">taxA read1\nAAAAAAAAAA\n>taxB read2\nCCCCCCCC\n>taxA read3\nAAAAAAA\n".to_vec(),
+        );
+
+        let assert = cmd.assert();
+
+        assert.success().stderr(b"" as &[u8]).stdout(b"" as &[u8]);
+
+        // each group is count independently
+        let mut groups = String::new();
+        group_temp.read_to_string(&mut groups)?;
+        assert_eq!(groups, "group,distinct,total\ntaxA,1,9\ntaxB,1,4\n");
+
+        // global count is the sum of groups
+        let mut output = String::new();
+        output_temp.read_to_string(&mut output)?;
+        let mut lines = output.lines().collect::<Vec<&str>>();
+        lines.sort_unstable();
+        assert_eq!(lines, vec!["AAAAA,9", "CCCCC,4"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sort_count_desc() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let mut output_temp = tempfile::NamedTempFile::new()?;
+        let output_path = output_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "-c",
+            &format!("{}", output_path.display()),
+            "--sort",
+            "count-desc",
+        ])
+        .write_stdin(buffer);
+
+        cmd.assert().success();
+
+        let mut output = String::new();
+        output_temp.read_to_string(&mut output)?;
+
+        let counts = output
+            .lines()
+            .map(|line| line.split(',').nth(1).unwrap().parse::<u64>())
+            .collect::<Result<Vec<u64>, _>>()?;
+
+        assert!(!counts.is_empty());
+        assert!(counts.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn append_csv() -> anyhow::Result<()> {
+        let directory = tempfile::tempdir()?;
+        let csv_path = directory.path().join("count.csv");
+
+        let run = || {
+            let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+            cmd.args([
+                "count",
+                "-k",
+                "5",
+                "-c",
+                &format!("{}", csv_path.display()),
+                "--append-csv",
+            ])
+            .write_stdin(b">1\nAAAAAAA\n>2\nGGGGGGGGG\n".to_vec());
+
+            cmd.assert().success().stderr(b"" as &[u8]);
+        };
+
+        // missing file is a fresh write
+        run();
+        assert_eq!(std::fs::read_to_string(&csv_path)?, "AAAAA,3\nCCCCC,5\n");
+
+        std::fs::write(&csv_path, b"AAAAA,2\nACGTA,1\n")?;
+        run();
+        assert_eq!(
+            std::fs::read_to_string(&csv_path)?,
+            "AAAAA,5\nACGTA,1\nCCCCC,5\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn append_csv_conflict() {
+        for option in [
+            ["--columns", "kmer,count"],
+            ["--sort", "kmer"],
+            ["--whitelist", "whitelist.fasta"],
+        ] {
+            let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+            cmd.args(["count", "-k", "5", "-c", "count.csv", "--append-csv"])
+                .args(option)
+                .write_stdin(b">1\nAAAAAAA\n".to_vec());
+
+            cmd.assert().failure().code(2).stdout(b"" as &[u8]);
+        }
+    }
+
+    #[test]
+    fn whitelist_without_csv() {
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "-p",
+            "count.pcon",
+            "--whitelist",
+            "whitelist.fasta",
+        ])
+        .write_stdin(b">1\nAAAAAAA\n".to_vec());
+
+        cmd.assert().failure().code(2).stdout(b"" as &[u8]);
+    }
+
+    #[test]
+    fn dry_run() -> anyhow::Result<()> {
+        let directory = tempfile::tempdir()?;
+        let pcon_path = directory.path().join("count.pcon");
+        let csv_path = directory.path().join("count.csv");
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "-i",
+            &format!("{}", directory.path().join("missing.fasta").display()),
+            "-p",
+            &format!("{}", pcon_path.display()),
+            "-c",
+            &format!("{}", csv_path.display()),
+            "--dry-run",
+        ]);
+
+        let assert = cmd.assert().success();
+        let stdout = String::from_utf8(assert.get_output().stdout.to_vec())?;
+
+        assert_eq!(
+            stdout,
+            format!(
+                "count 5-mer need {} bytes\nwrite csv in {}\nwrite pcon in {}\n",
+                512 * std::mem::size_of::<pcon::CountType>(),
+                csv_path.display(),
+                pcon_path.display()
+            )
+        );
+        assert!(!pcon_path.exists());
+        assert!(!csv_path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn every_nth() -> anyhow::Result<()> {
+        let mut output_temp = tempfile::NamedTempFile::new()?;
+        let output_path = output_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--every-nth",
+            "2",
+            "-c",
+            &format!("{}", output_path.display()),
+        ])
+        .write_stdin(b">1\nAAAAAAA\n>2\nCCCCCCC\n>3\nAAAAAAA\n>4\nCCCCCCC\n>5\nAAAAAAA\n".to_vec());
+
+        cmd.assert().success().stderr(b"" as &[u8]);
+
+        // records 1, 3 and 5 are count, each contains 3 AAAAA
+        let mut output = String::new();
+        output_temp.read_to_string(&mut output)?;
+        assert_eq!(output, "AAAAA,9\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn count_type() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let pcon_path = temp_dir.path().join("u16.pcon");
+        let csv_path = temp_dir.path().join("u16.csv");
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--count-type",
+            "u16",
+            "-p",
+            &format!("{}", pcon_path.display()),
+            "-c",
+            &format!("{}", csv_path.display()),
+        ])
+        .write_stdin(">1\nAAAAAAA\n".repeat(300));
+
+        cmd.assert().success();
+
+        // 300 records each contains 3 AAAAA, count overflow u8
+        assert_eq!(std::fs::read_to_string(&csv_path)?, "AAAAA,900\n");
+
+        let counter = pcon::counter::Counter::<u16>::from_stream(std::fs::File::open(&pcon_path)?)?;
+        assert_eq!(counter.k(), 5);
+        assert_eq!(counter.get(0), 900);
+
+        assert!(
+            pcon::counter::Counter::<u8>::from_stream(std::fs::File::open(&pcon_path)?).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn count_type_abundance() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let pcon_path = temp_dir.path().join("u16.pcon");
+        let csv_path = temp_dir.path().join("u16.csv");
+        let dump_path = temp_dir.path().join("dump.csv");
+
+        // 300 records each contains 3 AAAAA and one record contains one CCCCG
+        let mut input = ">1\nAAAAAAA\n".repeat(300);
+        input.push_str(">2\nCCCCG\n");
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--count-type",
+            "u16",
+            "-a",
+            "300",
+            "-p",
+            &format!("{}", pcon_path.display()),
+            "-c",
+            &format!("{}", csv_path.display()),
+        ])
+        .write_stdin(input);
+
+        cmd.assert().success();
+
+        assert_eq!(std::fs::read_to_string(&csv_path)?, "AAAAA,900\n");
+
+        // dump read count width in header whatever width choose at compile time
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "dump",
+            "-i",
+            &format!("{}", pcon_path.display()),
+            "-a",
+            "300",
+            "-c",
+            &format!("{}", dump_path.display()),
+        ]);
+
+        cmd.assert().success();
+
+        assert_eq!(std::fs::read_to_string(&dump_path)?, "AAAAA,900\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn raw() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let raw_path = temp_dir.path().join("count.raw");
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--count-type",
+            "u16",
+            "--raw",
+            &format!("{}", raw_path.display()),
+        ])
+        .write_stdin(">1\nAAAAAAA\n".repeat(300));
+
+        cmd.assert().success();
+
+        // file is read as a whole, like a mmap consumer would see it
+        let raw = std::fs::read(&raw_path)?;
+        assert_eq!(raw.len(), pcon::serialize::RAW_HEADER_LEN + 512 * 2);
+        assert_eq!(&raw[..2], &[5, 2]);
+
+        let count = |hash: usize| {
+            let offset = pcon::serialize::RAW_HEADER_LEN + hash * 2;
+            u16::from_le_bytes([raw[offset], raw[offset + 1]])
+        };
+        assert_eq!(count(0), 900);
+        assert_eq!(count((cocktail::kmer::seq2bit(b"CCCCC") >> 1) as usize), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn auto_widen() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let input_path = temp_dir.path().join("input.fasta");
+        let csv_path = temp_dir.path().join("output.csv");
+
+        // 300 records each contains 3 AAAAA, count saturate u8
+        std::fs::write(&input_path, ">1\nAAAAAAA\n".repeat(300))?;
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--count-type",
+            "u8",
+            "--auto-widen",
+            "-i",
+            &format!("{}", input_path.display()),
+            "-c",
+            &format!("{}", csv_path.display()),
+        ]);
+
+        cmd.assert().success();
+
+        assert_eq!(std::fs::read_to_string(&csv_path)?, "AAAAA,900\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn mkdir() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let output_path = temp_dir.path().join("nonexistent_dir").join("out.pcon");
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "-p",
+            &format!("{}", output_path.display()),
+        ])
+        .write_stdin(">1\nACGTACGT\n");
+
+        let assert = cmd.assert().failure();
+        let stderr = String::from_utf8(assert.get_output().stderr.to_vec())?;
+        assert!(stderr.contains(&format!(
+            "Parent directory of output {} doesn't exist",
+            output_path.display()
+        )));
+        assert!(!output_path.exists());
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--mkdir",
+            "-p",
+            &format!("{}", output_path.display()),
+        ])
+        .write_stdin(">1\nACGTACGT\n");
+
+        cmd.assert().success();
+        assert!(output_path.exists());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn checksum() -> anyhow::Result<()> {
+        use sha2::Digest as _;
+
+        let temp_dir = tempfile::tempdir()?;
+        let pcon_path = temp_dir.path().join("out.pcon");
+        let csv_path = temp_dir.path().join("out.csv");
+        let report_path = temp_dir.path().join("report.txt");
+        let group_path = temp_dir.path().join("group.csv");
+
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--checksum",
+            "-p",
+            &format!("{}", pcon_path.display()),
+            "-c",
+            &format!("{}", csv_path.display()),
+            "--report",
+            &format!("{}", report_path.display()),
+            "--group-by-header",
+            &format!("{}", group_path.display()),
+        ])
+        .write_stdin(buffer);
+
+        cmd.assert().success();
+
+        for (path, name) in [
+            (pcon_path, "out.pcon"),
+            (csv_path, "out.csv"),
+            (report_path, "report.txt"),
+            (group_path, "group.csv"),
+        ] {
+            let digest = sha2::Sha256::digest(std::fs::read(&path)?);
+            let mut sidecar = path.into_os_string();
+            sidecar.push(".sha256");
+
+            assert_eq!(
+                std::fs::read_to_string(sidecar)?,
+                format!("{:x}  {}\n", digest, name)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn whitelist() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let whitelist_temp = tempfile::NamedTempFile::new()?;
+        let whitelist_path = whitelist_temp.path();
+        std::fs::write(whitelist_path, b">first\nACGTA\n>second\nTTGCA\n")?;
+
+        let mut output_temp = tempfile::NamedTempFile::new()?;
+        let output_path = output_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "--whitelist",
+            &format!("{}", whitelist_path.display()),
+            "-c",
+            &format!("{}", output_path.display()),
+        ])
+        .write_stdin(buffer);
+
+        let assert = cmd.assert();
+
+        assert.success().stderr(b"" as &[u8]).stdout(b"" as &[u8]);
+
+        let mut output = String::new();
+        output_temp.read_to_string(&mut output)?;
+
+        let kmers = output
+            .lines()
+            .map(|line| line.split(',').next().unwrap_or("").to_string())
+            .collect::<Vec<String>>();
+        let expected = [b"ACGTA", b"TTGCA"]
+            .iter()
+            .map(|kmer| {
+                cocktail::kmer::kmer2seq(
+                    cocktail::kmer::canonical(cocktail::kmer::seq2bit(*kmer), 5),
+                    5,
+                )
+            })
+            .collect::<Vec<String>>();
+
+        assert_eq!(kmers, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn log_json() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args(["--log-json", "-vv", "count", "-k", "5"])
+            .write_stdin(buffer);
+
+        let assert = cmd.assert().success();
+        let stderr = String::from_utf8(assert.get_output().stderr.clone())?;
+
+        assert!(stderr.lines().count() > 0);
+        for line in stderr.lines() {
+            let value: serde_json::Value = serde_json::from_str(line)?;
+
+            assert!(value["timestamp"].is_number());
+            assert_eq!(value["level"], "INFO");
+            assert!(value["message"].is_string());
+        }
+
+        Ok(())
+    }
 }
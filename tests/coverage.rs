@@ -0,0 +1,63 @@
+/* std use */
+
+/* 3rd party use */
+
+/* local use */
+
+mod coverage {
+    #[test]
+    fn bedgraph() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let pcon_path = temp_dir.path().join("count.pcon");
+        let reference_path = temp_dir.path().join("reference.fasta");
+
+        std::fs::write(
+            &reference_path,
+            ">contig1\nACGTACGTACGTACGTACGT\n>short\nACG\n",
+        )?;
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "count",
+            "-k",
+            "5",
+            "-i",
+            &format!("{}", reference_path.display()),
+            "-p",
+            &format!("{}", pcon_path.display()),
+        ]);
+        cmd.assert().success();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "coverage",
+            "-r",
+            &format!("{}", reference_path.display()),
+            "-i",
+            &format!("{}", pcon_path.display()),
+            "-w",
+            "5",
+        ]);
+
+        let assert = cmd.assert();
+        let output = String::from_utf8(assert.success().get_output().stdout.to_vec())?;
+        let lines = output.lines().collect::<Vec<&str>>();
+
+        // contig1 has 16 kmer, 4 windows, short contig is shorter than k
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0].split('\t').take(3).collect::<Vec<&str>>(),
+            ["contig1", "0", "5"]
+        );
+        assert_eq!(
+            lines[3].split('\t').take(3).collect::<Vec<&str>>(),
+            ["contig1", "15", "16"]
+        );
+        for line in lines {
+            let mean: f64 = line.split('\t').nth(3).unwrap().parse()?;
+            assert!(mean > 0.0);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+/* std use */
+
+/* 3rd party use */
+
+/* local use */
+
+mod diff {
+    fn count(path: &std::path::Path, input: String) {
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args(["count", "-k", "5", "-p", &format!("{}", path.display())])
+            .write_stdin(input);
+        cmd.assert().success();
+    }
+
+    #[test]
+    fn report() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let a_path = temp_dir.path().join("a.pcon");
+        let b_path = temp_dir.path().join("b.pcon");
+
+        count(&a_path, format!(">repeat\n{}\n", "AACAT".repeat(40)));
+        count(
+            &b_path,
+            format!(">repeat\n{}\n>other\nGGGGG\n", "AACAT".repeat(41)),
+        );
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "diff",
+            "-r",
+            "-i",
+            &format!("{}", a_path.display()),
+            &format!("{}", b_path.display()),
+        ]);
+
+        let assert = cmd.assert();
+
+        assert.success().stdout(
+            &b"kmers gained: 1
+kmers lost: 0
+kmers changed: 5
+total absolute count difference: 6
+"[..],
+        );
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "diff",
+            "-i",
+            &format!("{}", a_path.display()),
+            &format!("{}", b_path.display()),
+        ]);
+
+        let assert = cmd.assert();
+
+        let output = String::from_utf8(assert.success().get_output().stdout.to_vec())?;
+        let lines = output.lines().collect::<Vec<&str>>();
+
+        assert_eq!(lines[0], "kmer,first,second");
+        assert_eq!(lines.len(), 7);
+        assert!(lines.contains(&"CCCCC,0,1"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn kmer_size_not_match() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let a_path = temp_dir.path().join("a.pcon");
+        let b_path = temp_dir.path().join("b.pcon");
+
+        count(&a_path, format!(">repeat\n{}\n", "AACAT".repeat(40)));
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args(["count", "-k", "7", "-p", &format!("{}", b_path.display())])
+            .write_stdin(format!(">repeat\n{}\n", "AACAT".repeat(40)));
+        cmd.assert().success();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "diff",
+            "-r",
+            "-i",
+            &format!("{}", a_path.display()),
+            &format!("{}", b_path.display()),
+        ]);
+
+        cmd.assert().failure();
+
+        Ok(())
+    }
+}
@@ -27,6 +27,105 @@ mod dump {
         Ok(())
     }
 
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn stats_only() -> anyhow::Result<()> {
+        let mut input_temp = tempfile::NamedTempFile::new()?;
+        input_temp.write_all(constant::TRUTH_PCON)?;
+        let input_path = input_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "dump",
+            "--stats-only",
+            "-a",
+            "35",
+            "-i",
+            &format!("{}", input_path.display()),
+        ]);
+
+        let assert = cmd.assert();
+        let output = String::from_utf8(assert.success().get_output().stdout.to_vec())?;
+
+        let counter = pcon::counter::Counter::<u8>::from_stream(constant::TRUTH_PCON)?;
+        let lines = output.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[0], format!("k: {}", counter.k()));
+        assert!(lines.contains(&format!("total kmers: {}", counter.total_kmers()).as_str()));
+        assert!(lines.contains(&format!("distinct kmers: {}", counter.distinct_kmers()).as_str()));
+        assert!(!output.contains(','));
+
+        Ok(())
+    }
+
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn top() -> anyhow::Result<()> {
+        for (args, expected) in [
+            (vec!["-a", "0"], "TGCAA,46\nCTACC,44\nCGCGG,43\n"),
+            (vec!["-a", "43"], "TGCAA,46\nCTACC,44\n"),
+            (
+                vec!["-a", "0", "--min-complexity", "1.0"],
+                "TGCAA,46\nCTACC,44\nTATTC,43\n",
+            ),
+        ] {
+            let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+            cmd.args(["dump", "--top", "3"])
+                .args(args)
+                .write_stdin(constant::TRUTH_PCON);
+
+            cmd.assert()
+                .success()
+                .stderr(b"" as &[u8])
+                .stdout(expected.as_bytes().to_vec());
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn fasta_count() -> anyhow::Result<()> {
+        let mut output_temp = tempfile::NamedTempFile::new()?;
+        let output_path = output_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "dump",
+            "-a",
+            "35",
+            "--fasta-count",
+            &format!("{}", output_path.display()),
+        ])
+        .write_stdin(constant::TRUTH_PCON);
+
+        let assert = cmd.assert();
+
+        assert.success().stderr(b"" as &[u8]).stdout(b"" as &[u8]);
+
+        let mut output = String::new();
+        output_temp.read_to_string(&mut output)?;
+        let lines = output.lines().collect::<Vec<&str>>();
+
+        // each record count match csv count of same kmer
+        let records = lines
+            .chunks(2)
+            .map(|record| {
+                format!(
+                    "{},{}",
+                    record[1],
+                    record[0].strip_prefix(">count_").unwrap_or("")
+                )
+            })
+            .collect::<Vec<String>>();
+        let csv = String::from_utf8(constant::TRUTH_CSV.to_vec())?;
+        let expected = csv.lines().collect::<Vec<&str>>();
+
+        assert!(!records.is_empty());
+        assert_eq!(records, expected);
+
+        Ok(())
+    }
+
     #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
     #[test]
     fn from_file_to_stdout() -> std::io::Result<()> {
@@ -140,4 +239,129 @@ mod dump {
 
         Ok(())
     }
+
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn merge() -> anyhow::Result<()> {
+        let mut first_temp = tempfile::NamedTempFile::new()?;
+        first_temp.write_all(constant::TRUTH_PCON)?;
+        let mut second_temp = tempfile::NamedTempFile::new()?;
+        second_temp.write_all(constant::TRUTH_PCON)?;
+
+        let mut output_temp = tempfile::NamedTempFile::new()?;
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "dump",
+            "-a",
+            "0",
+            "-i",
+            &format!("{}", first_temp.path().display()),
+            "-i",
+            &format!("{}", second_temp.path().display()),
+            "-p",
+            &format!("{}", output_temp.path().display()),
+        ]);
+
+        let assert = cmd.assert();
+
+        assert.success().stderr(b"" as &[u8]);
+
+        let mut output = vec![];
+        output_temp.read_to_end(&mut output)?;
+
+        let single = pcon::counter::Counter::<u8>::from_stream(constant::TRUTH_PCON)?;
+        let merged = pcon::counter::Counter::<u8>::from_stream(&output[..])?;
+        for (one, two) in single.raw().iter().zip(merged.raw()) {
+            assert_eq!(one.saturating_mul(2), *two);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn merge_width_not_match() -> anyhow::Result<()> {
+        let mut first_temp = tempfile::NamedTempFile::new()?;
+        first_temp.write_all(constant::TRUTH_PCON)?;
+
+        let second_temp = tempfile::NamedTempFile::new()?;
+        pcon::counter::Counter::<u16>::new(5)
+            .serialize()
+            .pcon(std::fs::File::create(second_temp.path())?)?;
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "dump",
+            "-a",
+            "0",
+            "-i",
+            &format!("{}", first_temp.path().display()),
+            "-i",
+            &format!("{}", second_temp.path().display()),
+        ]);
+
+        let assert = cmd.assert();
+
+        let stderr = String::from_utf8(assert.get_output().stderr.to_vec())?;
+        assert!(stderr.contains(&format!("{}", second_temp.path().display())));
+        assert!(stderr.contains("expected 1 bytes found 2 bytes"));
+
+        assert.failure().code(1).stdout(b"" as &[u8]);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "kff")]
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn to_kff() -> std::io::Result<()> {
+        let mut output_temp = tempfile::NamedTempFile::new()?;
+        let output_path = output_temp.path();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "dump",
+            "-a",
+            "0",
+            "--kff",
+            &format!("{}", output_path.display()),
+        ])
+        .write_stdin(constant::TRUTH_PCON);
+
+        let assert = cmd.assert();
+
+        assert.success().stderr(b"" as &[u8]).stdout(b"" as &[u8]);
+
+        let mut output = vec![];
+        output_temp.read_to_end(&mut output)?;
+        assert_eq!(&output[..3], b"KFF");
+        assert_eq!(&output[output.len() - 3..], b"KFF");
+
+        Ok(())
+    }
+
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn from_serialize_to_path() -> anyhow::Result<()> {
+        let counter = pcon::counter::Counter::<u8>::from_stream(constant::TRUTH_PCON)?;
+
+        let directory = tempfile::tempdir()?;
+        for name in ["count.pcon", "count.pcon.gz", "count.pcon.zst"] {
+            let path = directory.path().join(name);
+            counter
+                .clone()
+                .serialize_to_path(&path, 0, pcon::cli::DumpType::Pcon)?;
+
+            let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+            cmd.args(["dump", "-a", "35", "-i", &format!("{}", path.display())]);
+
+            cmd.assert()
+                .success()
+                .stderr(b"" as &[u8])
+                .stdout(constant::TRUTH_CSV);
+        }
+
+        Ok(())
+    }
 }
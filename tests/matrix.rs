@@ -0,0 +1,68 @@
+/* std use */
+use std::io::Write as _;
+
+/* 3rd party use */
+
+/* local use */
+pub mod constant;
+
+mod matrix {
+    /* local use */
+    use super::*;
+
+    #[cfg(not(any(feature = "count_u16", feature = "count_u32", feature = "count_u64")))]
+    #[test]
+    fn two_samples() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let a_path = temp_dir.path().join("a.pcon");
+        let b_path = temp_dir.path().join("b.pcon");
+
+        std::fs::File::create(&a_path)?.write_all(constant::TRUTH_PCON)?;
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args(["count", "-k", "5", "-p", &format!("{}", b_path.display())])
+            .write_stdin(format!(">repeat\n{}\n", "AACAT".repeat(40)));
+        cmd.assert().success();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "matrix",
+            "-a",
+            "35",
+            "-i",
+            &format!("{}", a_path.display()),
+            &format!("{}", b_path.display()),
+        ]);
+
+        let assert = cmd.assert();
+
+        let output = String::from_utf8(assert.get_output().stdout.to_vec())?;
+        let lines = output.lines().collect::<Vec<&str>>();
+
+        assert_eq!(lines[0], "kmer\ta\tb");
+        for line in &lines[1..] {
+            assert_eq!(line.split('\t').count(), 3);
+        }
+
+        let truth = String::from_utf8(constant::TRUTH_CSV.to_vec())?;
+        assert_eq!(
+            lines[1..]
+                .iter()
+                .filter(|line| line.split('\t').nth(1) == Some("1"))
+                .count(),
+            truth.lines().count()
+        );
+        assert_eq!(
+            lines[1..]
+                .iter()
+                .filter(|line| line.split('\t').nth(2) == Some("1"))
+                .count(),
+            5
+        );
+        assert!(lines.contains(&"AACAT\t1\t1"));
+
+        assert.success().stderr(b"" as &[u8]);
+
+        Ok(())
+    }
+}
@@ -118,4 +118,49 @@ mod minicount {
 
         Ok(())
     }
+
+    #[test]
+    fn same_seed_same_output() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder()
+            .sequence_len(150)
+            .sequence(biotest::values::Nucleotides::DnaUpper)
+            .build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let run = |seed: &str| -> Vec<String> {
+            let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+            cmd.args([
+                "--seed",
+                seed,
+                "mini-count",
+                "-k",
+                "7",
+                "-m",
+                "3",
+                "-a",
+                "20",
+            ])
+            .write_stdin(buffer.clone());
+
+            let assert = cmd.assert().success();
+
+            let mut output: Vec<String> = String::from_utf8_lossy(&assert.get_output().stdout)
+                .lines()
+                .map(str::to_string)
+                .collect();
+            output.sort_unstable();
+
+            output
+        };
+
+        let first = run("42");
+        assert!(!first.is_empty());
+        assert_eq!(first, run("42"));
+        assert_ne!(first, run("7"));
+
+        Ok(())
+    }
 }
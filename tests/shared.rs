@@ -0,0 +1,84 @@
+/* std use */
+
+/* 3rd party use */
+
+/* local use */
+
+mod shared {
+    fn count(path: &std::path::Path, input: String) {
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args(["count", "-k", "5", "-p", &format!("{}", path.display())])
+            .write_stdin(input);
+        cmd.assert().success();
+    }
+
+    fn shared(a_path: &std::path::Path, b_path: &std::path::Path) -> assert_cmd::assert::Assert {
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "shared",
+            "-i",
+            &format!("{}", a_path.display()),
+            &format!("{}", b_path.display()),
+        ]);
+
+        cmd.assert()
+    }
+
+    #[test]
+    fn identical() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let a_path = temp_dir.path().join("a.pcon");
+        let b_path = temp_dir.path().join("b.pcon");
+
+        count(&a_path, format!(">repeat\n{}\n", "AACAT".repeat(40)));
+        count(&b_path, format!(">repeat\n{}\n", "AACAT".repeat(40)));
+
+        shared(&a_path, &b_path).success().stdout(
+            &b"intersection: 5
+union: 5
+jaccard: 1.000000
+intersection density: 0.009766
+"[..],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn disjoint() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let a_path = temp_dir.path().join("a.pcon");
+        let b_path = temp_dir.path().join("b.pcon");
+
+        count(&a_path, format!(">poly_a\n{}\n", "A".repeat(20)));
+        count(&b_path, format!(">poly_c\n{}\n", "C".repeat(20)));
+
+        shared(&a_path, &b_path).success().stdout(
+            &b"intersection: 0
+union: 2
+jaccard: 0.000000
+intersection density: 0.000000
+"[..],
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn kmer_size_not_match() -> anyhow::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let a_path = temp_dir.path().join("a.pcon");
+        let b_path = temp_dir.path().join("b.pcon");
+
+        count(&a_path, format!(">repeat\n{}\n", "AACAT".repeat(40)));
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args(["count", "-k", "7", "-p", &format!("{}", b_path.display())])
+            .write_stdin(format!(">repeat\n{}\n", "AACAT".repeat(40)));
+        cmd.assert().success();
+
+        shared(&a_path, &b_path).failure();
+
+        Ok(())
+    }
+}
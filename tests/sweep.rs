@@ -0,0 +1,119 @@
+/* std use */
+
+/* 3rd party use */
+use biotest::Format as _;
+
+/* local use */
+pub mod constant;
+
+mod sweep {
+    /* local use */
+    use super::*;
+
+    #[test]
+    fn one_row_by_k() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let input_temp = tempfile::NamedTempFile::new()?;
+        let input_path = input_temp.path();
+
+        generator.create(input_path, &mut rng, 100)?;
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "sweep",
+            "-k",
+            "3,5,7",
+            "-i",
+            &format!("{}", input_path.display()),
+        ]);
+
+        let assert = cmd.assert();
+
+        let output = String::from_utf8(assert.get_output().stdout.to_vec())?;
+        let lines = output.lines().collect::<Vec<&str>>();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "k,threshold,distinct,solid");
+        for (line, k) in lines[1..].iter().zip(["3", "5", "7"]) {
+            assert_eq!(line.split(',').count(), 4);
+            assert_eq!(line.split(',').next(), Some(k));
+        }
+
+        assert.success().stderr(b"" as &[u8]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_distinct() -> anyhow::Result<()> {
+        let mut rng = biotest::rand();
+        let generator = biotest::Fasta::builder().sequence_len(150).build()?;
+
+        let mut buffer = Vec::new();
+        generator.records(&mut buffer, &mut rng, 100)?;
+
+        let input_temp = tempfile::NamedTempFile::new()?;
+        let input_path = input_temp.path();
+        std::fs::write(input_path, &buffer)?;
+
+        // k upper than DENSE_MAX_K is count by sparse backend, compare to canonical kmer of each valid window
+        let mut truth = std::collections::HashSet::new();
+        let mut reader = noodles::fasta::Reader::new(&buffer[..]);
+        for record in reader.records() {
+            let record = record?;
+            for window in record.sequence().as_ref().windows(17) {
+                if window.iter().all(|nuc| b"ACGTacgt".contains(nuc)) {
+                    truth.insert(cocktail::kmer::canonical(
+                        cocktail::kmer::seq2bit(window),
+                        17,
+                    ));
+                }
+            }
+        }
+
+        let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+        cmd.args([
+            "sweep",
+            "-k",
+            "17",
+            "-i",
+            &format!("{}", input_path.display()),
+        ]);
+
+        let assert = cmd.assert();
+
+        let output = String::from_utf8(assert.get_output().stdout.to_vec())?;
+        let lines = output.lines().collect::<Vec<&str>>();
+
+        assert_eq!(lines.len(), 2);
+        let row = lines[1].split(',').collect::<Vec<&str>>();
+        assert_eq!(row[0], "17");
+        assert_eq!(row[2], format!("{}", truth.len()));
+
+        assert.success().stderr(b"" as &[u8]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn kmer_size_out_of_range() -> anyhow::Result<()> {
+        let mut input_temp = tempfile::NamedTempFile::new()?;
+        std::io::Write::write_all(&mut input_temp, b">test\nACGTACGT\n")?;
+        let input_path = input_temp.path();
+
+        for k in ["0", "33", "3,0"] {
+            let mut cmd = assert_cmd::Command::cargo_bin("pcon").unwrap();
+            cmd.args(["sweep", "-k", k, "-i", &format!("{}", input_path.display())]);
+
+            let assert = cmd.assert().failure();
+
+            let stderr = String::from_utf8(assert.get_output().stderr.to_vec())?;
+            assert!(stderr.contains("--kmer-size"), "{}", stderr);
+            assert!(stderr.contains("1..=32"), "{}", stderr);
+        }
+
+        Ok(())
+    }
+}